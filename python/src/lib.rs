@@ -0,0 +1,266 @@
+//! Python bindings, built with `maturin build` in this directory
+//!
+//! ```python
+//! import crate_report
+//!
+//! report = crate_report.generate_report("path/to/crate")
+//! diff = report.diff(crate_report.load_report("baseline.csv"))
+//! for filename, change in diff.changes.items():
+//!     print(filename, change.status, change.after.unwraps if change.after else 0)
+//! ```
+
+use std::{
+    collections::{
+        BTreeMap,
+        BTreeSet,
+    },
+    path::PathBuf,
+};
+
+use pyo3::{
+    exceptions::PyValueError,
+    prelude::*,
+};
+
+/// The metrics of a file, or the totals of a report
+#[pyclass(frozen, get_all, skip_from_py_object, module = "crate_report")]
+#[derive(Clone)]
+struct CodeStats {
+    allowed_lints: isize,
+    clippy_lints: isize,
+    deprecated_uses: isize,
+    extern_callbacks: isize,
+    ffi_string_conversions: isize,
+    leak_prone_calls: isize,
+    manual_allocations: isize,
+    process_exits: isize,
+    recursive_fns: isize,
+    repr_types: isize,
+    static_mut_items: isize,
+    thread_unsafe_calls: isize,
+    total_fns: isize,
+    total_lines: isize,
+    total_statements: isize,
+    trivial_expects: isize,
+    unsafe_fns: isize,
+    unsafe_statements: isize,
+    unwraps: isize,
+    plugin_metrics: BTreeMap<String, isize>,
+}
+
+impl From<crate_report::CodeStats> for CodeStats {
+    fn from(stats: crate_report::CodeStats) -> Self {
+        let crate_report::CodeStats {
+            allowed_lints,
+            clippy_lints,
+            deprecated_uses,
+            extern_callbacks,
+            ffi_string_conversions,
+            leak_prone_calls,
+            manual_allocations,
+            process_exits,
+            recursive_fns,
+            repr_types,
+            static_mut_items,
+            thread_unsafe_calls,
+            total_fns,
+            total_lines,
+            total_statements,
+            trivial_expects,
+            unsafe_fns,
+            unsafe_statements,
+            unwraps,
+            plugin_metrics,
+        } = stats;
+        Self {
+            allowed_lints,
+            clippy_lints,
+            deprecated_uses,
+            extern_callbacks,
+            ffi_string_conversions,
+            leak_prone_calls,
+            manual_allocations,
+            process_exits,
+            recursive_fns,
+            repr_types,
+            static_mut_items,
+            thread_unsafe_calls,
+            total_fns,
+            total_lines,
+            total_statements,
+            trivial_expects,
+            unsafe_fns,
+            unsafe_statements,
+            unwraps,
+            plugin_metrics,
+        }
+    }
+}
+
+#[pymethods]
+impl CodeStats {
+    /// Every metric by its csv column name, e.g. for a pandas row
+    fn to_dict(&self) -> BTreeMap<String, isize> {
+        let mut metrics = BTreeMap::from([
+            ("allowed_lints".to_string(), self.allowed_lints),
+            ("clippy_lints".to_string(), self.clippy_lints),
+            ("deprecated_uses".to_string(), self.deprecated_uses),
+            ("extern_callbacks".to_string(), self.extern_callbacks),
+            (
+                "ffi_string_conversions".to_string(),
+                self.ffi_string_conversions,
+            ),
+            ("leak_prone_calls".to_string(), self.leak_prone_calls),
+            ("manual_allocations".to_string(), self.manual_allocations),
+            ("process_exits".to_string(), self.process_exits),
+            ("recursive_fns".to_string(), self.recursive_fns),
+            ("repr_types".to_string(), self.repr_types),
+            ("static_mut_items".to_string(), self.static_mut_items),
+            ("thread_unsafe_calls".to_string(), self.thread_unsafe_calls),
+            ("total_fns".to_string(), self.total_fns),
+            ("total_lines".to_string(), self.total_lines),
+            ("total_statements".to_string(), self.total_statements),
+            ("trivial_expects".to_string(), self.trivial_expects),
+            ("unsafe_fns".to_string(), self.unsafe_fns),
+            ("unsafe_statements".to_string(), self.unsafe_statements),
+            ("unwraps".to_string(), self.unwraps),
+        ]);
+        metrics.extend(self.plugin_metrics.clone());
+        metrics
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "CodeStats(unsafe_fns={}, unsafe_statements={}, static_mut_items={}, unwraps={}, total_fns={}, total_lines={})",
+            self.unsafe_fns,
+            self.unsafe_statements,
+            self.static_mut_items,
+            self.unwraps,
+            self.total_fns,
+            self.total_lines
+        )
+    }
+}
+
+/// The totals and per-file metrics of a crate
+#[pyclass(frozen, module = "crate_report")]
+struct Report(crate_report::Report);
+
+#[pymethods]
+impl Report {
+    #[getter]
+    fn total(&self) -> CodeStats {
+        self.0.total.clone().into()
+    }
+
+    /// Metrics by path relative to the crate root
+    #[getter]
+    fn files(&self) -> BTreeMap<String, CodeStats> {
+        self.0
+            .files
+            .iter()
+            .map(|(filename, stats)| (filename.clone(), stats.clone().into()))
+            .collect()
+    }
+
+    /// Compare against a baseline report
+    fn diff(&self, baseline: &Report) -> DiffReport {
+        self.0.diff(&baseline.0).into()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Report(files={}, total={})",
+            self.0.files.len(),
+            self.total().__repr__()
+        )
+    }
+}
+
+/// How a file's metrics changed, `before` is `None` for added files and
+/// `after` for removed ones
+#[pyclass(frozen, get_all, skip_from_py_object, module = "crate_report")]
+#[derive(Clone)]
+struct Diff {
+    /// `"added"`, `"changed"` or `"removed"`
+    status: &'static str,
+    before: Option<CodeStats>,
+    after: Option<CodeStats>,
+}
+
+impl From<crate_report::Diff> for Diff {
+    fn from(diff: crate_report::Diff) -> Self {
+        let (status, before, after) = match diff {
+            crate_report::Diff::Added(after) => ("added", None, Some(after)),
+            crate_report::Diff::Changed(change) => {
+                ("changed", Some(change.before), Some(change.after))
+            }
+            crate_report::Diff::Removed(before) => ("removed", Some(before), None),
+        };
+        Self {
+            status,
+            before: before.map(Into::into),
+            after: after.map(Into::into),
+        }
+    }
+}
+
+#[pymethods]
+impl Diff {
+    fn __repr__(&self) -> String {
+        format!("Diff(status={:?})", self.status)
+    }
+}
+
+/// A report compared against a baseline
+#[pyclass(frozen, get_all, module = "crate_report")]
+struct DiffReport {
+    before_total: CodeStats,
+    after_total: CodeStats,
+    /// only the files which were added, removed or had a metric change
+    changes: BTreeMap<String, Diff>,
+    /// metrics missing from the baseline, their before values are copied from after
+    unknown_metrics: BTreeSet<String>,
+}
+
+impl From<crate_report::DiffReport> for DiffReport {
+    fn from(diff: crate_report::DiffReport) -> Self {
+        Self {
+            before_total: diff.before_total.into(),
+            after_total: diff.after_total.into(),
+            changes: diff
+                .changes
+                .into_iter()
+                .map(|(filename, change)| (filename, change.into()))
+                .collect(),
+            unknown_metrics: diff.unknown_metrics,
+        }
+    }
+}
+
+/// Analyze every Rust file under a crate root, like running `crate-report` there
+#[pyfunction]
+fn generate_report(py: Python<'_>, path: PathBuf) -> Report {
+    Report(py.detach(|| crate_report::analyze_crate(path)))
+}
+
+/// Read a report written with `--format csv` or `--format json`, from a file
+/// or an http(s) URL
+#[pyfunction]
+fn load_report(py: Python<'_>, path: &str) -> PyResult<Report> {
+    py.detach(|| crate_report::load_report(path))
+        .map(Report)
+        .map_err(|err| PyValueError::new_err(format!("failed to load report '{path}': {err}")))
+}
+
+#[pymodule]
+#[pyo3(name = "crate_report")]
+fn crate_report_python(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<CodeStats>()?;
+    module.add_class::<Diff>()?;
+    module.add_class::<DiffReport>()?;
+    module.add_class::<Report>()?;
+    module.add_function(wrap_pyfunction!(generate_report, module)?)?;
+    module.add_function(wrap_pyfunction!(load_report, module)?)?;
+    Ok(())
+}
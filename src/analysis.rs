@@ -0,0 +1,767 @@
+use std::{
+    collections::{
+        BTreeMap,
+        BTreeSet,
+    },
+    iter::Sum,
+    path::Path,
+};
+
+use syn::{
+    Abi,
+    Attribute,
+    Expr,
+    ExprCall,
+    ExprCast,
+    ExprMethodCall,
+    ExprUnsafe,
+    Ident,
+    ImplItemFn,
+    ItemEnum,
+    ItemFn,
+    ItemStatic,
+    ItemStruct,
+    ItemUnion,
+    ItemUse,
+    Lit,
+    Local,
+    Pat,
+    StaticMutability,
+    Stmt,
+    Token,
+    Type,
+    UseTree,
+    punctuated::Punctuated,
+    spanned::Spanned,
+    visit::Visit,
+};
+
+use crate::plugin;
+
+/// The metrics of a file, or the sum of several
+///
+/// Run `crate-report explain` for what each one counts. Metrics missing when
+/// deserializing are zero, reports track which ones a baseline didn't have.
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct CodeStats {
+    /// lints named in `#[allow(...)]` attributes, `allow(dead_code, unused)`
+    /// counts two
+    pub allowed_lints: isize,
+    /// only populated when `--clippy-json` is given
+    pub clippy_lints: isize,
+    pub deprecated_uses: isize,
+    pub extern_callbacks: isize,
+    pub ffi_string_conversions: isize,
+    pub leak_prone_calls: isize,
+    pub manual_allocations: isize,
+    pub process_exits: isize,
+    pub recursive_fns: isize,
+    pub repr_types: isize,
+    pub static_mut_items: isize,
+    pub thread_unsafe_calls: isize,
+    pub total_fns: isize,
+    pub total_lines: isize,
+    pub total_statements: isize,
+    pub trivial_expects: isize,
+    pub unsafe_fns: isize,
+    pub unsafe_statements: isize,
+    pub unwraps: isize,
+    /// counts from registered [`plugin::MetricVisitor`]s, by metric name
+    #[serde(flatten)]
+    pub plugin_metrics: BTreeMap<String, isize>,
+}
+
+/// The kind of construct a [`Site`] points at
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum SiteKind {
+    Allocation,
+    /// a use of a `#[deprecated]` item, named after it, only found by the CLI
+    #[cfg_attr(not(feature = "cli"), allow(dead_code))]
+    DeprecatedUse,
+    ExternClosure,
+    ExternFn,
+    FfiString,
+    GlobalAllocator,
+    LeakProne,
+    ProcessExit,
+    /// a fn which calls itself, `exposed` when it's unsafe or exported over FFI
+    Recursive {
+        exposed: bool,
+    },
+    Repr(Repr),
+    StaticMut,
+    ThreadUnsafe,
+    TrivialExpect,
+    UnsafeBlock,
+    UnsafeFn,
+    Unwrap,
+}
+
+/// The layout a `#[repr]` type is given which makes it part of the ABI
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Repr {
+    C,
+    Transparent,
+    Packed,
+}
+
+impl Repr {
+    /// The repr a type is counted under, `packed` wins over `C` since it's the
+    /// one with the unaligned fields
+    fn of(attrs: &[Attribute]) -> Option<Self> {
+        attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("repr"))
+            .filter_map(|attr| attr.meta.require_list().ok())
+            .flat_map(|list| list.tokens.clone())
+            .filter_map(|token| match token {
+                proc_macro2::TokenTree::Ident(ident) => match ident.to_string().as_str() {
+                    "C" => Some(Repr::C),
+                    "transparent" => Some(Repr::Transparent),
+                    "packed" => Some(Repr::Packed),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .max()
+    }
+}
+
+impl SiteKind {
+    /// Unsafe code and unwraps, which pre-commit rejects, rather than FFI
+    /// boundaries, allocations, leaks, exits, thread-unsafe calls, recursion,
+    /// deprecated uses and trivial expects which are only counted and listed
+    /// for review
+    #[cfg_attr(not(feature = "cli"), allow(dead_code))]
+    pub(crate) fn is_hazard(self) -> bool {
+        matches!(
+            self,
+            SiteKind::StaticMut | SiteKind::UnsafeBlock | SiteKind::UnsafeFn | SiteKind::Unwrap
+        )
+    }
+}
+
+/// The location of a single counted construct within a file, only reported by
+/// the CLI
+#[derive(Clone, Debug)]
+#[cfg_attr(not(feature = "cli"), allow(dead_code))]
+pub(crate) struct Site {
+    pub(crate) kind: SiteKind,
+    /// the item name for fns and statics, otherwise the enclosing fn (if any)
+    pub(crate) name: String,
+    pub(crate) line: usize,
+    pub(crate) end_line: usize,
+}
+
+impl Sum for CodeStats {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.reduce(
+            |mut acc,
+             CodeStats {
+                 allowed_lints,
+                 clippy_lints,
+                 deprecated_uses,
+                 extern_callbacks,
+                 ffi_string_conversions,
+                 leak_prone_calls,
+                 manual_allocations,
+                 process_exits,
+                 recursive_fns,
+                 repr_types,
+                 static_mut_items,
+                 thread_unsafe_calls,
+                 total_fns,
+                 total_lines,
+                 total_statements,
+                 trivial_expects,
+                 unsafe_fns,
+                 unsafe_statements,
+                 unwraps,
+                 plugin_metrics,
+             }| {
+                acc.allowed_lints += allowed_lints;
+                acc.clippy_lints += clippy_lints;
+                acc.deprecated_uses += deprecated_uses;
+                acc.extern_callbacks += extern_callbacks;
+                acc.ffi_string_conversions += ffi_string_conversions;
+                acc.leak_prone_calls += leak_prone_calls;
+                acc.manual_allocations += manual_allocations;
+                acc.process_exits += process_exits;
+                acc.recursive_fns += recursive_fns;
+                acc.repr_types += repr_types;
+                acc.static_mut_items += static_mut_items;
+                acc.thread_unsafe_calls += thread_unsafe_calls;
+                acc.total_fns += total_fns;
+                acc.total_lines += total_lines;
+                acc.total_statements += total_statements;
+                acc.trivial_expects += trivial_expects;
+                acc.unsafe_fns += unsafe_fns;
+                acc.unsafe_statements += unsafe_statements;
+                acc.unwraps += unwraps;
+                for (metric, count) in plugin_metrics {
+                    *acc.plugin_metrics.entry(metric).or_default() += count;
+                }
+                acc
+            },
+        )
+        .unwrap_or_default()
+    }
+}
+
+struct CodeAnalyzer<'a> {
+    stats: &'a mut CodeStats,
+    sites: &'a mut Vec<Site>,
+    current_fn: Option<String>,
+    /// bindings in the current fn known to hold a `CString` or `CStr`
+    c_strings: BTreeSet<String>,
+    /// the module each name imported with `use` comes from, e.g. `alloc` for
+    /// `use std::alloc::{alloc, dealloc}`
+    imports: BTreeMap<String, String>,
+    /// the fn or method being visited, to spot it calling itself
+    recursion: Option<Recursion>,
+}
+
+struct Recursion {
+    name: String,
+    /// a method can call itself as `self.name()` or `Self::name()`, a free fn
+    /// only by its bare name
+    method: bool,
+    exposed: bool,
+    calls_itself: bool,
+}
+
+const ALLOC_FNS: [&str; 4] = ["alloc", "alloc_zeroed", "dealloc", "realloc"];
+
+/// libc fns which touch the environment or return pointers into static buffers
+const THREAD_UNSAFE_LIBC_FNS: [&str; 7] = [
+    "gmtime",
+    "localtime",
+    "putenv",
+    "setenv",
+    "strerror",
+    "strtok",
+    "unsetenv",
+];
+
+/// Collect the names a `use` tree imports with the module they're from
+fn imported_names(tree: &UseTree, module: Option<&Ident>, names: &mut BTreeMap<String, String>) {
+    match tree {
+        UseTree::Path(path) => imported_names(&path.tree, Some(&path.ident), names),
+        UseTree::Name(name) => {
+            if let Some(module) = module {
+                names.insert(name.ident.to_string(), module.to_string());
+            }
+        }
+        UseTree::Group(group) => {
+            for tree in &group.items {
+                imported_names(tree, module, names);
+            }
+        }
+        UseTree::Rename(_) | UseTree::Glob(_) => {}
+    }
+}
+
+/// Whether a type is `CString` or `CStr`, or a reference to one
+fn is_c_string_type(ty: &Type) -> bool {
+    match ty {
+        Type::Path(ty) => ty
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "CString" || segment.ident == "CStr"),
+        Type::Reference(ty) => is_c_string_type(&ty.elem),
+        Type::Paren(ty) => is_c_string_type(&ty.elem),
+        _ => false,
+    }
+}
+
+/// Whether a call is `CString::new` or `CStr::from_ptr`, by its last two path
+/// segments
+fn is_c_string_conversion(call: &ExprCall) -> bool {
+    let Expr::Path(func) = &*call.func else {
+        return false;
+    };
+    let segments: Vec<String> = func
+        .path
+        .segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect();
+    matches!(
+        segments.as_slice(),
+        [.., ty, f] if (ty == "CString" && f == "new") || (ty == "CStr" && f == "from_ptr")
+    )
+}
+
+/// Whether an `extern` ABI is a foreign one, i.e. anything but `"Rust"`
+fn is_foreign_abi(abi: Option<&Abi>) -> bool {
+    abi.is_some_and(|abi| abi.name.as_ref().is_none_or(|name| name.value() != "Rust"))
+}
+
+/// Whether a type is a fn pointer with a foreign ABI, e.g. `extern "C" fn(i32)`
+fn is_foreign_fn_pointer(ty: &Type) -> bool {
+    match ty {
+        Type::BareFn(ty) => is_foreign_abi(ty.abi.as_ref()),
+        Type::Paren(ty) => is_foreign_fn_pointer(&ty.elem),
+        _ => false,
+    }
+}
+
+/// Whether a fn is exported under its own symbol name, with `#[no_mangle]` or
+/// `#[export_name]`, including their `#[unsafe(...)]` forms
+pub(crate) fn is_exported(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        let path = attr.path();
+        path.is_ident("no_mangle")
+            || path.is_ident("export_name")
+            || (path.is_ident("unsafe")
+                && attr.meta.require_list().is_ok_and(|list| {
+                    list.tokens.clone().into_iter().any(|token| {
+                        matches!(token, proc_macro2::TokenTree::Ident(ident)
+                            if ident == "no_mangle" || ident == "export_name")
+                    })
+                }))
+    })
+}
+
+/// Whether `expect`'s message is a string literal that's empty or a single
+/// word, like `""` or `"failed"`, which says no more than `unwrap` would
+fn is_trivial_message(message: Option<&Expr>) -> bool {
+    match message {
+        Some(Expr::Lit(lit)) => {
+            matches!(&lit.lit, Lit::Str(message) if !message.value().trim().contains(char::is_whitespace))
+        }
+        _ => false,
+    }
+}
+
+/// The lints an `#[allow(...)]` or `#![allow(...)]` attribute allows, like
+/// `dead_code` or `clippy::too_many_lines`, none for any other attribute
+pub(crate) fn allowed_lints(attr: &Attribute) -> Vec<String> {
+    if !attr.path().is_ident("allow") {
+        return Vec::new();
+    }
+    attr.parse_args_with(Punctuated::<syn::Path, Token![,]>::parse_terminated)
+        .map(|lints| {
+            lints
+                .iter()
+                .map(|lint| {
+                    lint.segments
+                        .iter()
+                        .map(|segment| segment.ident.to_string())
+                        .collect::<Vec<_>>()
+                        .join("::")
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn is_closure(expr: &Expr) -> bool {
+    match expr {
+        Expr::Closure(_) => true,
+        Expr::Paren(expr) => is_closure(&expr.expr),
+        _ => false,
+    }
+}
+
+impl CodeAnalyzer<'_> {
+    /// Whether a call allocates or frees memory by hand, or constructs a
+    /// `Layout` to: `std::alloc::alloc(layout)`, `dealloc(ptr, layout)` with
+    /// `dealloc` imported from `std::alloc`, `Layout::new::<T>()`, ...
+    fn is_manual_allocation(&self, call: &ExprCall) -> bool {
+        match self.call_path(call).as_slice() {
+            [.., module, f] if module == "alloc" => ALLOC_FNS.contains(&f.as_str()),
+            [.., ty, _] => ty == "Layout",
+            _ => false,
+        }
+    }
+
+    /// Whether a call defeats RAII: `mem::forget`, `Box::leak`,
+    /// `ManuallyDrop::new` or any `into_raw`, like `Box::into_raw`
+    fn is_leak_prone(&self, call: &ExprCall) -> bool {
+        match self.call_path(call).as_slice() {
+            [.., a, b] => {
+                matches!(
+                    (a.as_str(), b.as_str()),
+                    ("mem", "forget") | ("Box", "leak") | ("ManuallyDrop", "new") | (_, "into_raw")
+                )
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether a call ends the process without unwinding: `process::exit`,
+    /// `process::abort`, or libc's `exit`, `_exit` or `abort`
+    fn is_process_exit(&self, call: &ExprCall) -> bool {
+        match self.call_path(call).as_slice() {
+            [.., module, f] if module == "process" => f == "exit" || f == "abort",
+            [.., module, f] if module == "libc" => matches!(f.as_str(), "exit" | "_exit" | "abort"),
+            _ => false,
+        }
+    }
+
+    /// Whether a call isn't safe while other threads run: `env::set_var` and
+    /// `env::remove_var`, unsafe since the 2024 edition, or a libc fn from
+    /// [`THREAD_UNSAFE_LIBC_FNS`]
+    fn is_thread_unsafe(&self, call: &ExprCall) -> bool {
+        match self.call_path(call).as_slice() {
+            [.., module, f] if module == "env" => f == "set_var" || f == "remove_var",
+            [.., module, f] if module == "libc" => THREAD_UNSAFE_LIBC_FNS.contains(&f.as_str()),
+            _ => false,
+        }
+    }
+
+    /// The path segments of the fn a call calls, with an imported fn's module
+    /// in front, e.g. `["mem", "forget"]` for `forget(x)` after
+    /// `use std::mem::forget`
+    fn call_path(&self, call: &ExprCall) -> Vec<String> {
+        let Expr::Path(func) = &*call.func else {
+            return Vec::new();
+        };
+        let mut segments: Vec<String> = func
+            .path
+            .segments
+            .iter()
+            .map(|segment| segment.ident.to_string())
+            .collect();
+        if let [name] = segments.as_slice()
+            && let Some(module) = self.imports.get(name)
+        {
+            segments.insert(0, module.clone());
+        }
+        segments
+    }
+
+    /// Whether an expression evaluates to a C string, going by what it's built
+    /// from: a conversion, a `c"..."` literal or a known binding, through
+    /// `unwrap`s, `?`s and references
+    fn is_c_string(&self, expr: &Expr) -> bool {
+        match expr {
+            Expr::Call(call) => is_c_string_conversion(call),
+            Expr::Lit(lit) => matches!(lit.lit, Lit::CStr(_)),
+            Expr::Path(path) => path
+                .path
+                .get_ident()
+                .is_some_and(|ident| self.c_strings.contains(&ident.to_string())),
+            Expr::MethodCall(call) => {
+                matches!(call.method.to_string().as_str(), "unwrap" | "expect")
+                    && self.is_c_string(&call.receiver)
+            }
+            Expr::Try(expr) => self.is_c_string(&expr.expr),
+            Expr::Reference(expr) => self.is_c_string(&expr.expr),
+            Expr::Paren(expr) => self.is_c_string(&expr.expr),
+            _ => false,
+        }
+    }
+
+    /// Visit a fn's body with it as the fn recursion is looked for in, then
+    /// count it if it called itself
+    fn visit_recursion_scope(
+        &mut self,
+        recursion: Recursion,
+        ident: &Ident,
+        visit: impl FnOnce(&mut Self),
+    ) {
+        let outer = self.recursion.replace(recursion);
+        visit(self);
+        if let Some(recursion) = std::mem::replace(&mut self.recursion, outer)
+            && recursion.calls_itself
+        {
+            self.stats.recursive_fns += 1;
+            self.push_site(
+                SiteKind::Recursive {
+                    exposed: recursion.exposed,
+                },
+                Some(recursion.name),
+                ident.span(),
+            );
+        }
+    }
+
+    fn push_extern_callback(
+        &mut self,
+        kind: SiteKind,
+        name: Option<String>,
+        span: proc_macro2::Span,
+    ) {
+        self.stats.extern_callbacks += 1;
+        self.push_site(kind, name, span);
+    }
+
+    fn push_repr(&mut self, attrs: &[Attribute], ident: &Ident) {
+        if let Some(repr) = Repr::of(attrs) {
+            self.stats.repr_types += 1;
+            self.push_site(SiteKind::Repr(repr), Some(ident.to_string()), ident.span());
+        }
+    }
+
+    fn push_leak_prone(&mut self, span: proc_macro2::Span) {
+        self.stats.leak_prone_calls += 1;
+        self.push_site(SiteKind::LeakProne, None, span);
+    }
+
+    fn push_ffi_string(&mut self, span: proc_macro2::Span) {
+        self.stats.ffi_string_conversions += 1;
+        self.push_site(SiteKind::FfiString, None, span);
+    }
+
+    fn push_site(&mut self, kind: SiteKind, name: Option<String>, span: proc_macro2::Span) {
+        self.sites.push(Site {
+            kind,
+            name: name.or_else(|| self.current_fn.clone()).unwrap_or_default(),
+            line: span.start().line,
+            end_line: span.end().line,
+        });
+    }
+}
+impl<'a, 'ast> Visit<'ast> for CodeAnalyzer<'a> {
+    fn visit_expr_cast(&mut self, i: &'ast ExprCast) {
+        if is_closure(&i.expr) && is_foreign_fn_pointer(&i.ty) {
+            self.push_extern_callback(SiteKind::ExternClosure, None, i.span());
+        }
+        syn::visit::visit_expr_cast(self, i);
+    }
+
+    fn visit_expr_call(&mut self, i: &'ast ExprCall) {
+        if is_c_string_conversion(i) {
+            self.push_ffi_string(i.func.span());
+        }
+        if self.is_manual_allocation(i) {
+            self.stats.manual_allocations += 1;
+            self.push_site(SiteKind::Allocation, None, i.func.span());
+        }
+        if self.is_leak_prone(i) {
+            self.push_leak_prone(i.func.span());
+        }
+        if self.is_process_exit(i) {
+            self.stats.process_exits += 1;
+            self.push_site(SiteKind::ProcessExit, None, i.func.span());
+        }
+        if self.is_thread_unsafe(i) {
+            self.stats.thread_unsafe_calls += 1;
+            self.push_site(SiteKind::ThreadUnsafe, None, i.func.span());
+        }
+        if let Expr::Path(func) = &*i.func
+            && let Some(recursion) = &mut self.recursion
+        {
+            let segments: Vec<_> = func.path.segments.iter().map(|s| &s.ident).collect();
+            recursion.calls_itself |= match segments.as_slice() {
+                [name] => !recursion.method && **name == recursion.name,
+                [ty, name] => recursion.method && **ty == "Self" && **name == recursion.name,
+                _ => false,
+            };
+        }
+        syn::visit::visit_expr_call(self, i);
+    }
+
+    fn visit_expr_method_call(&mut self, i: &'ast ExprMethodCall) {
+        if i.method == "unwrap" {
+            self.stats.unwraps += 1;
+            self.push_site(SiteKind::Unwrap, None, i.method.span());
+        }
+        if i.method == "expect" && is_trivial_message(i.args.first()) {
+            self.stats.trivial_expects += 1;
+            self.push_site(SiteKind::TrivialExpect, None, i.method.span());
+        }
+        if i.method == "into_raw" {
+            self.push_leak_prone(i.method.span());
+        }
+        if let Some(recursion) = &mut self.recursion
+            && recursion.method
+            && i.method == recursion.name
+            && matches!(&*i.receiver, Expr::Path(receiver) if receiver.path.is_ident("self"))
+        {
+            recursion.calls_itself = true;
+        }
+        if i.method == "to_string_lossy" || (i.method == "as_ptr" && self.is_c_string(&i.receiver))
+        {
+            self.push_ffi_string(i.method.span());
+        }
+        syn::visit::visit_expr_method_call(self, i);
+    }
+
+    fn visit_local(&mut self, i: &'ast Local) {
+        let (pat, ty) = match &i.pat {
+            Pat::Type(pat) => (&*pat.pat, Some(&*pat.ty)),
+            pat => (pat, None),
+        };
+        let init = i.init.as_ref().map(|init| &*init.expr);
+        if ty.is_some_and(is_foreign_fn_pointer) && init.is_some_and(is_closure) {
+            self.push_extern_callback(SiteKind::ExternClosure, None, i.span());
+        }
+        if let Pat::Ident(pat) = pat
+            && (ty.is_some_and(is_c_string_type) || init.is_some_and(|init| self.is_c_string(init)))
+        {
+            self.c_strings.insert(pat.ident.to_string());
+        }
+        syn::visit::visit_local(self, i);
+    }
+
+    fn visit_expr_unsafe(&mut self, i: &'ast ExprUnsafe) {
+        self.stats.unsafe_statements += i.block.stmts.len() as isize;
+        self.push_site(SiteKind::UnsafeBlock, None, i.span());
+        syn::visit::visit_expr_unsafe(self, i);
+    }
+
+    fn visit_item_fn(&mut self, i: &'ast ItemFn) {
+        let name = i.sig.ident.to_string();
+        self.stats.total_fns += 1;
+        if i.sig.unsafety.is_some() {
+            self.stats.unsafe_fns += 1;
+            self.push_site(
+                SiteKind::UnsafeFn,
+                Some(name.clone()),
+                i.sig.ident.span().join(i.block.span()).unwrap_or(i.span()),
+            );
+        }
+
+        if is_foreign_abi(i.sig.abi.as_ref()) {
+            self.push_extern_callback(SiteKind::ExternFn, Some(name.clone()), i.sig.ident.span());
+        }
+
+        let params = i.sig.inputs.iter().filter_map(|input| match input {
+            syn::FnArg::Typed(param) if is_c_string_type(&param.ty) => match &*param.pat {
+                Pat::Ident(pat) => Some(pat.ident.to_string()),
+                _ => None,
+            },
+            _ => None,
+        });
+        let outer_c_strings = std::mem::replace(&mut self.c_strings, params.collect());
+        let recursion = Recursion {
+            name: name.clone(),
+            method: false,
+            exposed: i.sig.unsafety.is_some()
+                || is_foreign_abi(i.sig.abi.as_ref())
+                || is_exported(&i.attrs),
+            calls_itself: false,
+        };
+        let outer_fn = self.current_fn.replace(name);
+        self.visit_recursion_scope(recursion, &i.sig.ident, |this| {
+            syn::visit::visit_item_fn(this, i);
+        });
+        self.current_fn = outer_fn;
+        self.c_strings = outer_c_strings;
+    }
+
+    /// Methods aren't counted as fns, only as extern callbacks and recursion
+    fn visit_impl_item_fn(&mut self, i: &'ast ImplItemFn) {
+        if is_foreign_abi(i.sig.abi.as_ref()) {
+            self.push_extern_callback(
+                SiteKind::ExternFn,
+                Some(i.sig.ident.to_string()),
+                i.sig.ident.span(),
+            );
+        }
+        let recursion = Recursion {
+            name: i.sig.ident.to_string(),
+            method: true,
+            exposed: i.sig.unsafety.is_some()
+                || is_foreign_abi(i.sig.abi.as_ref())
+                || is_exported(&i.attrs),
+            calls_itself: false,
+        };
+        self.visit_recursion_scope(recursion, &i.sig.ident, |this| {
+            syn::visit::visit_impl_item_fn(this, i);
+        });
+    }
+
+    fn visit_item_enum(&mut self, i: &'ast ItemEnum) {
+        self.push_repr(&i.attrs, &i.ident);
+        syn::visit::visit_item_enum(self, i);
+    }
+
+    fn visit_item_struct(&mut self, i: &'ast ItemStruct) {
+        self.push_repr(&i.attrs, &i.ident);
+        syn::visit::visit_item_struct(self, i);
+    }
+
+    fn visit_item_union(&mut self, i: &'ast ItemUnion) {
+        self.push_repr(&i.attrs, &i.ident);
+        syn::visit::visit_item_union(self, i);
+    }
+
+    fn visit_item_use(&mut self, i: &'ast ItemUse) {
+        imported_names(&i.tree, None, &mut self.imports);
+        syn::visit::visit_item_use(self, i);
+    }
+
+    fn visit_item_static(&mut self, i: &'ast ItemStatic) {
+        if i.attrs
+            .iter()
+            .any(|attr| attr.path().is_ident("global_allocator"))
+        {
+            self.stats.manual_allocations += 1;
+            self.push_site(
+                SiteKind::GlobalAllocator,
+                Some(i.ident.to_string()),
+                i.ident.span(),
+            );
+        }
+        if !matches!(i.mutability, StaticMutability::None) {
+            self.stats.static_mut_items += 1;
+            self.push_site(
+                SiteKind::StaticMut,
+                Some(i.ident.to_string()),
+                i.ident.span(),
+            );
+        }
+        syn::visit::visit_item_static(self, i);
+    }
+
+    fn visit_attribute(&mut self, i: &'ast Attribute) {
+        self.stats.allowed_lints += allowed_lints(i).len() as isize;
+        syn::visit::visit_attribute(self, i);
+    }
+
+    fn visit_stmt(&mut self, i: &'ast Stmt) {
+        self.stats.total_statements += 1;
+        syn::visit::visit_stmt(self, i);
+    }
+}
+
+pub(crate) fn analyze_syntax(
+    content: &str,
+    syntax: &syn::File,
+    path: Option<&Path>,
+) -> (CodeStats, Vec<Site>) {
+    let mut stats = CodeStats {
+        total_lines: content.lines().count() as isize,
+        plugin_metrics: plugin::count(
+            syntax,
+            plugin::FileInfo {
+                path,
+                source: content,
+            },
+        ),
+        ..CodeStats::default()
+    };
+    let mut sites = Vec::new();
+
+    let mut visitor = CodeAnalyzer {
+        stats: &mut stats,
+        sites: &mut sites,
+        current_fn: None,
+        c_strings: BTreeSet::new(),
+        imports: BTreeMap::new(),
+        recursion: None,
+    };
+    visitor.visit_file(syntax);
+
+    (stats, sites)
+}
+
+/// Count the metrics in a file's source
+///
+/// This is the whole analysis without the filesystem, git or the CLI, so it
+/// also builds for `wasm32-unknown-unknown` with `default-features = false`.
+///
+/// ```
+/// let stats = crate_report::analyze_source("fn main() { Some(1).unwrap(); }").unwrap();
+/// assert_eq!(stats.unwraps, 1);
+/// ```
+pub fn analyze_source(source: &str) -> syn::Result<CodeStats> {
+    let syntax = syn::parse_file(source)?;
+    Ok(analyze_syntax(source, &syntax, None).0)
+}
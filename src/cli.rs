@@ -0,0 +1,5432 @@
+mod allows;
+mod azure;
+mod bisect;
+mod bitbucket;
+mod blame;
+mod bool_candidates;
+mod candidate_counts;
+mod clippy;
+mod codeowners;
+mod compare;
+mod concurrency;
+mod config;
+mod context_candidates;
+mod coverage;
+mod deprecated;
+mod diff_formats;
+mod doc_coverage;
+mod drop_candidates;
+mod explain;
+mod export;
+mod geiger;
+mod get_candidates;
+mod git;
+mod gitea;
+mod github;
+mod history;
+mod html;
+mod init;
+mod iterator_candidates;
+mod leaderboard;
+mod libc_calls;
+mod links;
+mod merge;
+mod metadata;
+mod migration;
+mod notify;
+mod once_candidates;
+mod packages;
+mod panic_sites;
+mod patterns;
+mod precommit;
+mod question_mark;
+mod reachability;
+mod ref_candidates;
+mod risk;
+mod safe_candidates;
+mod severity;
+mod sonar;
+mod string_candidates;
+mod teamcity;
+mod timings;
+mod todo;
+mod trend;
+mod untested;
+mod unwrap_contexts;
+mod vec_candidates;
+
+use std::{
+    cmp,
+    collections::{
+        BTreeMap,
+        BTreeSet,
+    },
+    io::IsTerminal,
+    iter::Iterator,
+    ops::ControlFlow,
+    path::{
+        Path,
+        PathBuf,
+    },
+    sync::OnceLock,
+};
+
+use clap::CommandFactory;
+use clap::Parser;
+use colored::{
+    Color,
+    ColoredString,
+    Colorize,
+};
+use walkdir::WalkDir;
+
+use crate::{
+    analysis::{
+        CodeStats,
+        Repr,
+        Site,
+        SiteKind,
+        analyze_syntax,
+    },
+    plugin,
+};
+
+#[derive(Parser)]
+#[command(name = "crate-report")]
+#[command(about = "Analyze unsafe code usage in Rust crates")]
+struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[arg(help = "Root directory of the crate to analyze", default_value = ".")]
+    crate_root: String,
+
+    #[arg(
+        long,
+        help = "Baseline CSV or JSON file, `-` for stdin, or http(s) URL to compare against, repeat to compare against several (the first is used for detailed changes)"
+    )]
+    baseline: Vec<String>,
+
+    #[arg(
+        long,
+        value_name = "HEADER",
+        help = "Header sent when fetching a baseline URL, e.g. \"Authorization: Bearer $TOKEN\""
+    )]
+    baseline_header: Option<String>,
+
+    #[arg(
+        long,
+        help = "Match baseline file paths up with the analyzed files regardless of case, so baselines from case-insensitive filesystems diff cleanly"
+    )]
+    ignore_path_case: bool,
+
+    #[arg(
+        long,
+        help = "Reproducible output for committing and --check: no colors or dates, stable ordering"
+    )]
+    deterministic: bool,
+
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value_t = ColorMode::Auto,
+        help = "When to use colors, auto disables them when NO_COLOR is set or not writing to a terminal"
+    )]
+    color: ColorMode,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Config file (defaults to crate-report.toml in the crate root, if present)"
+    )]
+    config: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "METRIC=WARNING,DANGER",
+        value_parser = config::Threshold::parse_arg,
+        help = "Color a metric yellow above WARNING and red from DANGER, overriding the config (unsafe_fns is a percentage)"
+    )]
+    threshold: Vec<(String, config::Threshold)>,
+
+    #[arg(
+        long,
+        value_name = "WIDTH",
+        help = "Truncate filenames wider than this in the middle, overriding the config"
+    )]
+    max_path_width: Option<usize>,
+
+    #[arg(
+        long,
+        help = "Only print the totals, and the change in them when given a baseline, without the per-file table"
+    )]
+    summary: bool,
+
+    #[arg(
+        long,
+        help = "List files whose metrics didn't change in the diff against a baseline too, marked unchanged"
+    )]
+    show_unchanged: bool,
+
+    #[arg(
+        long,
+        help = "Show files as changed in diffs when only their total fns, statements or lines changed"
+    )]
+    report_all_metrics: bool,
+
+    #[arg(
+        long,
+        help = "Roll files whose diff is only moved code (within a file, a renamed file, or between files with --base-rev) into one note"
+    )]
+    suppress_churn: bool,
+
+    #[arg(
+        long,
+        value_name = "URL",
+        help = "Link filenames in html, markdown and pr-comment reports to this repository (defaults to the origin remote)"
+    )]
+    repo_url: Option<String>,
+
+    #[arg(
+        long = "ref",
+        value_name = "REF",
+        help = "The branch, tag or commit filenames link to (defaults to the checked out commit, unless --deterministic)"
+    )]
+    git_ref: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "REV",
+        help = "The git revision the baseline was generated from, e.g. origin/main, so pr-comment reports can list each new unsafe fn and block in changed files (added files are always listed)"
+    )]
+    base_rev: Option<String>,
+
+    #[arg(
+        long,
+        help = "Post the azure-pr-comment report to the pipeline's pull request, updating the previous run's comment, authenticating with AZURE_DEVOPS_PAT or SYSTEM_ACCESSTOKEN"
+    )]
+    post_azure_comment: bool,
+
+    #[arg(
+        long,
+        help = "Post the pr-comment report to a Gitea or Forgejo pull request, updating the previous run's comment (see the README for the environment variables it reads)"
+    )]
+    post_gitea_comment: bool,
+
+    #[arg(
+        long,
+        help = "Create a GitHub check run, failing on a regression or a file over a danger threshold, annotating new unsafe code and unwraps (needs GITHUB_TOKEN with checks: write)"
+    )]
+    github_check: bool,
+
+    #[arg(
+        long,
+        value_name = "URL",
+        help = "Post a summary (totals, their change against the baseline and the worst regressions) to a Slack or Discord incoming webhook"
+    )]
+    notify_webhook: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "URL",
+        help = "Where the full report is published, linked from --notify-webhook summaries"
+    )]
+    report_url: Option<String>,
+
+    #[arg(
+        long,
+        short,
+        global = true,
+        action = clap::ArgAction::Count,
+        help = "Log to stderr which files are analyzed or skipped and why, and baseline details (-vv for per-file timings)"
+    )]
+    verbose: u8,
+
+    #[arg(
+        long,
+        short,
+        global = true,
+        value_name = "N",
+        help = "Number of threads to analyze files with (defaults to the number of CPUs)"
+    )]
+    jobs: Option<std::num::NonZeroUsize>,
+
+    #[arg(
+        long,
+        help = "Skip parsing files without unsafe code, unwraps, static mut items or calls of fns they define, estimating their fn and statement counts"
+    )]
+    prefilter: bool,
+
+    #[arg(
+        long,
+        num_args = 1..,
+        value_name = "FILE",
+        help = "Analyze exactly these files instead of walking the crate root"
+    )]
+    files: Vec<String>,
+
+    #[arg(
+        long,
+        value_name = "LIST",
+        help = "Analyze exactly the files listed one per line in LIST (`-` for stdin) instead of walking the crate root"
+    )]
+    files_from: Option<String>,
+
+    #[arg(
+        long,
+        short,
+        value_name = "SPEC",
+        help = "Only analyze this workspace package, repeat for several"
+    )]
+    package: Vec<String>,
+
+    #[arg(
+        long,
+        value_name = "SPEC",
+        help = "Skip this workspace package, repeat for several"
+    )]
+    exclude: Vec<String>,
+
+    #[arg(
+        long,
+        help = "Analyze Rust source read from stdin instead of the crate, e.g. `git show :src/lib.rs`"
+    )]
+    stdin: bool,
+
+    #[arg(
+        long,
+        requires = "stdin",
+        help = "File name to report the source read with --stdin as"
+    )]
+    filename: Option<String>,
+
+    #[arg(
+        long,
+        help = "Write csv rows as each file is analyzed, keeping only the totals in memory (rows are in walk order, jsonl always streams)"
+    )]
+    stream: bool,
+
+    #[arg(
+        long,
+        help = "Print how long each phase and the slowest files took to stderr"
+    )]
+    timings: bool,
+
+    #[arg(
+        long,
+        short,
+        help = "Print nothing, exit 1 on a regression against the baseline or a file over a configured danger threshold"
+    )]
+    quiet: bool,
+
+    #[arg(
+        long,
+        short,
+        global = true,
+        help = "Output file path (defaults to stdout)"
+    )]
+    output: Option<String>,
+
+    #[arg(
+        long,
+        short,
+        help = "Output format",
+        value_enum,
+        default_value = "markdown"
+    )]
+    format: OutputFormat,
+
+    #[arg(long, default_value_t = false)]
+    safe_candidates: bool,
+
+    #[arg(long, default_value_t = false)]
+    bool_candidates: bool,
+
+    #[arg(long, default_value_t = false)]
+    get_candidates: bool,
+
+    #[arg(long, default_value_t = false)]
+    iterator_candidates: bool,
+
+    #[arg(long, default_value_t = false)]
+    drop_candidates: bool,
+
+    #[arg(long, default_value_t = false)]
+    vec_candidates: bool,
+
+    #[arg(long, default_value_t = false)]
+    string_candidates: bool,
+
+    #[arg(long, default_value_t = false)]
+    ref_candidates: bool,
+
+    #[arg(long, default_value_t = false)]
+    context_candidates: bool,
+
+    #[arg(long, default_value_t = false)]
+    once_candidates: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = Confidence::Low,
+        help = "Only list, or with --count-candidates count, --*-candidates of at least this confidence"
+    )]
+    min_confidence: Confidence,
+
+    #[arg(
+        long,
+        help = "Count each file's --*-candidates as metrics, so diffs against a baseline show whether they're being burned down"
+    )]
+    count_candidates: bool,
+
+    #[arg(
+        long,
+        help = "Append a list of every unsafe function to markdown and html output"
+    )]
+    list_unsafe_fns: bool,
+
+    #[arg(
+        long,
+        help = "Append the public safe functions which transitively reach unsafe code"
+    )]
+    reachability: bool,
+
+    #[arg(
+        long,
+        help = "Append the unsafe functions which are never referenced by any test"
+    )]
+    untested_unsafe: bool,
+
+    #[arg(
+        long,
+        help = "Append the unwraps of each file split by context: library code, main(), tests, examples and build scripts"
+    )]
+    unwrap_contexts: bool,
+
+    #[arg(
+        long,
+        help = "Append the files to audit next, ranked by a risk score weighted in the [risk] config"
+    )]
+    audit_next: bool,
+
+    #[arg(
+        long,
+        help = "Append a table of the libc functions each file calls directly, and how often"
+    )]
+    libc_calls: bool,
+
+    #[arg(
+        long,
+        help = "Append a table of the lints each file allows with #[allow(...)], and how often"
+    )]
+    allows: bool,
+
+    #[arg(
+        long,
+        help = "Append the concurrency surface, the locks, atomics, threads and UnsafeCells each file creates"
+    )]
+    concurrency: bool,
+
+    #[arg(
+        long,
+        help = "Append every place outside of tests which can panic: unwraps, expects, indexing, division and panicking macros"
+    )]
+    panic_sites: bool,
+
+    #[arg(
+        long,
+        value_name = "KIND",
+        help = "Append the file and line of every site of this kind, repeat for several"
+    )]
+    locations: Vec<LocationKind>,
+
+    #[arg(
+        long,
+        help = "Coverage data to merge into the report (llvm-cov json or lcov)"
+    )]
+    coverage: Option<String>,
+
+    #[arg(
+        long,
+        help = "Output of `cargo clippy --message-format=json` to merge lint counts from"
+    )]
+    clippy_json: Option<String>,
+
+    #[arg(
+        long,
+        help = "Count the pub items without doc comments in each file, as the undocumented_pub_items metric"
+    )]
+    doc_coverage: bool,
+
+    #[arg(
+        long,
+        help = "Output of `cargo geiger --output-format Json` to compare against"
+    )]
+    geiger: Option<String>,
+
+    #[arg(
+        long,
+        help = "Attribute unsafe code and unwraps to their last author using git blame"
+    )]
+    blame: bool,
+
+    #[arg(
+        long,
+        requires = "group_by_owner",
+        help = "CODEOWNERS file to group by (defaults to the standard GitHub locations in the repository)"
+    )]
+    codeowners: Option<String>,
+
+    #[arg(long, help = "Aggregate stats per CODEOWNERS owner")]
+    group_by_owner: bool,
+
+    #[arg(
+        long,
+        value_name = "RANGE",
+        help = "Rank contributors by unsafe code removed over a git range (e.g. v1.0..HEAD)"
+    )]
+    leaderboard: Option<String>,
+
+    #[arg(
+        long,
+        help = "Report progress towards zero unsafe fns and static mut items"
+    )]
+    migration: bool,
+
+    #[arg(
+        long,
+        help = "History file to append this run's totals to, used to estimate completion"
+    )]
+    history: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "REPORT_FILE",
+        help = "Exit non-zero and print a diff if the committed report is out of date (implies --deterministic)"
+    )]
+    check: Option<String>,
+}
+
+#[derive(Debug, Clone, clap::Subcommand)]
+enum Command {
+    /// Write an UNSAFE_TODO.md checklist of unsafe code to convert
+    ///
+    /// Lists every unsafe fn, static mut, and unsafe block without a SAFETY
+    /// comment. Rerunning it keeps already checked items checked.
+    Todo,
+
+    /// Analyze commits across a git range and emit the totals as a time series
+    ///
+    /// Files are read from each commit's tree, so the working tree is left
+    /// untouched. The output can be used as a `--history` file.
+    History {
+        #[arg(long, help = "Git revision range to analyze, e.g. v1.0..HEAD")]
+        range: String,
+
+        #[arg(
+            long,
+            default_value_t = 1,
+            help = "Only analyze every Nth commit, always including the last"
+        )]
+        step: usize,
+
+        #[arg(long, short, value_enum, default_value_t = SeriesFormat::Csv, help = "Output format")]
+        format: SeriesFormat,
+    },
+
+    /// Find the commit where a metric increased, or first exceeded a threshold
+    ///
+    /// Binary searches the first-parent history between two refs, analyzing
+    /// each step like `history` does, so the metric is assumed not to go back
+    /// down within the range.
+    Bisect {
+        #[arg(
+            long,
+            help = "Metric to track, a CSV column name like unsafe_statements"
+        )]
+        metric: String,
+
+        #[arg(long, help = "Known good ref")]
+        from: String,
+
+        #[arg(long, default_value = "HEAD", help = "Known bad ref")]
+        to: String,
+
+        #[arg(
+            long,
+            help = "Find where the metric first exceeds this value (defaults to its value at --from)"
+        )]
+        threshold: Option<isize>,
+    },
+
+    /// Explain exactly what a metric counts and how diffs treat it
+    Explain {
+        #[arg(
+            help = "Metric to explain, a CSV column name like unsafe_statements (defaults to all)"
+        )]
+        metric: Option<String>,
+    },
+
+    /// Scaffold a config, a baseline and a GitHub Actions workflow
+    ///
+    /// Writes crate-report.toml, crate-report-baseline.csv and
+    /// .github/workflows/crate-report.yml, which posts a PR comment comparing
+    /// against the baseline cached from the last push to main.
+    Init {
+        #[arg(long, help = "Overwrite files which already exist")]
+        force: bool,
+    },
+
+    /// Block a commit which stages new unsafe code or unwraps
+    ///
+    /// Compares the staged version of each changed `.rs` file with HEAD, for
+    /// use as a git pre-commit hook. New code is allowed by a
+    /// `// crate-report: allow` comment on its line or the line above.
+    PreCommit,
+
+    /// List unwraps and expects in fns returning `Result` or `Option`
+    ///
+    /// These could propagate with `?` instead of panicking. With --apply, each
+    /// `.unwrap()` on one line becomes a `?`, expects are left for their
+    /// messages to be carried over by hand. The receiver's type isn't known,
+    /// so run `cargo check` after: an `Option` unwrapped in a fn returning
+    /// `Result`, or an error without a `From` conversion, needs `.ok_or(...)`
+    /// or `.map_err(...)` first.
+    QuestionMark {
+        #[arg(long, help = "Rewrite the unwraps which fit on one line to `?`")]
+        apply: bool,
+    },
+
+    /// Print a shell completion script
+    ///
+    /// e.g. `crate-report completions bash > /etc/bash_completion.d/crate-report`
+    Completions {
+        #[arg(value_enum, help = "Shell to complete in")]
+        shell: clap_complete::Shell,
+    },
+
+    /// Append cargo-geiger results to a history file, to keep its trend when
+    /// migrating from geiger
+    ///
+    /// Each output's counts for this crate's package become an entry dated by
+    /// when the file was last modified: unsafe fns and methods as unsafe_fns,
+    /// unsafe exprs as unsafe_statements.
+    ImportGeiger {
+        #[arg(help = "History file to append to, like --history")]
+        history_file: String,
+
+        #[arg(
+            required = true,
+            help = "Outputs of `cargo geiger --output-format Json`"
+        )]
+        geiger_files: Vec<String>,
+    },
+
+    /// Chart each metric from a history file as a sparkline
+    Trend {
+        #[arg(help = "History file written by --history or the history subcommand")]
+        history_file: String,
+
+        #[arg(long, help = "Only chart the last N runs")]
+        last: Option<usize>,
+
+        #[arg(long, help = "Only chart the N days before the most recent run")]
+        days: Option<u64>,
+    },
+
+    /// Rank several crates side by side, one row per crate
+    ///
+    /// Each crate root is analyzed like running crate-report in it. Without
+    /// any, every package of the workspace at the crate root is compared.
+    Compare {
+        #[arg(help = "Crate roots to compare (defaults to the workspace's packages)")]
+        crates: Vec<String>,
+
+        #[arg(
+            long,
+            default_value = "unsafe_statements_per_kloc",
+            help = "Metric to rank by, highest first, a CSV column name like unsafe_statements"
+        )]
+        sort: String,
+
+        #[arg(long, short, value_enum, default_value_t = MatrixFormat::Markdown, help = "Output format")]
+        format: MatrixFormat,
+    },
+
+    /// Combine reports produced separately, e.g. by per-crate CI jobs
+    ///
+    /// File keys are prefixed with each report's name, `parser/src/lib.rs`
+    /// for `parser=parser.json` or a bare `parser.json`. The merged report can
+    /// be used as a --baseline like any other.
+    Merge {
+        #[arg(
+            required = true,
+            value_name = "[NAME=]REPORT",
+            help = "CSV or JSON reports to merge"
+        )]
+        reports: Vec<String>,
+
+        #[arg(long, short, value_enum, default_value_t = MergeFormat::Json, help = "Output format")]
+        format: MergeFormat,
+    },
+}
+
+#[derive(Debug, Clone, clap::ValueEnum)]
+enum MergeFormat {
+    Csv,
+    Json,
+    Markdown,
+    Table,
+}
+
+#[derive(Debug, Clone, clap::ValueEnum)]
+enum MatrixFormat {
+    Csv,
+    Markdown,
+    Table,
+}
+
+#[derive(Debug, Clone, clap::ValueEnum)]
+enum SeriesFormat {
+    Csv,
+    Json,
+}
+
+/// How cleanly a `--*-candidates` heuristic matched, lowest first
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    clap::ValueEnum,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Confidence {
+    /// the pattern is there but something around it may prevent the change
+    Low,
+    Medium,
+    /// the pattern matched cleanly, the suggestion can usually be applied
+    /// as is
+    High,
+}
+
+impl std::fmt::Display for Confidence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Confidence::Low => "low",
+            Confidence::Medium => "medium",
+            Confidence::High => "high",
+        })
+    }
+}
+
+/// The sites `--locations` can list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
+enum LocationKind {
+    /// uses of the crate's own `#[deprecated]` items, by name
+    Deprecated,
+    /// `extern "C" fn` items and closures cast to `extern "C" fn` pointers
+    ExternCallbacks,
+    /// `CString::new`, `CStr::from_ptr`, `as_ptr` on C strings and `to_string_lossy`
+    FfiStrings,
+    /// `std::alloc` calls, `Layout` construction and `#[global_allocator]`s
+    Allocations,
+    /// `mem::forget`, `Box::leak`, `ManuallyDrop::new` and `into_raw` calls
+    LeakProne,
+    /// `process::exit` and `process::abort` calls, and libc's
+    ProcessExits,
+    /// fns and methods which call themselves directly
+    Recursive,
+    /// recursive fns which are unsafe, `extern` or `#[no_mangle]`, where a
+    /// stack overflow is hardest to rule out
+    ExposedRecursive,
+    /// `#[repr(C)]`, `#[repr(transparent)]` and `#[repr(packed)]` structs, enums and unions
+    ReprTypes,
+    /// `env::set_var`, `env::remove_var` and thread-unsafe libc calls
+    ThreadUnsafe,
+    /// `expect` calls whose message is empty or a single word
+    TrivialExpects,
+}
+
+impl LocationKind {
+    fn includes(self, kind: SiteKind) -> bool {
+        match self {
+            LocationKind::Deprecated => kind == SiteKind::DeprecatedUse,
+            LocationKind::ExternCallbacks => {
+                matches!(kind, SiteKind::ExternClosure | SiteKind::ExternFn)
+            }
+            LocationKind::FfiStrings => kind == SiteKind::FfiString,
+            LocationKind::Allocations => {
+                matches!(kind, SiteKind::Allocation | SiteKind::GlobalAllocator)
+            }
+            LocationKind::LeakProne => kind == SiteKind::LeakProne,
+            LocationKind::ProcessExits => kind == SiteKind::ProcessExit,
+            LocationKind::Recursive => matches!(kind, SiteKind::Recursive { .. }),
+            LocationKind::ExposedRecursive => kind == SiteKind::Recursive { exposed: true },
+            LocationKind::ReprTypes => matches!(kind, SiteKind::Repr(_)),
+            LocationKind::ThreadUnsafe => kind == SiteKind::ThreadUnsafe,
+            LocationKind::TrivialExpects => kind == SiteKind::TrivialExpect,
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            LocationKind::Deprecated => "Deprecated Uses",
+            LocationKind::ExternCallbacks => "Extern Callbacks",
+            LocationKind::FfiStrings => "FFI String Conversions",
+            LocationKind::Allocations => "Manual Allocations",
+            LocationKind::LeakProne => "Leak-Prone Calls",
+            LocationKind::ProcessExits => "Process Exits",
+            LocationKind::Recursive => "Recursive Fns",
+            LocationKind::ExposedRecursive => "Recursive Unsafe or Exported Fns",
+            LocationKind::ReprTypes => "Repr Types",
+            LocationKind::ThreadUnsafe => "Thread-Unsafe Calls",
+            LocationKind::TrivialExpects => "Trivial Expects",
+        }
+    }
+}
+
+#[derive(Debug, Clone, clap::ValueEnum)]
+enum ColorMode {
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Debug, Clone, clap::ValueEnum)]
+enum OutputFormat {
+    Csv,
+    Html,
+    Json,
+    /// one JSON object per file, written as each file is analyzed
+    Jsonl,
+    Markdown,
+    PrComment,
+    /// a Bitbucket Code Insights report and its annotations, one per counted site, like an unsafe block or unwrap
+    BitbucketInsights,
+    /// TeamCity service messages, a build statistic per total and an inspection per regressed file
+    Teamcity,
+    /// SonarQube's generic issue import JSON, an issue per counted site, like an unsafe block or unwrap
+    Sonar,
+    /// pr-comment for Azure DevOps, whose markdown doesn't render collapsible `<details>`
+    AzurePrComment,
+    /// a box-drawing table for reading in a terminal
+    Table,
+    /// before, after and delta per file and metric against the first --baseline, as JSON
+    DiffJson,
+    /// before, after and delta per file and metric against the first --baseline, a row each
+    DiffCsv,
+}
+
+/// Only the totals and per-file stats are serialized, see [`BASELINE_SCHEMA_VERSION`]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct Report {
+    #[serde(default)]
+    pub total: CodeStats,
+    /// by path relative to the crate root, with `/` separators
+    pub files: BTreeMap<String, CodeStats>,
+    /// per-file locations, only populated for freshly analyzed crates (not baselines)
+    #[serde(skip)]
+    sites: BTreeMap<String, Vec<Site>>,
+    /// per-file line coverage, only populated when `--coverage` is given
+    #[serde(skip)]
+    coverage: BTreeMap<String, coverage::FileCoverage>,
+    /// whether `clippy_lints` were collected, so the column can be shown even when all zero
+    #[serde(skip)]
+    has_clippy_lints: bool,
+    /// metrics a baseline was written without, e.g. by an older version
+    #[serde(skip)]
+    unknown_metrics: BTreeSet<String>,
+    /// the `--min-confidence` the `*_candidates` metrics were counted at, only
+    /// set with `--count-candidates`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    min_confidence: Option<Confidence>,
+}
+
+#[derive(Copy, Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct Change<T> {
+    pub after: T,
+    pub before: T,
+}
+
+impl<T> Change<T> {
+    fn project<U>(&self, f: impl Fn(&T) -> U) -> Change<U> {
+        Change {
+            after: f(&self.after),
+            before: f(&self.before),
+        }
+    }
+}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "status", rename_all = "lowercase")]
+pub enum Diff {
+    Added(CodeStats),
+    Changed(Box<Change<CodeStats>>),
+    Removed(CodeStats),
+}
+
+/// Exit code when `--quiet` or `--check` find a policy violation
+pub const EXIT_VIOLATION: i32 = 1;
+/// Exit code when the analysis couldn't be run, e.g. a missing input file
+pub const EXIT_ERROR: i32 = 2;
+/// Exit code when `--quiet` finds a regression in the warn tier only, see
+/// [`config::tiers`]
+pub const EXIT_WARNING: i32 = 3;
+
+/// A report compared against a baseline, see [`Report::diff`]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct DiffReport {
+    pub after_total: CodeStats,
+    pub before_total: CodeStats,
+    /// only the files which were added, removed or had a metric change
+    pub changes: BTreeMap<String /* filename */, Diff>,
+    /// metrics missing from the baseline, their before values are copied from after
+    pub unknown_metrics: BTreeSet<String>,
+    /// the files in both whose metrics didn't change, only listed for `--show-unchanged`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub unchanged: BTreeMap<String, CodeStats>,
+    /// changes taken out of `changes` as only moved code, for `--suppress-churn`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub relocated: BTreeMap<String, Diff>,
+}
+
+impl DiffReport {
+    /// Move the changes which only relocate code into `relocated`: files whose
+    /// safety metrics are all the same (code moved within them), and pairs of
+    /// files whose safety metric changes cancel out which are either a removed
+    /// and an added file (a renamed file) or `moved_between` each other
+    fn without_churn(mut self, moved_between: impl Fn(&str, &str) -> bool) -> Self {
+        let metrics: Vec<String> = CodeStats::csv_headers()
+            .into_iter()
+            .skip(1)
+            .filter(|metric| !metric.starts_with("total_"))
+            .collect();
+        let delta = |change: &Diff| -> Vec<isize> {
+            let (before, after) = match change {
+                Diff::Added(stats) => (CodeStats::default(), stats.clone()),
+                Diff::Changed(change) => (change.before.clone(), change.after.clone()),
+                Diff::Removed(stats) => (stats.clone(), CodeStats::default()),
+            };
+            metrics
+                .iter()
+                .map(|metric| {
+                    after.metric(metric).unwrap_or_default()
+                        - before.metric(metric).unwrap_or_default()
+                })
+                .collect()
+        };
+
+        let mut relocated = BTreeSet::new();
+        let mut unpaired: Vec<(&str, &Diff, Vec<isize>)> = Vec::new();
+        for (filename, change) in &self.changes {
+            let delta = delta(change);
+            if delta.iter().all(|&d| d == 0) {
+                // a new or deleted file without any unsafe code isn't a move
+                if matches!(change, Diff::Changed(_)) {
+                    relocated.insert(filename.clone());
+                }
+                continue;
+            }
+            let negated: Vec<isize> = delta.iter().map(|d| -d).collect();
+            let is_rename = |other_change: &Diff| {
+                matches!(
+                    (other_change, change),
+                    (Diff::Added(_), Diff::Removed(_)) | (Diff::Removed(_), Diff::Added(_))
+                )
+            };
+            if let Some(index) = unpaired
+                .iter()
+                .position(|(other, other_change, other_delta)| {
+                    *other_delta == negated
+                        && (is_rename(other_change) || moved_between(other, filename))
+                })
+            {
+                let (other, _, _) = unpaired.swap_remove(index);
+                relocated.insert(other.to_string());
+                relocated.insert(filename.clone());
+            } else {
+                unpaired.push((filename, change, delta));
+            }
+        }
+
+        for filename in relocated {
+            if let Some(change) = self.changes.remove(&filename) {
+                self.relocated.insert(filename, change);
+            }
+        }
+        self
+    }
+
+    /// The unsafe fn percentage and unsafe statements per KLOC, which absolute
+    /// deltas hide when a lot of safe code is added too, `None` when the
+    /// baseline is missing a metric they're computed from
+    fn densities(&self) -> Option<[(&'static str, Change<f64>); 2]> {
+        if [
+            "unsafe_fns",
+            "total_fns",
+            "unsafe_statements",
+            "total_lines",
+        ]
+        .iter()
+        .any(|metric| self.unknown_metrics.contains(*metric))
+        {
+            return None;
+        }
+        let change = Change {
+            before: &self.before_total,
+            after: &self.after_total,
+        };
+        Some([
+            (
+                "unsafe_fns",
+                change.project(|stats| percentage(stats.unsafe_fns, stats.total_fns)),
+            ),
+            (
+                "unsafe_statements",
+                change.project(|stats| stats.unsafe_statements_per_kloc()),
+            ),
+        ])
+    }
+
+    /// A note listing the relocated files, if any
+    fn relocated_note(&self) -> Option<String> {
+        if self.relocated.is_empty() {
+            return None;
+        }
+        let files: Vec<&str> = self.relocated.keys().map(String::as_str).collect();
+        Some(format!(
+            "Relocated, no metric change ({} file{}): {}",
+            files.len(),
+            if files.len() == 1 { "" } else { "s" },
+            files.join(", ")
+        ))
+    }
+
+    /// List the files of `report` (the after side) which aren't among the
+    /// changes or relocations
+    fn with_unchanged(mut self, report: &Report) -> Self {
+        self.unchanged = report
+            .files
+            .iter()
+            .filter(|(filename, _)| {
+                !self.changes.contains_key(*filename) && !self.relocated.contains_key(*filename)
+            })
+            .map(|(filename, stats)| (filename.clone(), stats.clone()))
+            .collect();
+        self
+    }
+
+    /// A total's change, or just its current value when the baseline didn't have it
+    fn format_total(&self, metric: &str) -> String {
+        let after = self.after_total.metric(metric).unwrap_or_default();
+        if self.unknown_metrics.contains(metric) {
+            format!("{after} (unknown in baseline)")
+        } else {
+            let before = self.before_total.metric(metric).unwrap_or_default();
+            format_diff(before, after, config::decrease_is(metric))
+        }
+    }
+
+    /// A total's value before, `?` when the baseline didn't have it
+    fn format_before(&self, metric: &str) -> String {
+        if self.unknown_metrics.contains(metric) {
+            "?".to_string()
+        } else {
+            self.before_total
+                .metric(metric)
+                .unwrap_or_default()
+                .to_string()
+        }
+    }
+
+    /// Lint counts are only shown when either side of the diff collected them
+    fn has_clippy_lints(&self) -> bool {
+        self.before_total.clippy_lints != 0 || self.after_total.clippy_lints != 0
+    }
+
+    /// The [`SPARSE_METRICS`] either side has any of
+    fn sparse_metrics(&self) -> Vec<&'static SparseMetric> {
+        SPARSE_METRICS
+            .iter()
+            .filter(|sparse| {
+                self.before_total
+                    .sparse_metrics()
+                    .any(|m| m.metric == sparse.metric)
+                    || self
+                        .after_total
+                        .sparse_metrics()
+                        .any(|m| m.metric == sparse.metric)
+            })
+            .collect()
+    }
+
+    fn color_display<W>(&self, summary_heading: &str, mut out: W)
+    where
+        W: std::io::Write,
+    {
+        if self.changes.is_empty() {
+            _ = writeln!(&mut out, "No changes");
+        }
+
+        self.summary_display(summary_heading, &mut out);
+        self.files_display(&mut out);
+    }
+
+    /// The change in the totals, under a heading styled for the output format
+    fn summary_display<W>(&self, heading: &str, mut out: W)
+    where
+        W: std::io::Write,
+    {
+        _ = writeln!(
+            out,
+            "{heading}
+unsafe fn  : {}
+total fn   : {}
+total stmt : {}
+static mut : {}
+unwraps    : {}",
+            self.format_total("unsafe_fns"),
+            self.format_total("total_fns"),
+            self.format_total("unsafe_statements"),
+            self.format_total("static_mut_items"),
+            self.format_total("unwraps"),
+        );
+        if let Some([(_, fn_percent), (_, per_kloc)]) = self.densities() {
+            _ = writeln!(
+                out,
+                "unsafe fn %: {}\nunsafe/kloc: {}",
+                format_density_diff(fn_percent, "%", config::decrease_is("unsafe_fns")),
+                format_density_diff(per_kloc, "", config::decrease_is("unsafe_statements")),
+            );
+        }
+        if self.has_clippy_lints() {
+            _ = writeln!(out, "clippy     : {}", self.format_total("clippy_lints"));
+        }
+        for sparse in self.sparse_metrics() {
+            _ = writeln!(
+                out,
+                "{}: {}",
+                sparse.metric,
+                self.format_total(sparse.metric)
+            );
+        }
+        for metric in plugin::metrics() {
+            _ = writeln!(out, "{metric:<11}: {}", self.format_total(&metric));
+        }
+        _ = writeln!(out);
+    }
+
+    /// The changed, added, and removed files
+    fn files_display<W>(&self, mut out: W)
+    where
+        W: std::io::Write,
+    {
+        // print in order: changed, added, removed
+
+        for (filename, diff) in &self.changes {
+            if let Diff::Changed(change) = diff {
+                let unsafe_fns = change.project(|e| e.unsafe_fns);
+                let total_fns = change.project(|e| e.total_fns);
+
+                _ = writeln!(
+                    out,
+                    "{filename}
+unsafe fn   : {}
+unsafe stmt : {}
+static mut  : {}
+unwraps     : {}",
+                    format_unsafe_fn_change(unsafe_fns, total_fns),
+                    format_diff(
+                        change.before.unsafe_statements,
+                        change.after.unsafe_statements,
+                        config::decrease_is("unsafe_statements")
+                    ),
+                    format_diff(
+                        change.before.static_mut_items,
+                        change.after.static_mut_items,
+                        config::decrease_is("static_mut_items")
+                    ),
+                    format_diff(
+                        change.before.unwraps,
+                        change.after.unwraps,
+                        config::decrease_is("unwraps")
+                    ),
+                );
+                if change.before.clippy_lints != change.after.clippy_lints {
+                    _ = writeln!(
+                        out,
+                        "clippy      : {}",
+                        format_diff(
+                            change.before.clippy_lints,
+                            change.after.clippy_lints,
+                            config::decrease_is("clippy_lints")
+                        )
+                    );
+                }
+                for metric in ["total_fns", "total_statements", "total_lines"]
+                    .into_iter()
+                    .filter(|metric| config::reports_change(metric))
+                    .chain(SPARSE_METRICS.iter().map(|sparse| sparse.metric))
+                    .map(String::from)
+                    .chain(plugin::metrics())
+                {
+                    let change = change.project(|e| e.metric(&metric).unwrap_or_default());
+                    if change.before != change.after {
+                        _ = writeln!(
+                            out,
+                            "{metric:<12}: {}",
+                            format_diff(change.before, change.after, config::decrease_is(&metric))
+                        );
+                    }
+                }
+                _ = writeln!(out);
+            }
+        }
+
+        for (filename, diff) in &self.changes {
+            if let Diff::Added(CodeStats {
+                unsafe_fns,
+                total_fns,
+                unsafe_statements,
+                unwraps,
+                ..
+            }) = diff
+            {
+                _ = writeln!(
+                    out,
+                    "{filename} [NEW FILE]
+  Unsafe funcs: {unsafe_fns}
+   Total funcs: {total_fns}
+  Unsafe stmts: {unsafe_statements}
+       unwraps: {unwraps}
+"
+                );
+            }
+        }
+
+        for (filename, diff) in &self.changes {
+            if let Diff::Removed(CodeStats {
+                unsafe_fns,
+                total_fns,
+                unsafe_statements,
+                ..
+            }) = diff
+            {
+                _ = writeln!(
+                    out,
+                    "{filename} [REMOVED]
+  Had {unsafe_fns} unsafe / {total_fns} total fns, {unsafe_statements} unsafe lines\n"
+                );
+            }
+        }
+
+        if let Some(note) = self.relocated_note() {
+            _ = writeln!(out, "{note}\n");
+        }
+
+        for (filename, stats) in &self.unchanged {
+            _ = writeln!(
+                out,
+                "{filename} [UNCHANGED]
+  {} unsafe / {} total fns, {} unsafe lines\n",
+                stats.unsafe_fns, stats.total_fns, stats.unsafe_statements
+            );
+        }
+    }
+}
+
+impl Report {
+    fn from_files(files: BTreeMap<String, CodeStats>) -> Self {
+        Self {
+            total: files.values().cloned().sum(),
+            files,
+            sites: BTreeMap::new(),
+            coverage: BTreeMap::new(),
+            has_clippy_lints: false,
+            unknown_metrics: BTreeSet::new(),
+            min_confidence: None,
+        }
+    }
+
+    /// The files `keep` accepts, with their sites and coverage
+    fn subset(&self, keep: impl Fn(&str) -> bool) -> Self {
+        let files: BTreeMap<String, CodeStats> = self
+            .files
+            .iter()
+            .filter(|(filename, _)| keep(filename))
+            .map(|(filename, stats)| (filename.clone(), stats.clone()))
+            .collect();
+        Self {
+            sites: self
+                .sites
+                .iter()
+                .filter(|(filename, _)| files.contains_key(*filename))
+                .map(|(filename, sites)| (filename.clone(), sites.clone()))
+                .collect(),
+            coverage: self
+                .coverage
+                .iter()
+                .filter(|(filename, _)| files.contains_key(*filename))
+                .map(|(filename, coverage)| (filename.clone(), coverage.clone()))
+                .collect(),
+            has_clippy_lints: self.has_clippy_lints,
+            unknown_metrics: self.unknown_metrics.clone(),
+            min_confidence: self.min_confidence,
+            ..Self::from_files(files)
+        }
+    }
+
+    /// A row per package with its safety metrics and unsafe statement density,
+    /// package names linking to their section of a markdown report if `link_sections`
+    fn to_packages_table(&self, packages: &packages::Packages, link_sections: bool) -> Table {
+        let mut table = Table::with_headers(vec![
+            "package".into(),
+            " (unsafe/total) fns".into(),
+            "statements".into(),
+            "static mut".into(),
+            "unwrap".into(),
+            "unsafe/kloc".into(),
+        ]);
+        table.extend_rows(
+            packages
+                .group(&self.files)
+                .into_iter()
+                .map(|(package, stats)| {
+                    vec![
+                        if link_sections {
+                            markdown_link(&package, Some(markdown_anchor(&package))).into()
+                        } else {
+                            package.into()
+                        },
+                        colorize_ratio(stats.unsafe_fns, stats.total_fns),
+                        format!("{}/{}", stats.unsafe_statements, stats.total_statements).into(),
+                        colorize_simple(
+                            stats.static_mut_items,
+                            config::threshold("static_mut_items"),
+                        ),
+                        colorize_simple(stats.unwraps, config::threshold("unwraps")),
+                        format!("{:.2}", stats.unsafe_statements_per_kloc()).into(),
+                    ]
+                }),
+        );
+        table
+    }
+
+    /// Load a baseline CSV written by a previous run
+    ///
+    /// Both the CSV and JSON output formats can be used as baselines, JSON is
+    /// detected by a `.json` extension or content starting with `{`.
+    fn load_baseline(baseline_file: &str, content: &str) -> Result<Self, String> {
+        if content.trim().is_empty() {
+            return Err("the baseline is empty".to_string());
+        }
+        let report = if baseline_file.ends_with(".json") || content.trim_start().starts_with('{') {
+            Self::from_json(content)?
+        } else {
+            Self::from_csv(content)?
+        };
+        // baselines may come from another platform
+        Ok(report.map_paths(normalize_path))
+    }
+
+    /// Rewrite the file path keys of every per-file map
+    fn map_paths(self, f: impl Fn(&str) -> String) -> Self {
+        Self {
+            files: self.files.into_iter().map(|(k, v)| (f(&k), v)).collect(),
+            sites: self.sites.into_iter().map(|(k, v)| (f(&k), v)).collect(),
+            coverage: self.coverage.into_iter().map(|(k, v)| (f(&k), v)).collect(),
+            ..self
+        }
+    }
+
+    fn from_csv(content: &str) -> Result<Self, String> {
+        let schema_version = content
+            .lines()
+            .next()
+            .and_then(|line| line.strip_prefix(CSV_SCHEMA_PREFIX))
+            .map(|version| version.trim().parse().map_err(|_| "invalid schema version"))
+            .transpose()?
+            .unwrap_or(1);
+        check_schema_version(schema_version);
+        let min_confidence = content
+            .lines()
+            .take_while(|line| line.starts_with('#'))
+            .find_map(|line| line.strip_prefix(CSV_MIN_CONFIDENCE_PREFIX))
+            .map(|confidence| {
+                clap::ValueEnum::from_str(confidence.trim(), true)
+                    .map_err(|_| format!("invalid min confidence '{}'", confidence.trim()))
+            })
+            .transpose()?;
+
+        let mut reader = csv::ReaderBuilder::new()
+            .comment(Some(b'#'))
+            .from_reader(content.as_bytes());
+        let headers: Vec<String> = reader
+            .headers()
+            .map_err(|e| e.to_string())?
+            .iter()
+            .map(str::to_string)
+            .collect();
+        if !CodeStats::is_valid_csv_header(&headers) {
+            return Err("CSV headers do not match expected format".to_string());
+        }
+        let unknown_metrics = CodeStats::csv_headers()
+            .into_iter()
+            .skip(1)
+            .filter(|metric| !headers.contains(metric))
+            .collect();
+
+        let files = reader
+            .records()
+            .filter(|result| {
+                // totals are recomputed from the files
+                !matches!(result, Ok(record) if record.get(0) == Some(CSV_TOTAL_ROW))
+            })
+            .map(|result| {
+                let record = result.map_err(|e| e.to_string())?;
+                CodeStats::from_csv_record(&headers, &record)
+                    .ok_or_else(|| format!("invalid row {:?}", record.as_slice()))
+            })
+            .collect::<Result<BTreeMap<String, CodeStats>, String>>()?;
+        Ok(Self {
+            unknown_metrics,
+            min_confidence,
+            ..Self::from_files(files)
+        })
+    }
+
+    fn from_json(content: &str) -> Result<Self, String> {
+        let json: serde_json::Value = serde_json::from_str(content).map_err(|e| e.to_string())?;
+        let total = json.get("total").and_then(serde_json::Value::as_object);
+        let unknown_metrics = CodeStats::csv_headers()
+            .into_iter()
+            .skip(1)
+            .filter(|metric| !total.is_some_and(|total| total.contains_key(metric)))
+            .collect();
+
+        let report: Versioned<Self> = serde_json::from_value(json).map_err(|e| e.to_string())?;
+        check_schema_version(report.schema_version);
+        Ok(Self {
+            unknown_metrics,
+            min_confidence: report.content.min_confidence,
+            ..Self::from_files(report.content.files)
+        })
+    }
+
+    fn from_analyzed(analyzed: BTreeMap<String, (CodeStats, Vec<Site>)>) -> Self {
+        let mut files = BTreeMap::new();
+        let mut sites = BTreeMap::new();
+        for (filename, (stats, file_sites)) in analyzed {
+            sites.insert(filename.clone(), file_sites);
+            files.insert(filename, stats);
+        }
+
+        Self {
+            total: files.values().cloned().sum(),
+            files,
+            sites,
+            coverage: BTreeMap::new(),
+            has_clippy_lints: false,
+            unknown_metrics: BTreeSet::new(),
+            min_confidence: None,
+        }
+    }
+
+    /// The totals of each directory containing source files, `.` for the crate root
+    fn directories(&self) -> BTreeMap<String, CodeStats> {
+        let mut directories: BTreeMap<String, Vec<CodeStats>> = BTreeMap::new();
+        for (filename, stats) in &self.files {
+            let directory = Path::new(filename)
+                .parent()
+                .map(|parent| parent.display().to_string())
+                .filter(|parent| !parent.is_empty())
+                .unwrap_or_else(|| ".".to_string());
+            directories
+                .entry(directory)
+                .or_default()
+                .push(stats.clone());
+        }
+        directories
+            .into_iter()
+            .map(|(directory, stats)| (directory, stats.into_iter().sum()))
+            .collect()
+    }
+
+    /// Compare against a baseline, e.g. one read with [`load_report`]
+    pub fn diff(&self, baseline: &Self) -> DiffReport {
+        let all_files: BTreeSet<&str> = baseline
+            .files
+            .keys()
+            .chain(self.files.keys())
+            .map(|e| e.as_str())
+            .collect();
+
+        // treat metrics the baseline doesn't have as unchanged
+        let fill_unknown = |mut before: CodeStats, after: &CodeStats| {
+            for metric in &baseline.unknown_metrics {
+                if let (Some(value), Some(field)) =
+                    (after.metric(metric), before.metric_mut(metric))
+                {
+                    *field = value;
+                }
+            }
+            before
+        };
+
+        DiffReport {
+            after_total: self.total.clone(),
+            before_total: fill_unknown(baseline.total.clone(), &self.total),
+            unknown_metrics: baseline.unknown_metrics.clone(),
+            unchanged: BTreeMap::new(),
+            relocated: BTreeMap::new(),
+
+            changes: all_files
+                .into_iter()
+                .flat_map(|filename| {
+                    match (
+                        baseline.files.get(filename).cloned(),
+                        self.files.get(filename).cloned(),
+                    ) {
+                        (Some(before), Some(after))
+                            if fill_unknown(before.clone(), &after)
+                                .should_report_change(&after) =>
+                        {
+                            let before = fill_unknown(before, &after);
+                            Some((
+                                filename.to_string(),
+                                Diff::Changed(Box::new(Change { before, after })),
+                            ))
+                        }
+                        (None, Some(new)) => Some((filename.to_string(), Diff::Added(new))),
+                        (Some(old), None) => Some((filename.to_string(), Diff::Removed(old))),
+                        (_, _) => None,
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// The unsafe fn sites of every file which has any, in filename order
+    fn unsafe_fns_by_file(&self) -> impl Iterator<Item = (&str, Vec<&Site>)> {
+        self.sites.iter().filter_map(|(filename, sites)| {
+            let unsafe_fns: Vec<&Site> = sites
+                .iter()
+                .filter(|site| site.kind == SiteKind::UnsafeFn)
+                .collect();
+            (!unsafe_fns.is_empty()).then_some((filename.as_str(), unsafe_fns))
+        })
+    }
+
+    fn unsafe_fn_list<W>(&self, mut out: W)
+    where
+        W: std::io::Write,
+    {
+        _ = writeln!(
+            out,
+            "{}\n",
+            markdown_heading("Unsafe Functions", 1, config::HeadingStyle::Setext)
+        );
+        for (filename, unsafe_fns) in self.unsafe_fns_by_file() {
+            _ = writeln!(out, "{filename}");
+            for site in unsafe_fns {
+                _ = writeln!(
+                    out,
+                    "- `{}` {}",
+                    site.name,
+                    markdown_link(
+                        format!("{filename}:{}", site.line),
+                        links::line_url(filename, site.line)
+                    )
+                );
+            }
+            _ = writeln!(out);
+        }
+    }
+
+    /// The per-file table, with filenames as markdown links to the repository
+    /// when `link_filenames` and `--repo-url` is known
+    fn to_table(&self, link_filenames: bool) -> Table {
+        let show_coverage = !self.coverage.is_empty();
+
+        let mut headers: Vec<ColoredString> = vec![
+            "".into(),
+            " (unsafe/total) fns".into(),
+            "statements".into(),
+            "static mut".into(),
+            "unwrap".into(),
+        ];
+        if self.has_clippy_lints {
+            headers.push("lints".into());
+        }
+        if show_coverage {
+            headers.push("coverage".into());
+        }
+        let plugin_metrics = plugin::metrics();
+        headers.extend(plugin_metrics.iter().map(|metric| metric.as_str().into()));
+
+        let mut table = Table::with_headers(headers);
+        table.extend_rows(self.files.iter().map(|(filename, file_report)| {
+            let mut row = vec![
+                if link_filenames {
+                    markdown_link(
+                        style_filename(filename, file_report),
+                        links::file_url(filename),
+                    )
+                    .into()
+                } else {
+                    style_filename(filename, file_report)
+                }, // filename
+                colorize_ratio(file_report.unsafe_fns, file_report.total_fns), // unsafe fns
+                format!(
+                    "{}/{}",
+                    file_report.unsafe_statements, file_report.total_statements
+                )
+                .into(), // unsafe statements
+                colorize_simple(
+                    file_report.static_mut_items,
+                    config::threshold("static_mut_items"),
+                ), // static mut
+                colorize_simple(file_report.unwraps, config::threshold("unwraps")), // unwraps
+            ];
+            if self.has_clippy_lints {
+                row.push(colorize_simple(
+                    file_report.clippy_lints,
+                    config::threshold("clippy_lints"),
+                ));
+            }
+            if show_coverage {
+                row.push(format_coverage(self.file_coverage(filename)).into());
+            }
+            row.extend(plugin_metrics.iter().map(|metric| {
+                colorize_simple(
+                    file_report.metric(metric).unwrap_or_default(),
+                    config::threshold(metric),
+                )
+            }));
+            row
+        }));
+        table
+    }
+
+    fn set_clippy_lints(&mut self, lints: BTreeMap<String, isize>) {
+        for (filename, count) in lints {
+            if let Some(stats) = self.files.get_mut(&filename) {
+                stats.clippy_lints = count;
+            }
+        }
+        self.total = self.files.values().cloned().sum();
+        self.has_clippy_lints = true;
+    }
+
+    fn file_coverage(&self, filename: &str) -> Option<f64> {
+        self.coverage.get(filename)?.percent()
+    }
+
+    /// Unsafe fns with coverage data, ordered from least to most covered
+    fn least_covered_unsafe_fns(&self) -> Vec<(&str, &Site, f64)> {
+        let mut unsafe_fns: Vec<(&str, &Site, f64)> = self
+            .sites
+            .iter()
+            .flat_map(|(filename, sites)| sites.iter().map(move |site| (filename, site)))
+            .filter(|(_, site)| site.kind == SiteKind::UnsafeFn)
+            .filter_map(|(filename, site)| {
+                let percent = self
+                    .coverage
+                    .get(filename)?
+                    .percent_in(site.line, site.end_line)?;
+                Some((filename.as_str(), site, percent))
+            })
+            .collect();
+        unsafe_fns.sort_by(|a, b| a.2.total_cmp(&b.2));
+        unsafe_fns
+    }
+}
+
+impl CodeStats {
+    fn is_perfect(&self) -> bool {
+        self.unsafe_fns == 0
+            && self.unsafe_statements == 0
+            && self.static_mut_items == 0
+            && self.unwraps == 0
+    }
+
+    fn should_report_change(&self, rhs: &Self) -> bool {
+        let Self {
+            total_fns,
+            total_statements,
+            total_lines,
+
+            allowed_lints,
+            clippy_lints,
+            deprecated_uses,
+            extern_callbacks,
+            ffi_string_conversions,
+            leak_prone_calls,
+            manual_allocations,
+            process_exits,
+            recursive_fns,
+            repr_types,
+            unsafe_fns,
+            unsafe_statements,
+            static_mut_items,
+            thread_unsafe_calls,
+            trivial_expects,
+            unwraps,
+            plugin_metrics: _, // compared by name below, missing counts are zero
+        } = rhs;
+
+        // churn in the totals is ignored unless configured, see `config::reports_change`
+        let totals_changed = [
+            ("total_fns", self.total_fns, *total_fns),
+            ("total_statements", self.total_statements, *total_statements),
+            ("total_lines", self.total_lines, *total_lines),
+        ]
+        .into_iter()
+        .any(|(metric, before, after)| before != after && config::reports_change(metric));
+
+        totals_changed
+            || self.allowed_lints != *allowed_lints
+            || self.clippy_lints != *clippy_lints
+            || self.deprecated_uses != *deprecated_uses
+            || self.extern_callbacks != *extern_callbacks
+            || self.ffi_string_conversions != *ffi_string_conversions
+            || self.leak_prone_calls != *leak_prone_calls
+            || self.manual_allocations != *manual_allocations
+            || self.process_exits != *process_exits
+            || self.recursive_fns != *recursive_fns
+            || self.repr_types != *repr_types
+            || self.unsafe_fns != *unsafe_fns
+            || self.unsafe_statements != *unsafe_statements
+            || self.static_mut_items != *static_mut_items
+            || self.thread_unsafe_calls != *thread_unsafe_calls
+            || self.trivial_expects != *trivial_expects
+            || self.unwraps != *unwraps
+            || plugin::metrics()
+                .iter()
+                .any(|metric| self.metric(metric) != rhs.metric(metric))
+    }
+
+    /// Check that a baseline's headers can be read, i.e. they start with the
+    /// filename
+    ///
+    /// Metric columns may be missing (written before the metric was added) or
+    /// unknown (written by a newer version), neither invalidates the baseline.
+    fn is_valid_csv_header(headers: &[String]) -> bool {
+        headers.first().map(|h| h == "filename").unwrap_or(false)
+    }
+
+    /// Parse a baseline row by column name, missing columns are zero and
+    /// unknown ones are ignored
+    fn from_csv_record(headers: &[String], record: &csv::StringRecord) -> Option<(String, Self)> {
+        let mut filename = None;
+        let mut stats = Self::default();
+
+        for (header, value) in headers.iter().zip(record) {
+            if header == "filename" {
+                filename = Some(value.to_string());
+            } else if let Some(field) = stats.metric_mut(header) {
+                *field = value.parse().ok()?;
+            }
+        }
+
+        Some((filename?, stats))
+    }
+
+    /// A metric as compared against its threshold, unsafe fns are a percentage
+    fn threshold_value(&self, name: &str) -> Option<f64> {
+        if name == "unsafe_fns" {
+            Some(percentage(self.unsafe_fns, self.total_fns))
+        } else {
+            self.metric(name).map(|value| value as f64)
+        }
+    }
+
+    fn metric(&self, name: &str) -> Option<isize> {
+        self.clone().metric_mut(name).copied()
+    }
+
+    /// Look up a metric by its csv column name
+    fn metric_mut(&mut self, name: &str) -> Option<&mut isize> {
+        Some(match name {
+            "allowed_lints" => &mut self.allowed_lints,
+            "clippy_lints" => &mut self.clippy_lints,
+            "deprecated_uses" => &mut self.deprecated_uses,
+            "extern_callbacks" => &mut self.extern_callbacks,
+            "ffi_string_conversions" => &mut self.ffi_string_conversions,
+            "leak_prone_calls" => &mut self.leak_prone_calls,
+            "manual_allocations" => &mut self.manual_allocations,
+            "process_exits" => &mut self.process_exits,
+            "recursive_fns" => &mut self.recursive_fns,
+            "repr_types" => &mut self.repr_types,
+            "static_mut_items" => &mut self.static_mut_items,
+            "thread_unsafe_calls" => &mut self.thread_unsafe_calls,
+            "total_fns" => &mut self.total_fns,
+            "total_lines" => &mut self.total_lines,
+            "total_statements" => &mut self.total_statements,
+            "trivial_expects" => &mut self.trivial_expects,
+            "unsafe_fns" => &mut self.unsafe_fns,
+            "unsafe_statements" => &mut self.unsafe_statements,
+            "unwraps" => &mut self.unwraps,
+            _ if plugin::is_registered(name) => {
+                self.plugin_metrics.entry(name.to_string()).or_default()
+            }
+            _ => return None,
+        })
+    }
+
+    /// The metric columns, followed by those of registered plugins
+    fn csv_headers() -> Vec<String> {
+        let mut headers: Vec<String> = vec![
+            "filename".to_string(),
+            "allowed_lints".into(),
+            "clippy_lints".into(),
+            "deprecated_uses".into(),
+            "extern_callbacks".into(),
+            "ffi_string_conversions".into(),
+            "leak_prone_calls".into(),
+            "manual_allocations".into(),
+            "process_exits".into(),
+            "recursive_fns".into(),
+            "repr_types".into(),
+            "static_mut_items".into(),
+            "thread_unsafe_calls".into(),
+            "total_fns".into(),
+            "total_lines".into(),
+            "total_statements".into(),
+            "trivial_expects".into(),
+            "unsafe_fns".into(),
+            "unsafe_statements".into(),
+            "unwraps".into(),
+        ];
+        headers.extend(plugin::metrics());
+        headers
+    }
+
+    /// The [`SPARSE_METRICS`] this has any of
+    fn sparse_metrics(&self) -> impl Iterator<Item = &'static SparseMetric> {
+        SPARSE_METRICS
+            .iter()
+            .filter(|sparse| self.metric(sparse.metric).is_some_and(|count| count != 0))
+    }
+
+    /// Columns derived from the metrics, only written to CSV output for spreadsheets
+    const COMPUTED_CSV_HEADERS: [&str; 2] = ["unsafe_fn_percent", "unsafe_statements_per_kloc"];
+
+    fn computed_csv_row(&self) -> [String; 2] {
+        [
+            format!("{:.2}", percentage(self.unsafe_fns, self.total_fns)),
+            format!("{:.2}", self.unsafe_statements_per_kloc()),
+        ]
+    }
+
+    /// Unsafe statements per thousand lines, zero for an empty file
+    fn unsafe_statements_per_kloc(&self) -> f64 {
+        if self.total_lines == 0 {
+            0.0
+        } else {
+            self.unsafe_statements as f64 * 1000.0 / self.total_lines as f64
+        }
+    }
+
+    fn to_csv_row(&self, filename: String) -> Vec<String> {
+        let mut row = vec![
+            filename,
+            self.allowed_lints.to_string(),
+            self.clippy_lints.to_string(),
+            self.deprecated_uses.to_string(),
+            self.extern_callbacks.to_string(),
+            self.ffi_string_conversions.to_string(),
+            self.leak_prone_calls.to_string(),
+            self.manual_allocations.to_string(),
+            self.process_exits.to_string(),
+            self.recursive_fns.to_string(),
+            self.repr_types.to_string(),
+            self.static_mut_items.to_string(),
+            self.thread_unsafe_calls.to_string(),
+            self.total_fns.to_string(),
+            self.total_lines.to_string(),
+            self.total_statements.to_string(),
+            self.trivial_expects.to_string(),
+            self.unsafe_fns.to_string(),
+            self.unsafe_statements.to_string(),
+            self.unwraps.to_string(),
+        ];
+        row.extend(
+            plugin::metrics()
+                .iter()
+                .map(|metric| self.metric(metric).unwrap_or_default().to_string()),
+        );
+        row
+    }
+}
+
+/// A metric most crates have none of, like those of the FFI boundary, which
+/// only shows up in summaries when a crate has any
+struct SparseMetric {
+    metric: &'static str,
+    /// e.g. in the markdown totals
+    label: &'static str,
+    /// in the PR comment's summary table
+    title: &'static str,
+    /// unless configured under `[directions]`, FFI boundaries are neutral
+    decrease_is: DecreaseIs,
+}
+
+const SPARSE_METRICS: &[SparseMetric] = &[
+    SparseMetric {
+        metric: "allowed_lints",
+        label: "allowed lints",
+        title: "Allowed Lints",
+        decrease_is: DecreaseIs::Good,
+    },
+    SparseMetric {
+        metric: "deprecated_uses",
+        label: "uses of deprecated items",
+        title: "Deprecated Uses",
+        decrease_is: DecreaseIs::Good,
+    },
+    SparseMetric {
+        metric: "extern_callbacks",
+        label: "extern callbacks",
+        title: "Extern Callbacks",
+        decrease_is: DecreaseIs::Neutral,
+    },
+    SparseMetric {
+        metric: "ffi_string_conversions",
+        label: "FFI string conversions",
+        title: "FFI String Conversions",
+        decrease_is: DecreaseIs::Neutral,
+    },
+    SparseMetric {
+        metric: "leak_prone_calls",
+        label: "leak-prone calls",
+        title: "Leak-Prone Calls",
+        decrease_is: DecreaseIs::Good,
+    },
+    SparseMetric {
+        metric: "manual_allocations",
+        label: "manual allocations",
+        title: "Manual Allocations",
+        decrease_is: DecreaseIs::Good,
+    },
+    SparseMetric {
+        metric: "process_exits",
+        label: "process exits",
+        title: "Process Exits",
+        decrease_is: DecreaseIs::Good,
+    },
+    SparseMetric {
+        metric: "recursive_fns",
+        label: "recursive fns",
+        title: "Recursive Fns",
+        decrease_is: DecreaseIs::Good,
+    },
+    SparseMetric {
+        metric: "repr_types",
+        label: "repr types",
+        title: "Repr Types",
+        decrease_is: DecreaseIs::Neutral,
+    },
+    SparseMetric {
+        metric: "thread_unsafe_calls",
+        label: "thread-unsafe calls",
+        title: "Thread-Unsafe Calls",
+        decrease_is: DecreaseIs::Good,
+    },
+    SparseMetric {
+        metric: "trivial_expects",
+        label: "trivial expects",
+        title: "Trivial Expects",
+        decrease_is: DecreaseIs::Good,
+    },
+];
+
+/// Whether a file could contain anything besides totals, i.e. unsafe code,
+/// unwraps, expects, allows, static mut items, FFI boundaries, manual
+/// allocations, leaks, exits, thread-unsafe calls or recursive fns, by a byte
+/// scan which is much cheaper than parsing
+fn might_contribute(content: &str) -> bool {
+    // plugins can count anything
+    !plugin::metrics().is_empty()
+        || might_recurse(content)
+        || content.contains("unsafe")
+        || content.contains("unwrap")
+        || content.contains("expect")
+        || content.contains("allow")
+        || content.contains("CStr")
+        || content.contains("c\"")
+        || content.contains("to_string_lossy")
+        || content.contains("extern")
+        || content.contains("repr")
+        || content.contains("alloc")
+        || content.contains("Layout")
+        || content.contains("forget")
+        || content.contains("leak")
+        || content.contains("ManuallyDrop")
+        || content.contains("into_raw")
+        || content.contains("exit")
+        || content.contains("abort")
+        || content.contains("env")
+        || content.contains("libc")
+        || content.split("static").skip(1).any(|rest| {
+            rest.starts_with(char::is_whitespace) && rest.trim_start().starts_with("mut")
+        })
+}
+
+/// Whether a fn's name is called after its `fn`, as a recursive fn's is,
+/// including calls of other fns of the same name
+fn might_recurse(content: &str) -> bool {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    content.match_indices("fn ").any(|(start, _)| {
+        let rest = content[start + "fn ".len()..].trim_start();
+        let name_len = rest.find(|c| !is_ident(c)).unwrap_or(rest.len());
+        let (name, rest) = rest.split_at(name_len);
+        !name.is_empty()
+            && rest.match_indices(name).any(|(at, _)| {
+                let after = rest[at + name.len()..].trim_start();
+                !rest[..at].ends_with(is_ident)
+                    && (after.starts_with('(') || after.starts_with("::<"))
+            })
+    })
+}
+
+/// Count a file's lines, fns and statements without parsing it
+///
+/// This is approximate: fns are lines starting with `fn` after any qualifiers,
+/// which includes methods, and statements are semicolons.
+fn estimate_stats(content: &str) -> CodeStats {
+    let code_lines = || {
+        content
+            .lines()
+            .map(str::trim_start)
+            .filter(|line| !line.starts_with("//"))
+    };
+    CodeStats {
+        total_lines: content.lines().count() as isize,
+        total_fns: code_lines()
+            .filter(|line| {
+                line.split_whitespace()
+                    .find(|word| {
+                        !word.starts_with("pub")
+                            && !matches!(*word, "const" | "async" | "extern" | "\"C\"")
+                    })
+                    .is_some_and(|word| word == "fn")
+            })
+            .count() as isize,
+        total_statements: code_lines()
+            .map(|line| line.matches(';').count())
+            .sum::<usize>() as isize,
+        ..CodeStats::default()
+    }
+}
+
+/// Analyze a file, counting the uses of the `deprecated` items of its crate
+fn analyze_file(
+    path: &Path,
+    prefilter: bool,
+    deprecated: &BTreeSet<String>,
+) -> Option<(CodeStats, Vec<Site>)> {
+    let start = std::time::Instant::now();
+    let content = std::fs::read_to_string(path)
+        .inspect_err(|err| tracing::info!("skipping {}: {err}", path.display()))
+        .ok()?;
+    if prefilter && !might_contribute(&content) && !deprecated::is_mentioned(deprecated, &content) {
+        tracing::debug!("estimated {}, it has nothing to report", path.display());
+        return Some((estimate_stats(&content), Vec::new()));
+    }
+    let syntax = syn::parse_file(&content)
+        .inspect_err(|err| {
+            let location = err.span().start();
+            tracing::info!(
+                "skipping {}:{}:{}: failed to parse: {err}",
+                path.display(),
+                location.line,
+                location.column + 1
+            );
+        })
+        .ok()?;
+    let parsed = std::time::Instant::now();
+    let mut analysis = analyze_syntax(&content, &syntax, Some(path));
+    deprecated::count_uses(deprecated, &content, &syntax, &mut analysis);
+    timings::record_file(path, parsed - start, parsed.elapsed());
+    tracing::debug!("analyzed {} in {:?}", path.display(), start.elapsed());
+    Some(analysis)
+}
+
+/// Analyze source without a file, for its unsafe code and unwraps
+///
+/// Uses of deprecated items aren't counted, see [`analyze_source_with`].
+fn analyze_source_sites(content: &str) -> Option<(CodeStats, Vec<Site>)> {
+    analyze_source_with(content, &BTreeSet::new())
+}
+
+/// Analyze source without a file, counting the uses of the `deprecated` items
+/// of its crate
+fn analyze_source_with(
+    content: &str,
+    deprecated: &BTreeSet<String>,
+) -> Option<(CodeStats, Vec<Site>)> {
+    let syntax = syn::parse_file(content).ok()?;
+    let mut analysis = analyze_syntax(content, &syntax, None);
+    deprecated::count_uses(deprecated, content, &syntax, &mut analysis);
+    Some(analysis)
+}
+
+/// Use `/` as the path separator on every platform, so reports and baselines
+/// from Linux CI and Windows machines have the same keys
+fn normalize_path(path: &str) -> String {
+    path.replace('\\', "/")
+}
+
+/// Whether `--files` or `--files-from` name the files to analyze
+fn has_explicit_files(args: &Args) -> bool {
+    !args.files.is_empty() || args.files_from.is_some()
+}
+
+/// The files to analyze: those given with `--files` and `--files-from`, or
+/// every rust file under the crate root, only in the `--package`s if given
+/// and not in the `--exclude`d ones
+fn input_files(args: &Args) -> Box<dyn Iterator<Item = PathBuf> + '_> {
+    let files = given_or_walked_files(args);
+    let Some(is_selected) = package_selection(args) else {
+        return files;
+    };
+    Box::new(files.filter(move |path| {
+        let filename = relative_filename(&args.crate_root, path);
+        if !is_selected(&filename) {
+            tracing::info!("skipping {filename}: not in a selected package");
+            return false;
+        }
+        true
+    }))
+}
+
+/// Whether a file, by its name relative to the crate root, is in the
+/// `--package`s if given and not in the `--exclude`d ones, `None` when every
+/// package is selected
+fn package_selection(args: &Args) -> Option<impl Fn(&str) -> bool + '_> {
+    if args.package.is_empty() && args.exclude.is_empty() {
+        return None;
+    }
+
+    let packages = packages::Packages::find(&args.crate_root);
+    let names = packages.names();
+    if let Some(unknown) = args
+        .package
+        .iter()
+        .chain(&args.exclude)
+        .find(|spec| !names.contains(&spec.as_str()))
+    {
+        eprintln!(
+            "Error: package '{unknown}' not found under '{}', the packages are: {}",
+            args.crate_root,
+            names.join(", ")
+        );
+        std::process::exit(EXIT_ERROR);
+    }
+    Some(move |filename: &str| {
+        let package = packages
+            .package_of(filename)
+            .unwrap_or(packages::UNPACKAGED);
+        let selected = args.package.is_empty() || args.package.iter().any(|spec| spec == package);
+        selected && !args.exclude.iter().any(|spec| spec == package)
+    })
+}
+
+fn given_or_walked_files(args: &Args) -> Box<dyn Iterator<Item = PathBuf> + '_> {
+    if !has_explicit_files(args) {
+        return Box::new(rust_files(&args.crate_root).map(walkdir::DirEntry::into_path));
+    }
+    Box::new(given_files(args).iter().cloned())
+}
+
+/// The files given with `--files` and `--files-from`
+///
+/// `--files-from -` reads stdin, so the list is only read the first time.
+fn given_files(args: &Args) -> &'static [PathBuf] {
+    static GIVEN_FILES: OnceLock<Vec<PathBuf>> = OnceLock::new();
+    GIVEN_FILES.get_or_init(|| read_given_files(args))
+}
+
+fn read_given_files(args: &Args) -> Vec<PathBuf> {
+    let listed = args.files_from.as_ref().map(|list| {
+        let content = if list == "-" {
+            std::io::read_to_string(std::io::stdin())
+        } else {
+            std::fs::read_to_string(list)
+        };
+        content.unwrap_or_else(|err| {
+            eprintln!("Error: failed to read file list '{list}': {err}");
+            std::process::exit(EXIT_ERROR);
+        })
+    });
+    let files: Vec<PathBuf> = args
+        .files
+        .iter()
+        .map(String::as_str)
+        .chain(listed.iter().flat_map(|list| list.lines()))
+        .map(str::trim)
+        .filter(|file| !file.is_empty())
+        .map(PathBuf::from)
+        .collect();
+    if let Some(missing) = files.iter().find(|file| !file.is_file()) {
+        eprintln!("Error: no such file '{}'", missing.display());
+        std::process::exit(EXIT_ERROR);
+    }
+    files
+}
+
+/// A file's name in the report, relative to the crate root when it's inside it
+fn relative_filename(root: &str, path: &Path) -> String {
+    normalize_path(
+        &path
+            .strip_prefix(root)
+            .unwrap_or(path)
+            .display()
+            .to_string(),
+    )
+}
+
+/// Every `.rs` file under `root`, skipping `target` directories
+fn rust_files(root: impl AsRef<Path>) -> impl Iterator<Item = walkdir::DirEntry> {
+    WalkDir::new(root)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| {
+            let is_target = e.file_name().to_str() == Some("target");
+            if is_target {
+                tracing::info!("skipping {}: build output directory", e.path().display());
+            }
+            !is_target
+        })
+        .filter_map(|e| e.inspect_err(|err| tracing::info!("skipping: {err}")).ok())
+        .filter(|e| e.path().extension().map(|ext| ext == "rs").unwrap_or(false))
+        .inspect(|e| tracing::debug!("found {}", e.path().display()))
+}
+
+/// Parse `paths` for the `--*-candidates` detectors, by their name in the
+/// report, skipping files which don't parse
+fn parse_files(root: &str, paths: impl Iterator<Item = PathBuf>) -> Vec<(String, syn::File)> {
+    let mut files: Vec<(String, syn::File)> = paths
+        .filter_map(|path| {
+            let content = std::fs::read_to_string(&path).ok()?;
+            let syntax = syn::parse_file(&content)
+                .inspect_err(|err| tracing::info!("skipping {}: {err}", path.display()))
+                .ok()?;
+            Some((relative_filename(root, &path), syntax))
+        })
+        .collect();
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+    files
+}
+
+/// The files a `--*-candidates` detector looks at, the same ones which would
+/// be analyzed
+fn candidate_files(args: &Args) -> Vec<(String, syn::File)> {
+    parse_files(&args.crate_root, input_files(args))
+}
+
+/// A suggestion of one of the `--*-candidates` detectors
+trait Suggestion {
+    /// what the suggestion is about, usually the fn it's in
+    fn subject(&self) -> String;
+    fn line_number(&self) -> usize;
+    fn confidence(&self) -> Confidence;
+    /// what's suggested, listed after the location
+    fn detail(&self) -> Option<String>;
+}
+
+/// Print a detector's suggestions by file after the `heuristic` it uses, or
+/// that there are no candidates for `subject`
+fn print_candidates<S: Suggestion>(
+    files: impl Iterator<Item = (String, Vec<S>)>,
+    heuristic: &str,
+    subject: &str,
+) {
+    let files: Vec<(String, Vec<S>)> = files.collect();
+    if files.is_empty() {
+        println!("No candidates found for {subject} using a simple heuristic.");
+        return;
+    }
+
+    println!("These candidates are chosen using a very simple heuristic.\n{heuristic}\n");
+    for (filename, candidates) in &files {
+        println!("{filename}:");
+        for candidate in candidates {
+            let location = format!(
+                "{} @ {filename}:{} ({})",
+                candidate.subject(),
+                candidate.line_number(),
+                candidate.confidence()
+            );
+            match candidate.detail() {
+                Some(detail) => println!("\t{location}: {detail}"),
+                None => println!("\t{location}"),
+            }
+        }
+    }
+    let candidates_count: usize = files.iter().map(|(_, candidates)| candidates.len()).sum();
+    println!(
+        "\nFound {candidates_count} candidate{} in {} file{}",
+        if candidates_count == 1 { "" } else { "s" },
+        files.len(),
+        if files.len() == 1 { "" } else { "s" },
+    );
+}
+
+/// Analyze files one at a time, writing each file's JSON line or CSV row as
+/// soon as it's done
+///
+/// Unlike `generate_report` only the running total is kept in memory, so rows
+/// come out in directory walk order rather than sorted by filename.
+fn stream_report<W>(args: &Args, mut out: W)
+where
+    W: std::io::Write,
+{
+    let is_csv = matches!(args.format, OutputFormat::Csv);
+    if is_csv {
+        _ = writeln!(out, "{CSV_SCHEMA_PREFIX}{BASELINE_SCHEMA_VERSION}");
+        _ = write!(
+            out,
+            "{}",
+            format_csv_line((CodeStats::csv_headers(), CodeStats::COMPUTED_CSV_HEADERS))
+        );
+    }
+
+    // deprecated items can be used from any file
+    let deprecated = deprecated::definitions_in(input_files(args));
+    let mut total = CodeStats::default();
+    for path in input_files(args) {
+        let Some((stats, _)) = analyze_file(&path, args.prefilter, &deprecated) else {
+            continue;
+        };
+        let filename = relative_filename(&args.crate_root, &path);
+        if is_csv {
+            _ = write!(
+                out,
+                "{}",
+                format_csv_line((stats.to_csv_row(filename), stats.computed_csv_row()))
+            );
+        } else {
+            _ = writeln!(out, "{}", format_jsonl_line(&filename, &stats));
+        }
+        _ = out.flush();
+        total = [total, stats].into_iter().sum();
+    }
+
+    if is_csv {
+        _ = write!(
+            out,
+            "{}",
+            format_csv_line((
+                total.to_csv_row(CSV_TOTAL_ROW.to_string()),
+                total.computed_csv_row()
+            ))
+        );
+    }
+}
+
+/// A single CSV record, with its trailing newline
+fn format_csv_line(record: impl serde::Serialize) -> String {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    _ = writer.serialize(record);
+    String::from_utf8(writer.into_inner().unwrap()).unwrap()
+}
+
+/// One file's stats as a single line JSON object
+fn format_jsonl_line(filename: &str, stats: &CodeStats) -> String {
+    #[derive(serde::Serialize)]
+    struct Line<'a> {
+        filename: &'a str,
+        #[serde(flatten)]
+        stats: &'a CodeStats,
+    }
+    serde_json::to_string(&Line { filename, stats }).unwrap()
+}
+
+/// Don't draw a progress bar for trees which are analyzed in the blink of an eye
+const PROGRESS_MIN_FILES: usize = 200;
+
+/// Draw a progress bar only for interactive runs, it'd garble logs and pipes
+fn show_progress(args: &Args) -> bool {
+    !args.quiet
+        && args.verbose == 0
+        && std::io::stderr().is_terminal()
+        && (args.output.is_some() || std::io::stdout().is_terminal())
+}
+
+/// Analyze every Rust file under `crate_root`, like running `crate-report`
+/// there without options
+pub fn analyze_crate(crate_root: impl AsRef<Path>) -> Report {
+    let crate_root = crate_root.as_ref().to_string_lossy();
+    let file_paths: Vec<PathBuf> = rust_files(crate_root.as_ref())
+        .map(walkdir::DirEntry::into_path)
+        .collect();
+    let deprecated = deprecated::definitions_in(&file_paths);
+    let analyzed = analyze_paths(&crate_root, &file_paths, false, &deprecated, || {});
+    Report::from_analyzed(analyzed)
+}
+
+/// Analyze each Rust file under `crate_root` in directory walk order, passing
+/// its filename and metrics to `f` as soon as it's analyzed
+///
+/// Nothing is kept in memory, and returning [`ControlFlow::Break`] from `f`
+/// stops without analyzing the remaining files:
+///
+/// ```no_run
+/// use std::ops::ControlFlow;
+///
+/// let first_unsafe = crate_report::analyze_crate_with(".", |filename, stats| {
+///     if stats.unsafe_statements > 0 {
+///         ControlFlow::Break(filename.to_string())
+///     } else {
+///         ControlFlow::Continue(())
+///     }
+/// });
+/// ```
+pub fn analyze_crate_with<B>(
+    crate_root: impl AsRef<Path>,
+    mut f: impl FnMut(&str, CodeStats) -> ControlFlow<B>,
+) -> ControlFlow<B> {
+    let crate_root = crate_root.as_ref();
+    let deprecated =
+        deprecated::definitions_in(rust_files(crate_root).map(walkdir::DirEntry::into_path));
+    for entry in rust_files(crate_root) {
+        let Some((stats, _)) = analyze_file(entry.path(), false, &deprecated) else {
+            continue;
+        };
+        f(
+            &relative_filename(&crate_root.to_string_lossy(), entry.path()),
+            stats,
+        )?;
+    }
+    ControlFlow::Continue(())
+}
+
+/// Read a report written with `--format csv` or `--format json`, from a file
+/// or an http(s) URL, e.g. to [`Report::diff`] against
+pub fn load_report(path: &str) -> Result<Report, String> {
+    read_baseline(path, None).and_then(|content| Report::load_baseline(path, &content))
+}
+
+/// Analyze files in parallel (with the `rayon` feature), calling `on_analyzed`
+/// after each one
+fn analyze_paths(
+    crate_root: &str,
+    file_paths: &[PathBuf],
+    prefilter: bool,
+    deprecated: &BTreeSet<String>,
+    on_analyzed: impl Fn() + Sync,
+) -> BTreeMap<String, (CodeStats, Vec<Site>)> {
+    let analyze_path = |path: &PathBuf| {
+        let analysis = analyze_file(path, prefilter, deprecated);
+        on_analyzed();
+        Some((relative_filename(crate_root, path), analysis?))
+    };
+
+    #[cfg(feature = "rayon")]
+    use rayon::prelude::*;
+    #[cfg(feature = "rayon")]
+    return file_paths.par_iter().flat_map(analyze_path).collect();
+
+    #[cfg(not(feature = "rayon"))]
+    return file_paths.iter().flat_map(analyze_path).collect();
+}
+
+fn generate_report(args: &Args) -> Report {
+    let start = std::time::Instant::now();
+    let file_paths: Vec<_> = timings::phase("walk", || input_files(args).collect());
+    tracing::info!(
+        "found {} rust files in {:?}",
+        file_paths.len(),
+        start.elapsed()
+    );
+
+    let progress = if show_progress(args) && file_paths.len() >= PROGRESS_MIN_FILES {
+        indicatif::ProgressBar::new(file_paths.len() as u64).with_style(
+            indicatif::ProgressStyle::with_template(
+                "{bar:40} {pos}/{len} files analyzed, ETA {eta}",
+            )
+            .expect("valid progress template"),
+        )
+    } else {
+        indicatif::ProgressBar::hidden()
+    };
+
+    let deprecated = timings::phase("deprecated", || deprecated::definitions_in(&file_paths));
+    let mut analyzed = timings::phase("analyze", || {
+        analyze_paths(
+            &args.crate_root,
+            &file_paths,
+            args.prefilter,
+            &deprecated,
+            || progress.inc(1),
+        )
+    });
+    if args.count_candidates {
+        timings::phase("candidates", || {
+            let files = parse_files(&args.crate_root, file_paths.iter().cloned());
+            candidate_counts::count(&files, args.min_confidence, &mut analyzed)
+        });
+    }
+
+    progress.finish_and_clear();
+    tracing::info!(
+        "analyzed {} of {} files in {:?}",
+        analyzed.len(),
+        file_paths.len(),
+        start.elapsed()
+    );
+
+    let report = timings::phase("aggregate", || Report::from_analyzed(analyzed));
+    Report {
+        min_confidence: args.count_candidates.then_some(args.min_confidence),
+        ..report
+    }
+}
+
+/// Analyze Rust source read from stdin as a single file named `--filename`
+fn stdin_report(args: &Args) -> Report {
+    if args.baseline.iter().any(|baseline| baseline == "-") {
+        eprintln!("Error: --stdin and --baseline - can't both read from stdin");
+        std::process::exit(EXIT_ERROR);
+    }
+
+    let filename = args.filename.as_deref().unwrap_or(STDIN_FILENAME);
+    let content = std::io::read_to_string(std::io::stdin()).unwrap_or_else(|err| {
+        eprintln!("Error: failed to read stdin: {err}");
+        std::process::exit(EXIT_ERROR);
+    });
+    let syntax = syn::parse_file(&content).unwrap_or_else(|err| {
+        let location = err.span().start();
+        eprintln!(
+            "Error: failed to parse {filename}:{}:{}: {err}",
+            location.line,
+            location.column + 1
+        );
+        std::process::exit(EXIT_ERROR);
+    });
+
+    // the crate's other files can define deprecated items the input uses
+    let mut deprecated = deprecated::definitions_in(input_files(args));
+    deprecated.extend(deprecated::definitions([content.as_str()]));
+    let mut analysis = analyze_syntax(&content, &syntax, Some(Path::new(filename)));
+    deprecated::count_uses(&deprecated, &content, &syntax, &mut analysis);
+    Report::from_analyzed(BTreeMap::from([(normalize_path(filename), analysis)]))
+}
+
+/// How a decrease in a metric is colored in diffs
+#[derive(Clone, Copy, Debug, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum DecreaseIs {
+    /// decreases are green and increases red, e.g. unsafe fns
+    Good,
+    /// decreases are red and increases green, e.g. a test count
+    Bad,
+    Neutral,
+}
+
+impl DecreaseIs {
+    /// Whether a metric moved in the bad direction
+    fn is_regression(self, before: isize, after: isize) -> bool {
+        match self {
+            DecreaseIs::Good => after > before,
+            DecreaseIs::Bad => after < before,
+            DecreaseIs::Neutral => false,
+        }
+    }
+
+    fn color(self, delta: isize) -> Color {
+        match (self, delta.cmp(&0)) {
+            (_, cmp::Ordering::Equal) | (DecreaseIs::Neutral, _) => Color::BrightBlack,
+            (DecreaseIs::Good, cmp::Ordering::Less) | (DecreaseIs::Bad, cmp::Ordering::Greater) => {
+                Color::Green
+            }
+            (DecreaseIs::Good, cmp::Ordering::Greater) | (DecreaseIs::Bad, cmp::Ordering::Less) => {
+                Color::Red
+            }
+        }
+    }
+}
+fn format_diff(old: isize, new: isize, decrease_is: DecreaseIs) -> String {
+    let delta = new - old;
+
+    if delta == 0 {
+        return format!("{old} (no change)")
+            .color(Color::BrightBlack)
+            .to_string();
+    }
+
+    let plus = if delta > 0 { "+" } else { "" };
+    let color = decrease_is.color(delta);
+
+    format!("{old} -> {new} ({plus}{delta})")
+        .color(color)
+        .to_string()
+}
+
+/// Like [`format_diff`] for a ratio, to two decimal places
+fn format_density_diff(change: Change<f64>, unit: &str, decrease_is: DecreaseIs) -> String {
+    let Change { before, after } = change;
+    let Some(delta) = format_density_delta(change) else {
+        return format!("{before:.2}{unit} (no change)")
+            .color(Color::BrightBlack)
+            .to_string();
+    };
+
+    let color = decrease_is.color(if after > before { 1 } else { -1 });
+    format!("{before:.2}{unit} -> {after:.2}{unit} ({delta})")
+        .color(color)
+        .to_string()
+}
+
+fn format_unsafe_fn_change(unsafe_fn: Change<isize>, total_fn: Change<isize>) -> String {
+    let unsafe_lines_changed = unsafe_fn.after - unsafe_fn.before;
+    let total_lines_changed = total_fn.after - total_fn.before;
+
+    if unsafe_lines_changed == 0 && total_lines_changed == 0 {
+        return format!("{}/{} (no change)", unsafe_fn.after, total_fn.after)
+            .color(Color::White)
+            .to_string();
+    }
+
+    let sign = match unsafe_lines_changed.cmp(&0) {
+        cmp::Ordering::Less => "-",
+        cmp::Ordering::Greater => "+",
+        cmp::Ordering::Equal => "",
+    };
+    let color = match unsafe_lines_changed {
+        0 => Color::White,
+        delta => config::decrease_is("unsafe_fns").color(delta),
+    };
+
+    format!(
+        "{}/{} -> {}/{} ({sign}{})",
+        unsafe_fn.before,
+        total_fn.before,
+        unsafe_fn.after,
+        total_fn.after,
+        unsafe_lines_changed.abs()
+    )
+    .color(color)
+    .to_string()
+}
+
+/// The display text for an optional coverage percentage
+fn format_coverage(percent: Option<f64>) -> String {
+    percent
+        .map(|percent| format!("{percent:.1}%"))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn style_filename(filename: &str, stats: &CodeStats) -> ColoredString {
+    let filename = match config::max_path_width() {
+        Some(max_width) => truncate_middle(filename, max_width),
+        None => filename.to_string(),
+    };
+    if stats.is_perfect() {
+        filename.color(Color::Green)
+    } else {
+        filename.into()
+    }
+}
+
+/// A markdown heading styled by the `[markdown]` config, `style` unless the
+/// config chooses one
+///
+/// `level` is relative to the report's top headings, which are at the
+/// configured `heading_level`. Setext headings only exist for the first two
+/// levels, deeper ones are always ATX.
+fn markdown_heading(title: &str, level: u8, style: config::HeadingStyle) -> String {
+    let markdown = config::markdown();
+    let level = (markdown.heading_level.unwrap_or(1).clamp(1, 6) + level - 1).min(6);
+    match markdown.headings.unwrap_or(style) {
+        config::HeadingStyle::Setext if level <= 2 => {
+            let underline = if level == 1 { "=" } else { "-" };
+            format!("{title}\n{}", underline.repeat(title.chars().count()))
+        }
+        _ => format!("{} {title}", "#".repeat(level.into())),
+    }
+}
+
+/// An arrow between before and after values, `->` when the `[markdown]`
+/// config asks for ASCII
+fn markdown_arrow() -> &'static str {
+    if config::markdown().ascii {
+        "->"
+    } else {
+        "→"
+    }
+}
+
+/// `text` as a markdown link to `url`, or as is without one
+fn markdown_link(text: impl std::fmt::Display, url: Option<String>) -> String {
+    match url {
+        Some(url) => format!("[{text}]({url})"),
+        None => text.to_string(),
+    }
+}
+
+/// The `#fragment` GitHub gives a heading: lowercased, punctuation other than
+/// `-` and `_` dropped and spaces turned into `-`
+fn markdown_anchor(heading: &str) -> String {
+    let anchor: String = heading
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | ' '))
+        .map(|c| if c == ' ' { '-' } else { c })
+        .collect();
+    format!("#{anchor}")
+}
+
+/// `count` as a percentage of `total`, zero when there's nothing to count
+fn percentage(count: isize, total: isize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        (count as f64 / total as f64) * 100.0
+    }
+}
+
+fn level_color(level: config::Level) -> Color {
+    match level {
+        config::Level::Good => Color::Green,
+        config::Level::Warning => Color::Yellow,
+        config::Level::Danger => Color::Red,
+    }
+}
+
+/// The color of an unsafe fn ratio, grey when there are no fns at all
+fn ratio_color(unsafe_count: isize, total_count: isize) -> Color {
+    if total_count == 0 {
+        Color::BrightBlack
+    } else {
+        level_color(config::threshold("unsafe_fns").level(percentage(unsafe_count, total_count)))
+    }
+}
+
+fn colorize_percentage(unsafe_count: isize, total_count: isize) -> ColoredString {
+    let color = ratio_color(unsafe_count, total_count);
+
+    let percentage = percentage(unsafe_count, total_count);
+
+    format!("{percentage:.02}% ({unsafe_count} / {total_count})").color(color)
+}
+
+fn colorize_ratio(unsafe_count: isize, total_count: isize) -> ColoredString {
+    format!("{unsafe_count}/{total_count}").color(ratio_color(unsafe_count, total_count))
+}
+
+/// colorize by the threshold, by default zero is green, single digit is yellow, more then that is red
+fn colorize_simple(count: isize, threshold: config::Threshold) -> ColoredString {
+    count
+        .to_string()
+        .color(level_color(threshold.level(count as f64)))
+}
+
+/// Parse the command line and run crate-report, exiting on errors
+///
+/// Embedders call this from their own `main` after registering any extra
+/// metrics with [`plugin::register`].
+pub fn run() {
+    let mut args = Args::parse();
+    // a report with a date or a link to HEAD is out of date once either moves
+    if args.check.is_some() {
+        args.deterministic = true;
+    }
+
+    colored::control::set_override(should_colorize(&args));
+    init_logging(args.verbose);
+    init_thread_pool(args.jobs);
+    if args.timings {
+        timings::enable();
+    }
+
+    let mut config =
+        config::Config::load(&args.crate_root, args.config.as_deref()).unwrap_or_else(|err| {
+            eprintln!("Error: failed to load config: {err}");
+            std::process::exit(EXIT_ERROR);
+        });
+    config.thresholds.extend(args.threshold.iter().cloned());
+    if args.max_path_width.is_some() {
+        config.max_path_width = args.max_path_width;
+    }
+    if args.report_all_metrics {
+        config.report_changes = ["total_fns", "total_statements", "total_lines"]
+            .map(String::from)
+            .to_vec();
+    }
+    for (metric, patterns) in &config.patterns {
+        if CodeStats::csv_headers().contains(metric) {
+            eprintln!("Error: pattern metric '{metric}' is already a metric");
+            std::process::exit(EXIT_ERROR);
+        }
+        let counter = patterns::PatternCounter::new(metric, patterns).unwrap_or_else(|err| {
+            eprintln!("Error: {err}");
+            std::process::exit(EXIT_ERROR);
+        });
+        plugin::register(counter);
+    }
+    if args.doc_coverage {
+        plugin::register(doc_coverage::DocCoverage);
+    }
+    if args.count_candidates {
+        plugin::register(candidate_counts::CandidateCounts);
+    }
+    // plugin metrics are only known once registered
+    let known: Vec<String> = explain::METRICS
+        .iter()
+        .map(|(name, _)| name.to_string())
+        .chain(plugin::metrics())
+        .collect();
+    let unknown = (args.threshold.iter())
+        .map(|(metric, _)| ("--threshold", metric))
+        .chain(
+            config
+                .thresholds
+                .keys()
+                .map(|metric| ("[thresholds]", metric)),
+        )
+        .chain(
+            config
+                .directions
+                .keys()
+                .map(|metric| ("[directions]", metric)),
+        )
+        .chain(config.severity.keys().map(|metric| ("[severity]", metric)))
+        .chain(
+            config
+                .report_changes
+                .iter()
+                .map(|metric| ("report_changes", metric)),
+        )
+        .find(|(_, metric)| !known.contains(metric));
+    if let Some((source, metric)) = unknown {
+        eprintln!(
+            "Error: unknown metric '{metric}' in {source}, expected one of {}",
+            known.join(", ")
+        );
+        std::process::exit(EXIT_ERROR);
+    }
+    config::set(config);
+
+    // these don't analyze anything, so work outside of a crate too
+    match &args.command {
+        Some(Command::Completions { shell }) => {
+            clap_complete::generate(
+                *shell,
+                &mut Args::command(),
+                "crate-report",
+                &mut std::io::stdout(),
+            );
+            return;
+        }
+        Some(Command::Explain { metric }) => {
+            format_explain(metric.as_deref());
+            return;
+        }
+        // each crate root is checked for a Cargo.toml instead
+        Some(Command::Compare {
+            crates,
+            sort,
+            format,
+        }) => {
+            format_compare(&args, crates, sort, format);
+            return;
+        }
+        Some(Command::Merge { reports, format }) => {
+            format_merge(&args, reports, format);
+            return;
+        }
+        _ => {}
+    }
+
+    // Sanity check: ensure Cargo.toml exists in the crate root
+    let crate_root_path = Path::new(&args.crate_root);
+    let cargo_toml_path = crate_root_path.join("Cargo.toml");
+    if !args.stdin && !has_explicit_files(&args) && !cargo_toml_path.exists() {
+        let mut cmd = Args::command();
+        let expanded_path = crate_root_path
+            .canonicalize()
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|_| args.crate_root.clone());
+        eprintln!("Error: No Cargo.toml found in '{}'", expanded_path);
+        eprintln!("Please specify a valid Rust crate directory.");
+        eprintln!();
+        _ = cmd.print_help();
+        std::process::exit(EXIT_ERROR);
+    }
+
+    if args.post_azure_comment && !matches!(args.format, OutputFormat::AzurePrComment) {
+        eprintln!("Error: --post-azure-comment needs --format azure-pr-comment");
+        std::process::exit(EXIT_ERROR);
+    }
+    if args.post_gitea_comment && !matches!(args.format, OutputFormat::PrComment) {
+        eprintln!("Error: --post-gitea-comment needs --format pr-comment");
+        std::process::exit(EXIT_ERROR);
+    }
+    if matches!(args.format, OutputFormat::DiffJson | OutputFormat::DiffCsv)
+        && args.baseline.is_empty()
+    {
+        eprintln!("Error: --format diff-json and diff-csv need a --baseline");
+        std::process::exit(EXIT_ERROR);
+    }
+    if args.files_from.as_deref() == Some("-")
+        && args.baseline.iter().any(|baseline| baseline == "-")
+    {
+        eprintln!("Error: --files-from - and --baseline - can't both read from stdin");
+        std::process::exit(EXIT_ERROR);
+    }
+    if streams(&args) && (!args.baseline.is_empty() || args.history.is_some()) {
+        eprintln!(
+            "Error: --baseline and --history need the whole report, they can't be used with --stream or --format jsonl"
+        );
+        std::process::exit(EXIT_ERROR);
+    }
+    // some detectors match things up across files, so they're only counted
+    // once the whole crate is parsed
+    if args.count_candidates
+        && (args.stream
+            || args.stdin
+            || matches!(args.format, OutputFormat::Jsonl)
+            || matches!(
+                args.command,
+                Some(
+                    Command::History { .. }
+                        | Command::Bisect { .. }
+                        | Command::PreCommit
+                        | Command::Compare { .. }
+                )
+            ))
+    {
+        eprintln!(
+            "Error: --count-candidates needs the whole crate, it can't be used with --stream, --stdin, --format jsonl, history, bisect, pre-commit or compare"
+        );
+        std::process::exit(EXIT_ERROR);
+    }
+
+    if matches!(
+        args.format,
+        OutputFormat::Html
+            | OutputFormat::Markdown
+            | OutputFormat::PrComment
+            | OutputFormat::AzurePrComment
+    ) {
+        links::init(
+            &args.crate_root,
+            args.repo_url.as_deref(),
+            args.git_ref.as_deref(),
+            args.deterministic,
+        );
+    }
+
+    if let Some(command) = &args.command {
+        run_command(command, &args);
+        return;
+    }
+
+    if args.safe_candidates {
+        let stats = safe_candidates::find_candidates(&candidate_files(&args), args.min_confidence);
+        print_candidates(
+            stats
+                .into_iter()
+                .map(|file| (file.filename, file.stats.candidates)),
+            "If a function is unsafe and has no raw pointers as parameters, it may be a good candidate for making safe.\n\
+             Note that there may be other reasons why these functions shouldn't be converted.",
+            "functions to convert from unsafe to safe",
+        );
+        return;
+    }
+
+    if args.bool_candidates {
+        let stats = bool_candidates::find_candidates(&candidate_files(&args), args.min_confidence);
+        print_candidates(
+            stats
+                .into_iter()
+                .map(|file| (file.filename, file.stats.candidates)),
+            "If a function returns i32 and all return statements return literal 0 or 1 values, it may be a good candidate for converting to return bool.\n\
+             Note that there may be other reasons why these functions shouldn't be converted.",
+            "functions to convert from i32 to bool",
+        );
+        return;
+    }
+
+    if args.get_candidates {
+        let stats = get_candidates::find_candidates(&candidate_files(&args), args.min_confidence);
+        print_candidates(
+            stats
+                .into_iter()
+                .map(|file| (file.filename, file.stats.candidates)),
+            "If indexing is in a function returning Option or Result, it may be a good candidate for `get` with `?` instead of panicking.\n\
+             Note that a Result needs an error for the missing element, written as `.ok_or(...)` below.",
+            "indexing to convert to get",
+        );
+        return;
+    }
+
+    if args.iterator_candidates {
+        let stats =
+            iterator_candidates::find_candidates(&candidate_files(&args), args.min_confidence);
+        print_candidates(
+            stats
+                .into_iter()
+                .map(|file| (file.filename, file.stats.candidates)),
+            "If a loop walks an index and uses it to index something, it may be a good candidate for an iterator, which also avoids the bounds checks.\n\
+             Note that loops which stop before the end, modify the index or read elements at other offsets may not convert directly.",
+            "index loops to convert to iterators",
+        );
+        return;
+    }
+
+    if args.drop_candidates {
+        let stats = drop_candidates::find_candidates(&candidate_files(&args), args.min_confidence);
+        print_candidates(
+            stats
+                .into_iter()
+                .map(|file| (file.filename, file.stats.candidates)),
+            "If a type is freed by a *_free or *_destroy function called from several places, or a struct's field is passed to free, it may be a good candidate for a Drop implementation.\n\
+             Note that types handed back to C, or freed by C code, may need to keep being freed explicitly.",
+            "types to free with a Drop implementation",
+        );
+        return;
+    }
+
+    if args.vec_candidates {
+        let stats = vec_candidates::find_candidates(&candidate_files(&args), args.min_confidence);
+        print_candidates(
+            stats
+                .into_iter()
+                .map(|file| (file.filename, file.stats.candidates)),
+            "If a buffer from malloc, calloc or realloc is reallocated or indexed, it may be a good candidate for converting to a Vec.\n\
+             Note that buffers shared with C code need to keep using its allocator.",
+            "C buffers to convert to Vec",
+        );
+        return;
+    }
+
+    if args.string_candidates {
+        let stats =
+            string_candidates::find_candidates(&candidate_files(&args), args.min_confidence);
+        print_candidates(
+            stats
+                .into_iter()
+                .map(|file| (file.filename, file.stats.candidates)),
+            "If a function builds a C string with strcpy, strcat, snprintf and the like, it may be a good candidate for building a String with push_str or format! instead.\n\
+             Note that the buffer may still need converting to a CString where it's passed to C code.",
+            "C string building to convert to String",
+        );
+        return;
+    }
+
+    if args.ref_candidates {
+        let stats = ref_candidates::find_candidates(&candidate_files(&args), args.min_confidence);
+        print_candidates(
+            stats
+                .into_iter()
+                .map(|file| (file.filename, file.stats.candidates)),
+            "If an unsafe function only dereferences a raw pointer parameter, it may be a good candidate for taking a reference instead.\n\
+             Note that for functions called from C, a reference only asserts the pointer is valid and aligned, it doesn't check it.",
+            "pointer parameters to convert to references",
+        );
+        return;
+    }
+
+    if args.context_candidates {
+        let stats =
+            context_candidates::find_candidates(&candidate_files(&args), args.min_confidence);
+        print_candidates(
+            stats
+                .into_iter()
+                .map(|file| (file.filename, file.stats.candidates)),
+            "If several static mut items are used by the same functions, they may be a good candidate for the fields of a context struct passed to those functions by reference.\n\
+             Note that the functions' callers need the context too, so it usually spreads further than listed.",
+            "static mut items to bundle into a context struct",
+        );
+        return;
+    }
+
+    if args.once_candidates {
+        let stats = once_candidates::find_candidates(&candidate_files(&args), args.min_confidence);
+        print_candidates(
+            stats
+                .into_iter()
+                .map(|file| (file.filename, file.stats.candidates)),
+            "If a static mut item is written at most once and read elsewhere, it may be a good candidate for a OnceLock, a LazyLock or a plain static.\n\
+             Note that reads before the write see the initial value, which a OnceLock makes explicit as None.",
+            "static mut items to convert to OnceLock, LazyLock or static",
+        );
+        return;
+    }
+
+    if streams(&args) {
+        if let Some(output_file) = &args.output {
+            let file = std::fs::File::create(output_file).unwrap();
+            timings::phase("analyze", || {
+                stream_report(&args, std::io::BufWriter::new(file))
+            });
+        } else {
+            timings::phase("analyze", || stream_report(&args, std::io::stdout().lock()));
+        }
+        timings::print();
+        return;
+    }
+
+    let mut report = if args.stdin {
+        stdin_report(&args)
+    } else {
+        generate_report(&args)
+    };
+    if let Some(history_file) = &args.history
+        && let Err(err) = history::append(
+            history_file,
+            history::HistoryEntry::now(report.total.clone()),
+        )
+    {
+        eprintln!("Error: failed to update history '{history_file}': {err}");
+        std::process::exit(EXIT_ERROR);
+    }
+
+    if let Some(coverage_file) = &args.coverage {
+        match coverage::load(coverage_file, report.files.keys()) {
+            Ok(coverage) => report.coverage = coverage,
+            Err(err) => {
+                eprintln!("Error: failed to load coverage from '{coverage_file}': {err}");
+                std::process::exit(EXIT_ERROR);
+            }
+        }
+    }
+
+    if let Some(clippy_file) = &args.clippy_json {
+        match clippy::load(clippy_file, report.files.keys()) {
+            Ok(lints) => report.set_clippy_lints(lints),
+            Err(err) => {
+                eprintln!("Error: failed to load clippy output from '{clippy_file}': {err}");
+                std::process::exit(EXIT_ERROR);
+            }
+        }
+    }
+
+    let baselines = load_baselines(&args);
+
+    if let Some(check_file) = &args.check {
+        check_report(&report, &baselines, &args, check_file);
+        return;
+    }
+
+    // Handle output based on format
+    if args.post_azure_comment {
+        let output_content = timings::phase("render", || render_report(&report, &baselines, &args));
+        if let Err(err) = azure::post_comment(&output_content) {
+            eprintln!("Error: failed to post the Azure DevOps comment: {err}");
+            std::process::exit(EXIT_ERROR);
+        }
+    } else if args.post_gitea_comment {
+        let output_content = timings::phase("render", || render_report(&report, &baselines, &args));
+        if let Err(err) = gitea::post_comment(&output_content) {
+            eprintln!("Error: failed to post the Gitea comment: {err}");
+            std::process::exit(EXIT_ERROR);
+        }
+    } else if let Some(output_file) = &args.output {
+        let output_content = timings::phase("render", || render_report(&report, &baselines, &args));
+        std::fs::write(output_file, output_content).unwrap();
+    } else if !args.quiet {
+        let output_content = timings::phase("render", || render_report(&report, &baselines, &args));
+        match args.format {
+            OutputFormat::Html => {
+                println!();
+                print!("{output_content}");
+            }
+            OutputFormat::Markdown => println!("\n{output_content}"),
+            _ => print!("{output_content}"),
+        }
+    }
+    timings::print();
+
+    if args.github_check
+        && let Err(err) = github::create_check_run(&report, &baselines, &args)
+    {
+        eprintln!("Error: failed to create the GitHub check run: {err}");
+        std::process::exit(EXIT_ERROR);
+    }
+
+    if let Err(err) = export::push(&args.crate_root, &report) {
+        eprintln!("Error: failed to export metrics: {err}");
+        std::process::exit(EXIT_ERROR);
+    }
+
+    if let Some(webhook) = &args.notify_webhook
+        && let Err(err) = notify::post(webhook, &report, &baselines, &args)
+    {
+        eprintln!("Error: failed to post to --notify-webhook: {err}");
+        std::process::exit(EXIT_ERROR);
+    }
+
+    if args.quiet {
+        match severity::assess(&severity::findings(&report, &baselines)) {
+            severity::Severity::Fail => std::process::exit(EXIT_VIOLATION),
+            severity::Severity::Warn => std::process::exit(EXIT_WARNING),
+            severity::Severity::Info => {}
+        }
+    }
+}
+
+/// Whether rows are written as files are analyzed without building the report,
+/// unless it needs to be compared with `--check` or `--quiet`
+fn streams(args: &Args) -> bool {
+    let streams = match args.format {
+        OutputFormat::Jsonl => true,
+        OutputFormat::Csv => args.stream,
+        _ => false,
+    };
+    streams && args.command.is_none() && !args.stdin && args.check.is_none() && !args.quiet
+}
+
+/// Whether to emit ANSI colors: `--color` if given, otherwise only when
+/// writing to a terminal and `NO_COLOR` isn't set
+fn should_colorize(args: &Args) -> bool {
+    if args.deterministic {
+        return false;
+    }
+    match args.color {
+        ColorMode::Always => true,
+        ColorMode::Never => false,
+        ColorMode::Auto => {
+            std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+                && args.output.is_none()
+                && std::io::stdout().is_terminal()
+        }
+    }
+}
+
+/// Log to stderr, nothing by default, `-v` for info and `-vv` for debug
+fn init_logging(verbose: u8) {
+    let level = match verbose {
+        0 => return,
+        1 => tracing::Level::INFO,
+        _ => tracing::Level::DEBUG,
+    };
+    tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_target(false)
+        .with_ansi(std::io::stderr().is_terminal())
+        .with_writer(std::io::stderr)
+        .init();
+}
+
+/// Bound the threads used for analysis, rayon uses one per CPU by default
+fn init_thread_pool(jobs: Option<std::num::NonZeroUsize>) {
+    #[cfg(feature = "rayon")]
+    if let Some(jobs) = jobs {
+        _ = rayon::ThreadPoolBuilder::new()
+            .num_threads(jobs.get())
+            .build_global();
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    if jobs.is_some_and(|jobs| jobs.get() > 1) {
+        tracing::warn!("--jobs has no effect, crate-report was built without the rayon feature");
+    }
+}
+
+/// Render the report without colors, e.g. for comparing with `--check`
+fn format_report(report: &Report, baselines: &[(String, Report)], args: &Args) -> String {
+    colored::control::set_override(false);
+    let output_content = render_report(report, baselines, args);
+    colored::control::set_override(should_colorize(args));
+    output_content
+}
+
+fn render_report(report: &Report, baselines: &[(String, Report)], args: &Args) -> String {
+    match args.format {
+        OutputFormat::Csv => format_csv_report(report),
+        OutputFormat::Json => format_json_report(report, baselines, args),
+        OutputFormat::Jsonl if args.stdin => report
+            .files
+            .iter()
+            .map(|(filename, stats)| format_jsonl_line(filename, stats) + "\n")
+            .collect(),
+        OutputFormat::Jsonl => {
+            let mut out = Vec::new();
+            stream_report(args, &mut out);
+            String::from_utf8(out).unwrap()
+        }
+        OutputFormat::Html => html::format_html_report(report, baselines, args),
+        OutputFormat::BitbucketInsights => {
+            bitbucket::format_bitbucket_insights(report, baselines, args)
+        }
+        OutputFormat::Sonar => sonar::format_sonar_report(report, args),
+        OutputFormat::Teamcity => teamcity::format_teamcity_messages(report, baselines, args),
+        OutputFormat::Markdown => format_markdown_report(
+            report,
+            &packages::Packages::find(&args.crate_root),
+            baselines,
+            args,
+        ),
+        OutputFormat::Table => format_table_report(
+            report,
+            &packages::Packages::find(&args.crate_root),
+            baselines,
+            args,
+        ),
+        OutputFormat::PrComment | OutputFormat::AzurePrComment => {
+            format_pr_comment_report(report, baselines, args)
+        }
+        OutputFormat::DiffJson => diff_formats::format_diff_json(report, baselines, args),
+        OutputFormat::DiffCsv => diff_formats::format_diff_csv(report, baselines, args),
+    }
+}
+
+/// [`Report::diff`] for showing, which rolls moved code into a note with
+/// `--suppress-churn` and lists the unchanged files too with `--show-unchanged`
+fn display_diff(report: &Report, baseline: &Report, args: &Args) -> DiffReport {
+    let mut diff = report.diff(baseline);
+    if args.suppress_churn {
+        let deprecated = match args.base_rev {
+            Some(_) => deprecated::definitions_in(input_files(args)),
+            None => BTreeSet::new(),
+        };
+        // the sites a file gained and lost since `--base-rev`, by kind and
+        // enclosing item
+        let moved_sites = |filename: &str| {
+            let before = base_sites(args, &deprecated, filename)?;
+            let after = report.sites.get(filename).cloned().unwrap_or_default();
+            let keys = |sites: Vec<Site>| {
+                let mut keys: Vec<(SiteKind, String)> = sites
+                    .into_iter()
+                    .map(|site| (site.kind, site.name))
+                    .collect();
+                keys.sort();
+                keys
+            };
+            Some((
+                keys(precommit::added_sites(before.clone(), after.clone())),
+                keys(precommit::added_sites(after, before)),
+            ))
+        };
+        diff = diff.without_churn(|filename, other| {
+            match (moved_sites(filename), moved_sites(other)) {
+                (Some((gained, lost)), Some((other_gained, other_lost))) => {
+                    (!gained.is_empty() || !lost.is_empty())
+                        && gained == other_lost
+                        && lost == other_gained
+                }
+                _ => false,
+            }
+        });
+    }
+    if args.show_unchanged {
+        diff = diff.with_unchanged(report);
+    }
+    diff
+}
+
+/// Whether `--quiet` should exit with [`EXIT_VIOLATION`]: a regression against
+/// the first baseline, or a file at a configured danger threshold
+fn has_violation(report: &Report, baselines: &[(String, Report)]) -> bool {
+    severity::assess(&severity::findings(report, baselines)) == severity::Severity::Fail
+}
+
+/// Compare a committed report with a freshly generated one, exiting with 1 if they differ
+fn check_report(report: &Report, baselines: &[(String, Report)], args: &Args, check_file: &str) {
+    let committed = std::fs::read_to_string(check_file).unwrap_or_default();
+    let generated = format_report(report, baselines, args);
+
+    if committed == generated {
+        if !args.quiet {
+            println!("{check_file} is up to date");
+        }
+        return;
+    }
+    if args.quiet {
+        std::process::exit(EXIT_VIOLATION);
+    }
+
+    let diff = similar::TextDiff::from_lines(&committed, &generated);
+    print!(
+        "{}",
+        diff.unified_diff()
+            .header(check_file, &format!("{check_file} (generated)"))
+    );
+    eprintln!(
+        "Error: {check_file} is out of date, regenerate it with the same options and --deterministic"
+    );
+    std::process::exit(EXIT_VIOLATION);
+}
+
+/// The report as JSON, with the diff against the first baseline if given
+fn format_json_report(report: &Report, baselines: &[(String, Report)], args: &Args) -> String {
+    #[derive(serde::Serialize)]
+    struct JsonReport<'a> {
+        #[serde(flatten)]
+        report: &'a Report,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        diff: Option<DiffReport>,
+    }
+
+    let diff = baselines
+        .first()
+        .map(|(_, baseline)| display_diff(report, baseline, args));
+    serde_json::to_string_pretty(&Versioned::new(JsonReport { report, diff })).unwrap() + "\n"
+}
+
+fn format_csv_report(report: &Report) -> String {
+    let mut header = format!("{CSV_SCHEMA_PREFIX}{BASELINE_SCHEMA_VERSION}\n");
+    if let Some(min_confidence) = report.min_confidence {
+        header.push_str(&format!("{CSV_MIN_CONFIDENCE_PREFIX}{min_confidence}\n"));
+    }
+    let mut writer = csv::WriterBuilder::new().from_writer(header.into_bytes());
+
+    _ = writer.serialize((CodeStats::csv_headers(), CodeStats::COMPUTED_CSV_HEADERS));
+    for (filename, code_stats) in report.files.iter() {
+        _ = writer.serialize((
+            code_stats.to_csv_row(filename.to_string()),
+            code_stats.computed_csv_row(),
+        ));
+    }
+    _ = writer.serialize((
+        report.total.to_csv_row(CSV_TOTAL_ROW.to_string()),
+        report.total.computed_csv_row(),
+    ));
+
+    String::from_utf8(writer.into_inner().unwrap()).unwrap()
+}
+
+fn run_command(command: &Command, args: &Args) {
+    match command {
+        Command::Todo => {
+            let output_file = args.output.as_deref().unwrap_or("UNSAFE_TODO.md");
+            let existing = std::fs::read_to_string(output_file).ok();
+            let report = generate_report(args);
+            let content = todo::render(&args.crate_root, &report, existing.as_deref());
+            std::fs::write(output_file, content).unwrap();
+        }
+        Command::Bisect {
+            metric,
+            from,
+            to,
+            threshold,
+        } => {
+            if CodeStats::default().metric(metric).is_none() {
+                eprintln!("Error: unknown metric '{metric}'");
+                std::process::exit(EXIT_ERROR);
+            }
+            let outcome = bisect::bisect(&args.crate_root, metric, from, to, *threshold);
+            format_bisect(&args.crate_root, metric, &outcome);
+        }
+        Command::Completions { .. }
+        | Command::Explain { .. }
+        | Command::Compare { .. }
+        | Command::Merge { .. } => {
+            unreachable!("handled before the Cargo.toml check")
+        }
+        Command::Init { force } => {
+            let baseline = format_csv_report(&generate_report(args));
+            if let Err(err) = init::init(&args.crate_root, &baseline, *force) {
+                eprintln!("Error: failed to initialize: {err}");
+                std::process::exit(EXIT_ERROR);
+            }
+        }
+        Command::PreCommit => {
+            let violations = precommit::check(&args.crate_root);
+            if violations.is_empty() {
+                return;
+            }
+            for violation in &violations {
+                println!(
+                    "{}:{}: new {}",
+                    violation.filename, violation.line, violation.description
+                );
+            }
+            eprintln!(
+                "Error: {} new unsafe sites or unwraps staged, allow them with a `// {}` comment",
+                violations.len(),
+                precommit::SUPPRESSION
+            );
+            std::process::exit(EXIT_VIOLATION);
+        }
+        Command::QuestionMark { apply } => {
+            let census = question_mark::census(&args.crate_root, &generate_report(args));
+            for (filename, candidates) in &census {
+                for candidate in candidates {
+                    println!(
+                        "{filename}:{}: `{}` in `{}` returning {}",
+                        candidate.line,
+                        candidate.method,
+                        candidate.fn_name,
+                        candidate.returns.name()
+                    );
+                }
+            }
+            if *apply {
+                let applied =
+                    question_mark::apply(&args.crate_root, &census).unwrap_or_else(|err| {
+                        eprintln!("Error: failed to rewrite: {err}");
+                        std::process::exit(EXIT_ERROR);
+                    });
+                eprintln!("Rewrote {applied} unwraps to `?`, check them with `cargo check`");
+            }
+        }
+        Command::ImportGeiger {
+            history_file,
+            geiger_files,
+        } => {
+            let Some(package) = metadata::package(&args.crate_root) else {
+                eprintln!("Error: no package in the Cargo.toml at the crate root");
+                std::process::exit(EXIT_ERROR);
+            };
+            for geiger_file in geiger_files {
+                let entry =
+                    geiger::history_entry(geiger_file, &package.name).unwrap_or_else(|err| {
+                        eprintln!(
+                            "Error: failed to import cargo-geiger output from '{geiger_file}': {err}"
+                        );
+                        std::process::exit(EXIT_ERROR);
+                    });
+                if let Err(err) = history::append(history_file, entry) {
+                    eprintln!("Error: failed to update history '{history_file}': {err}");
+                    std::process::exit(EXIT_ERROR);
+                }
+            }
+        }
+        Command::Trend {
+            history_file,
+            last,
+            days,
+        } => {
+            let history = history::load(history_file).unwrap_or_else(|err| {
+                eprintln!("Error: failed to load history '{history_file}': {err}");
+                std::process::exit(EXIT_ERROR);
+            });
+            format_trend(trend::window(&history, *last, *days));
+        }
+        Command::History {
+            range,
+            step,
+            format,
+        } => {
+            let entries = history::backfill(&args.crate_root, range, *step);
+            let output_content = match format {
+                SeriesFormat::Csv => {
+                    let mut out = Vec::new();
+                    history::write_csv(&mut out, &entries).unwrap();
+                    String::from_utf8(out).unwrap()
+                }
+                SeriesFormat::Json => history::to_json(&entries) + "\n",
+            };
+            if let Some(output_file) = &args.output {
+                std::fs::write(output_file, output_content).unwrap();
+            } else {
+                print!("{output_content}");
+            }
+        }
+    }
+}
+
+fn format_compare(args: &Args, crates: &[String], sort: &str, format: &MatrixFormat) {
+    if !compare::is_metric(sort) {
+        eprintln!("Error: unknown metric '{sort}'");
+        std::process::exit(EXIT_ERROR);
+    }
+    let mut rows = compare::rows(&args.crate_root, crates).unwrap_or_else(|err| {
+        eprintln!("Error: {err}");
+        std::process::exit(EXIT_ERROR);
+    });
+    compare::rank(&mut rows, sort);
+
+    let mut out = Vec::new();
+    match format {
+        MatrixFormat::Csv => compare::write_csv(&mut out, &rows).unwrap(),
+        MatrixFormat::Markdown => compare::to_table(&rows).to_markdown(&mut out),
+        MatrixFormat::Table => compare::to_table(&rows).to_terminal(&mut out),
+    }
+    let output_content = String::from_utf8(out).unwrap();
+    if let Some(output_file) = &args.output {
+        std::fs::write(output_file, output_content).unwrap();
+    } else {
+        print!("{output_content}");
+    }
+}
+
+fn format_merge(args: &Args, reports: &[String], format: &MergeFormat) {
+    let (report, packages) = merge::merge(reports).unwrap_or_else(|err| {
+        eprintln!("Error: {err}");
+        std::process::exit(EXIT_ERROR);
+    });
+    let baselines = load_baselines(args);
+    let output_content = match format {
+        MergeFormat::Csv => format_csv_report(&report),
+        MergeFormat::Json => format_json_report(&report, &baselines, args),
+        MergeFormat::Markdown => format_markdown_report(&report, &packages, &baselines, args),
+        MergeFormat::Table => format_table_report(&report, &packages, &baselines, args),
+    };
+    if let Some(output_file) = &args.output {
+        std::fs::write(output_file, output_content).unwrap();
+    } else {
+        print!("{output_content}");
+    }
+}
+
+fn format_trend(history: &[history::HistoryEntry]) {
+    let (Some(first), Some(latest)) = (history.first(), history.last()) else {
+        println!("No history to chart");
+        return;
+    };
+
+    println!(
+        "Trend over {} runs ({} to {})\n",
+        history.len(),
+        history::format_date(first.timestamp),
+        history::format_date(latest.timestamp)
+    );
+    for metric in CodeStats::csv_headers().iter().skip(1) {
+        let values: Vec<isize> = history
+            .iter()
+            .map(|entry| entry.totals.metric(metric).unwrap_or_default())
+            .collect();
+        println!(
+            "{metric:<17} {}  {}",
+            trend::sparkline(&values),
+            format_diff(
+                values[0],
+                values[values.len() - 1],
+                config::decrease_is(metric)
+            )
+        );
+    }
+}
+
+fn format_explain(metric: Option<&str>) {
+    let metrics: Vec<&str> = match metric {
+        Some(metric) if explain::explanation(metric).is_none() => {
+            let known: Vec<&str> = explain::METRICS.iter().map(|(name, _)| *name).collect();
+            eprintln!(
+                "Error: unknown metric '{metric}', expected one of {}",
+                known.join(", ")
+            );
+            std::process::exit(EXIT_ERROR);
+        }
+        Some(metric) => vec![metric],
+        None => explain::METRICS.iter().map(|(name, _)| *name).collect(),
+    };
+
+    for (i, metric) in metrics.into_iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        println!("{}", metric.bold());
+        println!("{}", explain::explanation(metric).unwrap_or_default());
+        println!();
+        let decrease = match config::decrease_is(metric) {
+            DecreaseIs::Good => "a decrease is good (green) and an increase bad (red)",
+            DecreaseIs::Bad => "a decrease is bad (red) and an increase good (green)",
+            DecreaseIs::Neutral => "changes are neutral (not colored)",
+        };
+        println!("In diffs {decrease}, configurable under [directions].");
+        if !metric.starts_with("total_") {
+            let threshold = config::threshold(metric);
+            let unit = if metric == "unsafe_fns" { "%" } else { "" };
+            println!(
+                "Values above {}{unit} are yellow and from {}{unit} red, configurable under [thresholds.{metric}].",
+                threshold.warning, threshold.danger
+            );
+        }
+    }
+}
+
+fn format_bisect(crate_root: &str, metric: &str, outcome: &bisect::Outcome) {
+    let Some(culprit) = &outcome.culprit else {
+        println!(
+            "{metric} never exceeds {} in {}",
+            outcome.threshold, outcome.range
+        );
+        return;
+    };
+
+    println!(
+        "{} is the first commit where {metric} exceeds {}",
+        culprit.commit, outcome.threshold
+    );
+    if let Some(subject) = git::subject(crate_root, &culprit.commit) {
+        println!("{subject}");
+    }
+    println!(
+        "{metric}: {}",
+        format_diff(culprit.before, culprit.after, config::decrease_is(metric))
+    );
+}
+
+/// The totals and a box-drawing table of every file, for interactive use
+const TABLE_SUMMARY_HEADING: &str = "Summary\n=======";
+
+fn format_table_report(
+    report: &Report,
+    packages: &packages::Packages,
+    baselines: &[(String, Report)],
+    args: &Args,
+) -> String {
+    let mut out = Vec::<u8>::new();
+
+    let CodeStats {
+        total_lines,
+        unsafe_statements,
+        static_mut_items,
+        unwraps,
+        ..
+    } = report.total;
+    out.extend(
+        format!(
+            "Total lines             : {total_lines}
+Total unsafe functions  : {}
+Total unsafe statements : {unsafe_statements}
+Total static mut items  : {static_mut_items}
+Total unwrap calls      : {unwraps}
+{}
+",
+            colorize_percentage(report.total.unsafe_fns, report.total.total_fns),
+            report
+                .total
+                .sparse_metrics()
+                .map(|sparse| sparse.metric.to_string())
+                .chain(plugin::metrics())
+                .map(|metric| format!(
+                    "Total {metric:<18}: {}\n",
+                    report.total.metric(&metric).unwrap_or_default()
+                ))
+                .collect::<String>()
+        )
+        .bytes(),
+    );
+    if packages.is_workspace() && !args.summary {
+        report
+            .to_packages_table(packages, false)
+            .to_terminal(&mut out);
+        out.extend("\n".bytes());
+    }
+    if let Some((_, old_report)) = baselines.first() {
+        let diff = display_diff(report, old_report, args);
+        if args.summary {
+            diff.summary_display(TABLE_SUMMARY_HEADING, &mut out);
+        } else {
+            report.to_table(false).to_terminal(&mut out);
+            out.extend("\n".bytes());
+            diff.color_display(TABLE_SUMMARY_HEADING, &mut out);
+        }
+    } else if !args.summary {
+        report.to_table(false).to_terminal(&mut out);
+    }
+
+    String::from_utf8(out).unwrap()
+}
+
+/// A table of the workspace's packages, then a section per package with its
+/// files' table
+fn format_markdown_packages(report: &Report, packages: &packages::Packages, out: &mut Vec<u8>) {
+    report
+        .to_packages_table(packages, true)
+        .to_markdown(&mut *out);
+    for package in packages.group(&report.files).into_keys() {
+        out.extend(
+            format!(
+                "\n{}\n\n",
+                markdown_heading(&package, 2, config::HeadingStyle::Setext)
+            )
+            .bytes(),
+        );
+        report
+            .subset(|filename| {
+                packages
+                    .package_of(filename)
+                    .unwrap_or(packages::UNPACKAGED)
+                    == package
+            })
+            .to_table(true)
+            .to_markdown(&mut *out);
+    }
+}
+
+fn format_markdown_report(
+    report: &Report,
+    packages: &packages::Packages,
+    baselines: &[(String, Report)],
+    args: &Args,
+) -> String {
+    let mut out = Vec::<u8>::new();
+
+    let CodeStats {
+        total_lines,
+        unsafe_statements,
+        static_mut_items,
+        unwraps,
+        ..
+    } = report.total;
+    out.extend(
+        format!(
+            "{}
+_{}_
+
+- Total lines: {total_lines}
+- Total unsafe functions: {}
+- Total statements in unsafe blocks: {unsafe_statements}
+- Total static mut items: {static_mut_items}
+- Total unwrap calls: {unwraps}
+{}
+",
+            markdown_heading("Code Report", 1, config::HeadingStyle::Setext),
+            metadata::Metadata::collect(&args.crate_root, args.deterministic),
+            colorize_percentage(report.total.unsafe_fns, report.total.total_fns),
+            report
+                .total
+                .sparse_metrics()
+                .map(|sparse| format!(
+                    "- Total {}: {}\n",
+                    sparse.label,
+                    report.total.metric(sparse.metric).unwrap_or_default()
+                ))
+                .chain(plugin::metrics().iter().map(|metric| format!(
+                    "- Total {metric}: {}\n",
+                    report.total.metric(metric).unwrap_or_default()
+                )))
+                .collect::<String>()
+        )
+        .bytes(),
+    );
+    if !args.summary {
+        if packages.is_workspace() {
+            format_markdown_packages(report, packages, &mut out);
+        } else {
+            report.to_table(true).to_markdown(&mut out);
+        }
+    }
+
+    if baselines.len() > 1 {
+        out.extend(
+            format!(
+                "\nCompared to baselines: {}\n",
+                format_baseline_comparison(report, baselines)
+            )
+            .bytes(),
+        );
+    }
+
+    if args.summary {
+        if let Some((_, old_report)) = baselines.first() {
+            report.diff(old_report).summary_display(
+                &markdown_heading("Summary", 1, config::HeadingStyle::Setext),
+                &mut out,
+            );
+        }
+        return String::from_utf8(out).unwrap();
+    }
+
+    if let Some((_, old_report)) = baselines.first() {
+        out.extend("\n\n".bytes());
+        display_diff(report, old_report, args).color_display(
+            &markdown_heading("Summary", 1, config::HeadingStyle::Setext),
+            &mut out,
+        );
+    }
+
+    if args.list_unsafe_fns {
+        out.extend("\n".bytes());
+        report.unsafe_fn_list(&mut out);
+    }
+
+    if !report.coverage.is_empty() {
+        out.extend("\n".bytes());
+        format_least_covered(report, &mut out);
+    }
+
+    if let Some(geiger_file) = &args.geiger {
+        out.extend("\n".bytes());
+        let packages = load_geiger(geiger_file);
+        format_geiger(report, &packages, &args.crate_root, &mut out);
+    }
+
+    if args.migration {
+        out.extend("\n".bytes());
+        format_migration(report, args, &mut out);
+    }
+
+    if let Some(owners) = load_codeowners(args) {
+        out.extend(
+            format!(
+                "\n{}\n\n",
+                markdown_heading("Owners", 1, config::HeadingStyle::Setext)
+            )
+            .bytes(),
+        );
+        Report::from_files(owners.group(&report.files))
+            .to_table(false)
+            .to_markdown(&mut out);
+    }
+
+    if let Some(range) = &args.leaderboard {
+        out.extend("\n".bytes());
+        let contributors = leaderboard::rank_contributors(&args.crate_root, range);
+        format_leaderboard(&contributors, &mut out);
+    }
+
+    if args.blame {
+        out.extend("\n".bytes());
+        let authors = blame::attribute_sites(&args.crate_root, report);
+        format_blame(&authors, &mut out);
+    }
+
+    if args.reachability {
+        out.extend("\n".bytes());
+        let entry_points = reachability::find_unsafe_entry_points(&args.crate_root);
+        format_entry_points(&entry_points, &mut out);
+    }
+
+    if args.untested_unsafe {
+        out.extend("\n".bytes());
+        let untested = untested::find_untested_unsafe_fns(&args.crate_root, report);
+        format_untested_unsafe_fns(&untested, &mut out);
+    }
+
+    if args.unwrap_contexts {
+        out.extend("\n".bytes());
+        format_unwrap_contexts(&unwrap_contexts::census(&args.crate_root, report), &mut out);
+    }
+
+    if args.audit_next {
+        out.extend("\n".bytes());
+        format_audit_next(&risk::rank(&args.crate_root, report), &mut out);
+    }
+
+    if args.libc_calls {
+        out.extend("\n".bytes());
+        format_libc_calls(&libc_calls::census(&args.crate_root, report), &mut out);
+    }
+
+    if args.allows {
+        out.extend("\n".bytes());
+        format_allows(&allows::census(&args.crate_root, report), &mut out);
+    }
+
+    if args.concurrency {
+        out.extend("\n".bytes());
+        format_concurrency(&concurrency::census(&args.crate_root, report), &mut out);
+    }
+
+    if args.panic_sites {
+        out.extend("\n".bytes());
+        format_panic_sites(&panic_sites::census(&args.crate_root, report), &mut out);
+    }
+
+    for kind in args.locations.iter().copied().collect::<BTreeSet<_>>() {
+        out.extend("\n".bytes());
+        format_locations(report, kind, &mut out);
+    }
+
+    out.extend(
+        "\nGenerated by [crate-report](https://github.com/richardscollin/crate-report)\n".bytes(),
+    );
+    String::from_utf8(out).unwrap()
+}
+
+/// How many unsafe fns the least-covered section lists
+const LEAST_COVERED_LIMIT: usize = 10;
+
+fn format_least_covered<W>(report: &Report, mut out: W)
+where
+    W: std::io::Write,
+{
+    _ = writeln!(
+        out,
+        "{}",
+        markdown_heading("Least-Covered Unsafe Code", 1, config::HeadingStyle::Setext)
+    );
+    for (filename, site, percent) in report
+        .least_covered_unsafe_fns()
+        .into_iter()
+        .take(LEAST_COVERED_LIMIT)
+    {
+        _ = writeln!(
+            out,
+            "- `{}` {} ({percent:.1}%)",
+            site.name,
+            markdown_link(
+                format!("{filename}:{}", site.line),
+                links::line_url(filename, site.line)
+            )
+        );
+    }
+}
+
+/// How many files the audit next section lists
+const AUDIT_NEXT_LIMIT: usize = 10;
+
+fn format_audit_next<W>(ranked: &[risk::FileRisk], mut out: W)
+where
+    W: std::io::Write,
+{
+    _ = writeln!(
+        out,
+        "{}\n",
+        markdown_heading("Audit Next", 1, config::HeadingStyle::Setext)
+    );
+    if ranked.is_empty() {
+        _ = writeln!(out, "No files with unsafe code");
+        return;
+    }
+
+    let mut table = Table::with_headers(vec![
+        "file".into(),
+        "risk".into(),
+        "unsafe stmts".into(),
+        "static mut".into(),
+        "unchecked calls".into(),
+        "no SAFETY comment".into(),
+        "coverage".into(),
+    ]);
+    table.extend_rows(ranked.iter().take(AUDIT_NEXT_LIMIT).map(|file| {
+        vec![
+            markdown_link(&file.filename, links::file_url(&file.filename)).into(),
+            format!("{:.1}", file.score).into(),
+            file.unsafe_statements.to_string().into(),
+            file.static_mut_items.to_string().into(),
+            file.unchecked_calls.to_string().into(),
+            file.missing_safety_comments.to_string().into(),
+            file.coverage
+                .map_or("-".to_string(), |percent| format!("{percent:.1}%"))
+                .into(),
+        ]
+    }));
+    table.to_markdown(&mut out);
+}
+
+fn format_libc_calls<W>(census: &BTreeMap<String, libc_calls::Counts>, mut out: W)
+where
+    W: std::io::Write,
+{
+    _ = writeln!(
+        out,
+        "{}\n",
+        markdown_heading("libc Calls", 1, config::HeadingStyle::Setext)
+    );
+    if census.is_empty() {
+        _ = writeln!(out, "No direct libc calls");
+        return;
+    }
+
+    let calls: usize = census.values().flat_map(|counts| counts.values()).sum();
+    let distinct: BTreeSet<&String> = census.values().flat_map(|counts| counts.keys()).collect();
+    _ = writeln!(
+        out,
+        "{calls} calls to {} distinct libc functions\n",
+        distinct.len()
+    );
+    let mut table = Table::with_headers(vec!["file".into(), "function".into(), "calls".into()]);
+    table.extend_rows(census.iter().flat_map(|(filename, counts)| {
+        counts.iter().map(move |(function, count)| {
+            vec![
+                markdown_link(filename, links::file_url(filename)).into(),
+                format!("`libc::{function}`").into(),
+                count.to_string().into(),
+            ]
+        })
+    }));
+    table.to_markdown(&mut out);
+}
+
+fn format_unwrap_contexts<W>(census: &BTreeMap<String, unwrap_contexts::Contexts>, mut out: W)
+where
+    W: std::io::Write,
+{
+    _ = writeln!(
+        out,
+        "{}\n",
+        markdown_heading("Unwraps by Context", 1, config::HeadingStyle::Setext)
+    );
+    if census.is_empty() {
+        _ = writeln!(out, "No unwraps");
+        return;
+    }
+
+    let unwraps: usize = census.values().map(unwrap_contexts::Contexts::total).sum();
+    let library: usize = census.values().map(|contexts| contexts.library).sum();
+    _ = writeln!(out, "{library} of {unwraps} unwraps are in library code\n");
+    let mut table = Table::with_headers(vec![
+        "file".into(),
+        "library".into(),
+        "main()".into(),
+        "tests".into(),
+        "examples".into(),
+        "build script".into(),
+    ]);
+    table.extend_rows(census.iter().map(|(filename, contexts)| {
+        vec![
+            markdown_link(filename, links::file_url(filename)).into(),
+            contexts.library.to_string().into(),
+            contexts.main.to_string().into(),
+            contexts.tests.to_string().into(),
+            contexts.examples.to_string().into(),
+            contexts.build_scripts.to_string().into(),
+        ]
+    }));
+    table.to_markdown(&mut out);
+}
+
+fn format_allows<W>(census: &BTreeMap<String, allows::Counts>, mut out: W)
+where
+    W: std::io::Write,
+{
+    _ = writeln!(
+        out,
+        "{}\n",
+        markdown_heading("Allowed Lints", 1, config::HeadingStyle::Setext)
+    );
+    if census.is_empty() {
+        _ = writeln!(out, "No #[allow(...)] attributes");
+        return;
+    }
+
+    let allows: usize = census.values().flat_map(|counts| counts.values()).sum();
+    let distinct: BTreeSet<&String> = census.values().flat_map(|counts| counts.keys()).collect();
+    _ = writeln!(
+        out,
+        "{allows} allows of {} distinct lints\n",
+        distinct.len()
+    );
+    let mut table = Table::with_headers(vec!["file".into(), "lint".into(), "allows".into()]);
+    table.extend_rows(census.iter().flat_map(|(filename, counts)| {
+        counts.iter().map(move |(lint, count)| {
+            vec![
+                markdown_link(filename, links::file_url(filename)).into(),
+                format!("`{lint}`").into(),
+                count.to_string().into(),
+            ]
+        })
+    }));
+    table.to_markdown(&mut out);
+}
+
+fn format_concurrency<W>(census: &BTreeMap<String, concurrency::Surface>, mut out: W)
+where
+    W: std::io::Write,
+{
+    _ = writeln!(
+        out,
+        "{}\n",
+        markdown_heading("Concurrency Surface", 1, config::HeadingStyle::Setext)
+    );
+    if census.is_empty() {
+        _ = writeln!(out, "No locks, atomics, spawned threads or UnsafeCells");
+        return;
+    }
+
+    let primitives: usize = census.values().map(concurrency::Surface::total).sum();
+    _ = writeln!(
+        out,
+        "{primitives} concurrency primitives in {} file{}\n",
+        census.len(),
+        if census.len() == 1 { "" } else { "s" },
+    );
+    let mut table = Table::with_headers(vec![
+        "file".into(),
+        "Mutex".into(),
+        "RwLock".into(),
+        "Condvar".into(),
+        "atomics".into(),
+        "spawns".into(),
+        "UnsafeCell".into(),
+    ]);
+    table.extend_rows(census.iter().map(|(filename, surface)| {
+        vec![
+            markdown_link(filename, links::file_url(filename)).into(),
+            surface.mutexes.to_string().into(),
+            surface.rw_locks.to_string().into(),
+            surface.condvars.to_string().into(),
+            surface.atomics.to_string().into(),
+            surface.spawns.to_string().into(),
+            surface.unsafe_cells.to_string().into(),
+        ]
+    }));
+    table.to_markdown(&mut out);
+}
+
+fn format_panic_sites<W>(census: &BTreeMap<String, Vec<panic_sites::PanicSite>>, mut out: W)
+where
+    W: std::io::Write,
+{
+    _ = writeln!(
+        out,
+        "{}\n",
+        markdown_heading("Panic Sites", 1, config::HeadingStyle::Setext)
+    );
+    if census.is_empty() {
+        _ = writeln!(out, "No potential panics");
+        return;
+    }
+
+    let sites: usize = census.values().map(Vec::len).sum();
+    _ = writeln!(
+        out,
+        "{sites} potential panics in {} file{}\n",
+        census.len(),
+        if census.len() == 1 { "" } else { "s" },
+    );
+    let mut table = Table::with_headers(vec!["location".into(), "site".into()]);
+    table.extend_rows(census.iter().flat_map(|(filename, sites)| {
+        sites.iter().map(move |site| {
+            vec![
+                markdown_link(
+                    format!("{filename}:{}", site.line),
+                    links::line_url(filename, site.line),
+                )
+                .into(),
+                site.kind.to_string().into(),
+            ]
+        })
+    }));
+    table.to_markdown(&mut out);
+}
+
+/// Every site of a kind, in filename and line order
+fn locations(report: &Report, kind: LocationKind) -> Vec<(&String, &Site)> {
+    report
+        .sites
+        .iter()
+        .flat_map(|(filename, sites)| sites.iter().map(move |site| (filename, site)))
+        .filter(|(_, site)| kind.includes(site.kind))
+        .collect()
+}
+
+fn format_locations<W>(report: &Report, kind: LocationKind, mut out: W)
+where
+    W: std::io::Write,
+{
+    _ = writeln!(
+        out,
+        "{}\n",
+        markdown_heading(kind.title(), 1, config::HeadingStyle::Setext)
+    );
+    let locations = locations(report, kind);
+    if locations.is_empty() {
+        _ = writeln!(out, "None");
+        return;
+    }
+
+    let mut table = Table::with_headers(vec!["location".into(), "site".into()]);
+    table.extend_rows(locations.into_iter().map(|(filename, site)| {
+        vec![
+            markdown_link(
+                format!("{filename}:{}", site.line),
+                links::line_url(filename, site.line),
+            )
+            .into(),
+            precommit::describe(site).into(),
+        ]
+    }));
+    table.to_markdown(&mut out);
+}
+
+/// The estimated completion date from the history file, if there is a downward trend
+fn migration_eta(args: &Args) -> Option<String> {
+    let history = history::load(args.history.as_ref()?).ok()?;
+    migration::estimate_completion(&history).map(history::format_date)
+}
+
+fn format_migration<W>(report: &Report, args: &Args, mut out: W)
+where
+    W: std::io::Write,
+{
+    let overall = migration::Progress::new("total".to_string(), &report.total);
+    _ = writeln!(
+        out,
+        "{}
+- Complete: {:.1}% ({} unsafe fns and static mut items remaining)",
+        markdown_heading("Migration Progress", 1, config::HeadingStyle::Setext),
+        overall.percent_complete(),
+        overall.remaining,
+    );
+    // the date moves with every run appended to the history
+    if !args.deterministic {
+        _ = writeln!(
+            out,
+            "- Estimated completion: {}",
+            migration_eta(args)
+                .unwrap_or_else(|| "unknown (needs a downward trend in --history)".into())
+        );
+    }
+    _ = writeln!(out);
+
+    let mut table = Table::with_headers(vec![
+        "directory".into(),
+        "complete".into(),
+        "remaining".into(),
+    ]);
+    table.extend_rows(
+        migration::per_directory(report)
+            .into_iter()
+            .map(|progress| {
+                let color = if progress.remaining == 0 {
+                    Color::Green
+                } else {
+                    Color::Yellow
+                };
+                vec![
+                    progress.name.as_str().into(),
+                    format!("{:.1}%", progress.percent_complete()).color(color),
+                    progress.remaining.to_string().into(),
+                ]
+            }),
+    );
+    table.to_markdown(&mut out);
+}
+
+/// Load CODEOWNERS when grouping by owner is requested
+fn load_codeowners(args: &Args) -> Option<codeowners::CodeOwners> {
+    if !args.group_by_owner {
+        return None;
+    }
+
+    let path = args
+        .codeowners
+        .as_ref()
+        .map(std::path::PathBuf::from)
+        .or_else(|| codeowners::CodeOwners::find(&args.crate_root))
+        .unwrap_or_else(|| {
+            eprintln!("Error: no CODEOWNERS file found, pass one with --codeowners");
+            std::process::exit(EXIT_ERROR);
+        });
+
+    let owners = codeowners::CodeOwners::load(&path).unwrap_or_else(|err| {
+        eprintln!(
+            "Error: failed to load CODEOWNERS from '{}': {err}",
+            path.display()
+        );
+        std::process::exit(EXIT_ERROR);
+    });
+    Some(owners.in_crate(&args.crate_root))
+}
+
+fn load_geiger(geiger_file: &str) -> Vec<geiger::GeigerPackage> {
+    geiger::load(geiger_file).unwrap_or_else(|err| {
+        eprintln!("Error: failed to load cargo-geiger output from '{geiger_file}': {err}");
+        std::process::exit(EXIT_ERROR);
+    })
+}
+
+fn format_geiger<W>(
+    report: &Report,
+    packages: &[geiger::GeigerPackage],
+    crate_root: &str,
+    mut out: W,
+) where
+    W: std::io::Write,
+{
+    _ = writeln!(
+        out,
+        "{}\n",
+        markdown_heading("cargo-geiger", 1, config::HeadingStyle::Setext)
+    );
+
+    // reconcile geiger's numbers for this crate with our own
+    if let Some(name) = metadata::package(crate_root).map(|package| package.name)
+        && let Some(package) = packages.iter().find(|p| p.is(&name))
+    {
+        _ = writeln!(
+            out,
+            "- {name}: {} unsafe fns (geiger: {}), {} unsafe statements (geiger exprs: {})
+",
+            report.total.unsafe_fns,
+            package.unsafe_fns,
+            report.total.unsafe_statements,
+            package.unsafe_exprs
+        );
+    }
+
+    let mut table = Table::with_headers(vec![
+        "package".into(),
+        "unsafe fns".into(),
+        "unsafe exprs".into(),
+        "unsafe impls".into(),
+        "forbids unsafe".into(),
+    ]);
+    table.extend_rows(packages.iter().map(|package| {
+        vec![
+            format!("{} {}", package.name, package.version).into(),
+            colorize_simple(package.unsafe_fns, config::Threshold::COUNT),
+            colorize_simple(package.unsafe_exprs, config::Threshold::COUNT),
+            colorize_simple(package.unsafe_items, config::Threshold::COUNT),
+            if package.forbids_unsafe { "yes" } else { "no" }.into(),
+        ]
+    }));
+    table.to_markdown(&mut out);
+}
+
+fn format_leaderboard<W>(contributors: &[(String, leaderboard::Reductions)], mut out: W)
+where
+    W: std::io::Write,
+{
+    _ = writeln!(
+        out,
+        "{}\n",
+        markdown_heading(
+            "Unsafe Reduction Leaderboard",
+            1,
+            config::HeadingStyle::Setext
+        )
+    );
+
+    let mut table = Table::with_headers(vec![
+        "contributor".into(),
+        "unsafe fns".into(),
+        "unsafe stmts".into(),
+        "unwraps".into(),
+        "total".into(),
+    ]);
+    table.extend_rows(contributors.iter().map(|(author, removed)| {
+        vec![
+            author.as_str().into(),
+            removed.unsafe_fns.to_string().into(),
+            removed.unsafe_statements.to_string().into(),
+            removed.unwraps.to_string().into(),
+            removed.total().to_string().color(Color::Green),
+        ]
+    }));
+    table.to_markdown(&mut out);
+}
+
+/// Authors ordered by how many counted constructs they last touched
+fn sorted_authors(
+    authors: &BTreeMap<String, blame::AuthorStats>,
+) -> Vec<(&String, &blame::AuthorStats)> {
+    let mut authors: Vec<_> = authors.iter().collect();
+    authors.sort_by_key(|(_, stats)| cmp::Reverse(stats.total()));
+    authors
+}
+
+fn format_blame<W>(authors: &BTreeMap<String, blame::AuthorStats>, mut out: W)
+where
+    W: std::io::Write,
+{
+    _ = writeln!(
+        out,
+        "{}\n",
+        markdown_heading("Ownership", 1, config::HeadingStyle::Setext)
+    );
+
+    let mut table = Table::with_headers(vec![
+        "author".into(),
+        "unsafe fns".into(),
+        "unsafe blocks".into(),
+        "static mut".into(),
+        "unwrap".into(),
+    ]);
+    table.extend_rows(sorted_authors(authors).into_iter().map(|(author, stats)| {
+        vec![
+            author.as_str().into(),
+            stats.unsafe_fns.to_string().into(),
+            stats.unsafe_blocks.to_string().into(),
+            stats.static_mut_items.to_string().into(),
+            stats.unwraps.to_string().into(),
+        ]
+    }));
+    table.to_markdown(&mut out);
+}
+
+fn format_entry_points<W>(entry_points: &[reachability::UnsafeEntryPoint], mut out: W)
+where
+    W: std::io::Write,
+{
+    _ = writeln!(
+        out,
+        "{}
+{} public safe functions reach unsafe code
+",
+        markdown_heading("Effectively Unsafe API", 1, config::HeadingStyle::Setext),
+        entry_points.len()
+    );
+    for entry_point in entry_points {
+        _ = writeln!(
+            out,
+            "- `{}` {} (depth {}: {})",
+            entry_point.fn_name,
+            markdown_link(
+                format!("{}:{}", entry_point.filename, entry_point.line_number),
+                links::line_url(&entry_point.filename, entry_point.line_number)
+            ),
+            entry_point.depth,
+            entry_point.path.join(" -> ")
+        );
+    }
+}
+
+fn format_untested_unsafe_fns<W>(untested: &[(String, Site)], mut out: W)
+where
+    W: std::io::Write,
+{
+    _ = writeln!(
+        out,
+        "{}
+{} unsafe functions are not referenced by any test
+",
+        markdown_heading("Untested Unsafe Functions", 1, config::HeadingStyle::Setext),
+        untested.len()
+    );
+    for (filename, site) in untested {
+        _ = writeln!(
+            out,
+            "- `{}` {}",
+            site.name,
+            markdown_link(
+                format!("{filename}:{}", site.line),
+                links::line_url(filename, site.line)
+            )
+        );
+    }
+}
+
+/// The last line of every PR comment, which is also how posting finds the
+/// comment of a previous run to update instead of adding another
+const PR_COMMENT_FOOTER: &str =
+    "*Generated by [crate-report](https://github.com/richardscollin/crate-report)*";
+
+fn format_pr_comment_report(
+    report: &Report,
+    baselines: &[(String, Report)],
+    args: &Args,
+) -> String {
+    // first runs have nothing to compare against yet
+    let Some((_, old_report)) = baselines.first() else {
+        return format_pr_current_state(report, args);
+    };
+
+    let diff = display_diff(report, old_report, args);
+    let metadata = metadata::Metadata::collect(&args.crate_root, args.deterministic);
+
+    let comparison = if baselines.len() > 1 {
+        format!(
+            "**Compared to baselines:** {}\n\n",
+            format_baseline_comparison(report, baselines)
+        )
+    } else {
+        String::new()
+    };
+
+    let relocated = diff
+        .relocated_note()
+        .map(|note| format!("_{note}_\n\n"))
+        .unwrap_or_default();
+
+    // If no changes, generate a "no changes" comment
+    if diff.changes.is_empty() {
+        return format!(
+            "{}\n\n\
+             _{metadata}_\n\n\
+             **No safety changes detected.** This PR doesn't modify any safety-related metrics.\n\n\
+             {relocated}\
+             {comparison}\
+             {}\n\
+             ---\n\
+             {PR_COMMENT_FOOTER}",
+            markdown_heading("Safety Analysis Report", 2, config::HeadingStyle::Atx),
+            format_pr_totals(&diff.after_total)
+        );
+    }
+
+    let mut out = String::new();
+
+    // Header
+    out.push_str(&format!(
+        "{}\n\n",
+        markdown_heading("Crate Report", 2, config::HeadingStyle::Atx)
+    ));
+    out.push_str(&format!("_{metadata}_\n\n"));
+
+    // Summary section
+    let unsafe_fn_delta = diff.after_total.unsafe_fns - diff.before_total.unsafe_fns;
+    let unsafe_stmt_delta =
+        diff.after_total.unsafe_statements - diff.before_total.unsafe_statements;
+    let static_mut_delta = diff.after_total.static_mut_items - diff.before_total.static_mut_items;
+    let unwrap_delta = diff.after_total.unwraps - diff.before_total.unwraps;
+
+    out.push_str(&format!(
+        "{}\n\n",
+        markdown_heading("Summary", 3, config::HeadingStyle::Atx)
+    ));
+    out.push_str(&format!(
+        "| Metric | Before | After | Change |\n\
+         |--------|--------|-------|--------|\n\
+         | Unsafe Functions | {} | {} | {} |\n\
+         | Unsafe Statements | {} | {} | {} |\n\
+         | Static Mut Items | {} | {} | {} |\n\
+         | Unwrap Calls | {} | {} | {} |\n",
+        diff.format_before("unsafe_fns"),
+        diff.after_total.unsafe_fns,
+        format_pr_change(&diff, "unsafe_fns", unsafe_fn_delta),
+        diff.format_before("unsafe_statements"),
+        diff.after_total.unsafe_statements,
+        format_pr_change(&diff, "unsafe_statements", unsafe_stmt_delta),
+        diff.format_before("static_mut_items"),
+        diff.after_total.static_mut_items,
+        format_pr_change(&diff, "static_mut_items", static_mut_delta),
+        diff.format_before("unwraps"),
+        diff.after_total.unwraps,
+        format_pr_change(&diff, "unwraps", unwrap_delta)
+    ));
+    if diff.has_clippy_lints() {
+        out.push_str(&format!(
+            "| Clippy Lints | {} | {} | {} |\n",
+            diff.format_before("clippy_lints"),
+            diff.after_total.clippy_lints,
+            format_pr_change(
+                &diff,
+                "clippy_lints",
+                diff.after_total.clippy_lints - diff.before_total.clippy_lints
+            )
+        ));
+    }
+    if let Some([(_, fn_percent), (_, per_kloc)]) = diff.densities() {
+        out.push_str(&format!(
+            "| Unsafe Function % | {:.2}% | {:.2}% | {} |\n\
+             | Unsafe Statements / KLOC | {:.2} | {:.2} | {} |\n",
+            fn_percent.before,
+            fn_percent.after,
+            format_density_delta(fn_percent).unwrap_or("0".into()),
+            per_kloc.before,
+            per_kloc.after,
+            format_density_delta(per_kloc).unwrap_or("0".into()),
+        ));
+    }
+    for sparse in diff.sparse_metrics() {
+        let before = diff.before_total.metric(sparse.metric).unwrap_or_default();
+        let after = diff.after_total.metric(sparse.metric).unwrap_or_default();
+        out.push_str(&format!(
+            "| {} | {} | {after} | {} |\n",
+            sparse.title,
+            diff.format_before(sparse.metric),
+            format_pr_change(&diff, sparse.metric, after - before)
+        ));
+    }
+    out.push('\n');
+    out.push_str(&comparison);
+
+    if let Some(owners) = load_codeowners(args) {
+        let team_diff = Report::from_files(owners.group(&report.files))
+            .diff(&Report::from_files(owners.group(&old_report.files)));
+        if !team_diff.changes.is_empty() {
+            out.push_str(&format!(
+                "{}\n\n",
+                markdown_heading("Team Changes", 3, config::HeadingStyle::Atx)
+            ));
+            out.push_str(&format_pr_changes(&team_diff, false));
+            out.push('\n');
+        }
+    }
+
+    // Overall assessment, prefixed by the severity of the regressions
+    let findings = severity::findings(report, baselines);
+    let total_negative_changes = [
+        unsafe_fn_delta,
+        unsafe_stmt_delta,
+        static_mut_delta,
+        unwrap_delta,
+    ]
+    .iter()
+    .filter(|&&x| x > 0)
+    .count();
+
+    let total_positive_changes = [
+        unsafe_fn_delta,
+        unsafe_stmt_delta,
+        static_mut_delta,
+        unwrap_delta,
+    ]
+    .iter()
+    .filter(|&&x| x < 0)
+    .count();
+
+    let assessment = if total_negative_changes == 0 && total_positive_changes > 0 {
+        "This PR reduces unsafe code usage."
+    } else if total_negative_changes > 0 && total_positive_changes == 0 {
+        "This PR introduces more unsafe code."
+    } else if total_negative_changes > 0 && total_positive_changes > 0 {
+        "This PR has both quality improvements and regressions."
+    } else {
+        "**No safety changes.** File changes detected but no impact on quality metrics."
+    };
+    out.push_str(&format!(
+        "{} {assessment}\n\n",
+        severity::assess(&findings).badge()
+    ));
+    if !findings.is_empty() {
+        for finding in &findings {
+            out.push_str(&format!(
+                "- {}: {}\n",
+                finding.severity.label(),
+                finding.reason
+            ));
+        }
+        out.push('\n');
+    }
+
+    out.push_str(&format_pr_safety_checklist(report, &diff, args));
+
+    out.push_str(&format!(
+        "{}\n\n",
+        markdown_heading("File Changes", 3, config::HeadingStyle::Atx)
+    ));
+    out.push_str(&format_pr_changes_by_directory(&diff, args));
+    if !relocated.is_empty() {
+        out.push_str(&format!("\n{}\n", relocated.trim_end()));
+    }
+    if !diff.unchanged.is_empty() {
+        let files: String = diff
+            .unchanged
+            .keys()
+            .map(|filename| {
+                format!(
+                    "- {} [UNCHANGED]\n",
+                    markdown_link(filename, links::file_url(filename))
+                )
+            })
+            .collect();
+        out.push_str(&pr_details(
+            &pr_bold(&format!("Unchanged files: {}", diff.unchanged.len()), args),
+            &files,
+            false,
+            args,
+        ));
+    }
+
+    out.push_str(&format!("\n---\n{PR_COMMENT_FOOTER}"));
+
+    out
+}
+
+/// The current totals as a PR comment table
+fn format_pr_totals(total: &CodeStats) -> String {
+    format!(
+        "| Metric | Current |\n\
+         |--------|--------|\n\
+         | Unsafe Functions | {} |\n\
+         | Unsafe Statements | {} |\n\
+         | Static Mut Items | {} |\n\
+         | Unwrap Calls | {} |\n",
+        total.unsafe_fns, total.unsafe_statements, total.static_mut_items, total.unwraps
+    )
+}
+
+/// A PR comment without a baseline, summarizing the current totals and every
+/// file with unsafe code or unwraps so first runs still get useful output
+fn format_pr_current_state(report: &Report, args: &Args) -> String {
+    let metadata = metadata::Metadata::collect(&args.crate_root, args.deterministic);
+    let mut out = format!(
+        "{}\n\n\
+         _{metadata}_\n\n\
+         **No baseline to compare against.** This is the crate's current state, \
+         pass `--baseline` a report of the base branch to see what the PR changes.\n\n\
+         {}\n",
+        markdown_heading("Safety Analysis Report", 2, config::HeadingStyle::Atx),
+        format_pr_totals(&report.total)
+    );
+
+    let files: Vec<String> = report
+        .files
+        .iter()
+        .filter(|(_, stats)| {
+            stats.unsafe_fns + stats.unsafe_statements + stats.static_mut_items + stats.unwraps > 0
+        })
+        .map(|(filename, stats)| {
+            format!(
+                "- **{}**\n  - Unsafe functions: {}, Statements: {}, Static mut items: {}, Unwraps: {}\n",
+                markdown_link(filename, links::file_url(filename)),
+                stats.unsafe_fns,
+                stats.unsafe_statements,
+                stats.static_mut_items,
+                stats.unwraps
+            )
+        })
+        .collect();
+    if !files.is_empty() {
+        out.push_str(&format!(
+            "{}\n\n{}",
+            markdown_heading(
+                "Files With Unsafe Code or Unwraps",
+                3,
+                config::HeadingStyle::Atx
+            ),
+            pr_details(
+                &format!("{} of {} files", files.len(), report.files.len()),
+                &files.concat(),
+                files.len() <= PR_EXPANDED_CHANGES_LIMIT,
+                args
+            )
+        ));
+    }
+
+    out.push_str(&format!("\n---\n{PR_COMMENT_FOOTER}"));
+    out
+}
+
+/// A file's sites at `--base-rev`, matching uses of the `deprecated` items,
+/// `None` without one
+fn base_sites(args: &Args, deprecated: &BTreeSet<String>, filename: &str) -> Option<Vec<Site>> {
+    let base_rev = args.base_rev.as_ref()?;
+    Some(
+        git::show_file(&args.crate_root, base_rev, filename)
+            .as_deref()
+            .and_then(|base| analyze_source_with(base, deprecated))
+            .map(|(_, sites)| sites)
+            .unwrap_or_default(),
+    )
+}
+
+/// The sites a diff adds, with their filenames
+///
+/// Every site in an added file is new. Which sites in a changed file are new
+/// is only known with `--base-rev`, by matching them against the base
+/// revision's sites like the pre-commit check does.
+fn new_sites(report: &Report, diff: &DiffReport, args: &Args) -> Vec<(String, Site)> {
+    // the base revision's uses of the deprecated items are matched up too
+    let deprecated = deprecated::definitions_in(input_files(args));
+    let mut new_sites = Vec::new();
+    for (filename, change) in &diff.changes {
+        let Some(sites) = report.sites.get(filename) else {
+            continue;
+        };
+        let added = match change {
+            Diff::Added(_) => sites.clone(),
+            Diff::Changed(_) => {
+                let Some(base_sites) = base_sites(args, &deprecated, filename) else {
+                    continue;
+                };
+                precommit::added_sites(base_sites, sites.iter().cloned())
+            }
+            Diff::Removed(_) => continue,
+        };
+        new_sites.extend(added.into_iter().map(|site| (filename.clone(), site)));
+    }
+    new_sites
+}
+
+/// A checklist item per unsafe fn and block the PR adds, asking for a safety
+/// justification so review can be anchored to each one
+fn format_pr_safety_checklist(report: &Report, diff: &DiffReport, args: &Args) -> String {
+    let mut items = String::new();
+    for (filename, site) in new_sites(report, diff, args) {
+        if !matches!(site.kind, SiteKind::UnsafeFn | SiteKind::UnsafeBlock) {
+            continue;
+        }
+        items.push_str(&format!(
+            "- [ ] {} {}: add a safety justification\n",
+            markdown_link(
+                format!("{filename}:{}", site.line),
+                links::line_url(&filename, site.line)
+            ),
+            precommit::describe(&site)
+        ));
+    }
+    if items.is_empty() {
+        return String::new();
+    }
+
+    format!(
+        "{}\n\n{items}\n",
+        markdown_heading("New Unsafe Code", 3, config::HeadingStyle::Atx)
+    )
+}
+
+/// A collapsible block, or for Azure DevOps (which doesn't render `<details>`)
+/// the summary as a paragraph followed by the body
+fn pr_details(summary: &str, body: &str, open: bool, args: &Args) -> String {
+    if matches!(args.format, OutputFormat::AzurePrComment) {
+        return format!("{summary}\n\n{body}\n");
+    }
+    format!(
+        "<details{}>\n<summary>{summary}</summary>\n\n{body}\n</details>\n",
+        if open { " open" } else { "" }
+    )
+}
+
+/// Bold text for a [`pr_details`] summary, where GitHub doesn't render markdown
+fn pr_bold(text: &str, args: &Args) -> String {
+    if matches!(args.format, OutputFormat::AzurePrComment) {
+        format!("**{text}**")
+    } else {
+        format!("<b>{text}</b>")
+    }
+}
+
+/// PR comments with more file changes than this start with every directory collapsed
+const PR_EXPANDED_CHANGES_LIMIT: usize = 5;
+
+/// The file changes in a collapsible block per top directory, each summarizing
+/// how the directory's totals changed
+fn format_pr_changes_by_directory(diff: &DiffReport, args: &Args) -> String {
+    let mut directories: BTreeMap<&str, BTreeMap<String, Diff>> = BTreeMap::new();
+    for (filename, change) in &diff.changes {
+        let directory = match filename.split_once('/') {
+            Some((directory, _)) => directory,
+            None => ".",
+        };
+        directories
+            .entry(directory)
+            .or_default()
+            .insert(filename.clone(), change.clone());
+    }
+
+    let mut out = String::new();
+    for (directory, changes) in directories {
+        let (before, after): (Vec<CodeStats>, Vec<CodeStats>) = changes
+            .values()
+            .map(|change| match change {
+                Diff::Added(stats) => (CodeStats::default(), stats.clone()),
+                Diff::Changed(change) => (change.before.clone(), change.after.clone()),
+                Diff::Removed(stats) => (stats.clone(), CodeStats::default()),
+            })
+            .unzip();
+        let directory_diff = DiffReport {
+            before_total: before.into_iter().sum(),
+            after_total: after.into_iter().sum(),
+            changes,
+            unknown_metrics: diff.unknown_metrics.clone(),
+            unchanged: BTreeMap::new(),
+            relocated: BTreeMap::new(),
+        };
+
+        let subtotals: Vec<String> = [
+            ("unsafe_fns", "unsafe fns"),
+            ("unsafe_statements", "unsafe statements"),
+            ("static_mut_items", "static mut items"),
+            ("unwraps", "unwraps"),
+        ]
+        .into_iter()
+        .filter_map(|(metric, label)| {
+            let delta = directory_diff.after_total.metric(metric)?
+                - directory_diff.before_total.metric(metric)?;
+            (delta != 0).then(|| format!("{label} {}", format_pr_change(diff, metric, delta)))
+        })
+        .collect();
+        let files = directory_diff.changes.len();
+        let summary = format!(
+            "{}: {files} file{}, {}",
+            pr_bold(&format!("{directory}/"), args),
+            if files == 1 { "" } else { "s" },
+            if subtotals.is_empty() {
+                "no metric changes".to_string()
+            } else {
+                subtotals.join(", ")
+            }
+        );
+        out.push_str(&pr_details(
+            &summary,
+            &format_pr_changes(&directory_diff, true),
+            diff.changes.len() <= PR_EXPANDED_CHANGES_LIMIT,
+            args,
+        ));
+    }
+    out
+}
+
+/// One list entry per added, removed, or modified file (or other grouping key,
+/// unless `link_filenames`)
+fn format_pr_changes(diff: &DiffReport, link_filenames: bool) -> String {
+    let mut out = String::new();
+
+    for (filename, change) in &diff.changes {
+        let filename = if link_filenames {
+            markdown_link(filename, links::file_url(filename))
+        } else {
+            filename.clone()
+        };
+        match change {
+            Diff::Added(stats) => {
+                out.push_str(&format!(
+                    "- **{}** [NEW]\n  - Unsafe functions: {}, Statements: {}, Unwraps: {}\n",
+                    filename, stats.unsafe_fns, stats.unsafe_statements, stats.unwraps
+                ));
+            }
+            Diff::Removed(stats) => {
+                out.push_str(&format!(
+                    "- **{}** [REMOVED]\n  - Had: {} unsafe functions, {} statements, {} unwraps\n",
+                    filename, stats.unsafe_fns, stats.unsafe_statements, stats.unwraps
+                ));
+            }
+            Diff::Changed(change) => {
+                let mut changes = Vec::new();
+                if change.before.unsafe_fns != change.after.unsafe_fns {
+                    changes.push(format!(
+                        "unsafe functions: {} {} {}",
+                        change.before.unsafe_fns,
+                        markdown_arrow(),
+                        change.after.unsafe_fns
+                    ));
+                }
+                if change.before.unsafe_statements != change.after.unsafe_statements {
+                    changes.push(format!(
+                        "unsafe statements: {} {} {}",
+                        change.before.unsafe_statements,
+                        markdown_arrow(),
+                        change.after.unsafe_statements
+                    ));
+                }
+                if change.before.unwraps != change.after.unwraps {
+                    changes.push(format!(
+                        "unwraps: {} {} {}",
+                        change.before.unwraps,
+                        markdown_arrow(),
+                        change.after.unwraps
+                    ));
+                }
+                if change.before.clippy_lints != change.after.clippy_lints {
+                    changes.push(format!(
+                        "clippy lints: {} {} {}",
+                        change.before.clippy_lints,
+                        markdown_arrow(),
+                        change.after.clippy_lints
+                    ));
+                }
+                for (metric, label) in [
+                    ("total_fns", "total functions"),
+                    ("total_statements", "total statements"),
+                    ("total_lines", "total lines"),
+                ] {
+                    let change = change.project(|e| e.metric(metric).unwrap_or_default());
+                    if change.before != change.after && config::reports_change(metric) {
+                        changes.push(format!(
+                            "{label}: {} {} {}",
+                            change.before,
+                            markdown_arrow(),
+                            change.after
+                        ));
+                    }
+                }
+
+                if !changes.is_empty() {
+                    out.push_str(&format!(
+                        "- **{}** [MODIFIED]\n  - {}\n",
+                        filename,
+                        changes.join(", ")
+                    ));
+                }
+            }
+        }
+    }
+
+    out
+}
+
+/// The version of the CSV and JSON formats, bumped whenever a metric is added
+/// or their shape changes
+///
+/// 1. (unversioned) the original seven metrics
+/// 2. adds `clippy_lints`
+/// 3. CSV adds a `TOTAL` row and the computed `unsafe_fn_percent` and
+///    `unsafe_statements_per_kloc` columns, which are ignored when loading
+/// 4. JSON reports add a `diff` against the first baseline, the JSON history
+///    is an object with `schema_version` and `entries` rather than an array
+/// 5. adds `ffi_string_conversions`
+/// 6. adds `extern_callbacks`
+/// 7. adds `repr_types`
+/// 8. adds `manual_allocations`
+/// 9. adds `leak_prone_calls`
+/// 10. adds `process_exits`
+/// 11. adds `thread_unsafe_calls`
+/// 12. adds `recursive_fns`
+/// 13. adds `deprecated_uses`
+/// 14. adds `trivial_expects`
+/// 15. adds `allowed_lints`
+/// 16. adds the `min_confidence` the `--count-candidates` columns were counted
+///     at, a `# crate-report min-confidence` line in CSV
+const BASELINE_SCHEMA_VERSION: u64 = 16;
+
+/// A JSON document with the [`BASELINE_SCHEMA_VERSION`] it was written with,
+/// `1` when it predates versioning
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Versioned<T> {
+    #[serde(default = "Versioned::<T>::unversioned")]
+    schema_version: u64,
+    #[serde(flatten)]
+    content: T,
+}
+
+impl<T> Versioned<T> {
+    fn new(content: T) -> Self {
+        Self {
+            schema_version: BASELINE_SCHEMA_VERSION,
+            content,
+        }
+    }
+
+    fn unversioned() -> u64 {
+        1
+    }
+}
+
+/// The comment line CSV baselines start with, followed by the schema version
+const CSV_SCHEMA_PREFIX: &str = "# crate-report schema ";
+
+/// The comment line recording the `--min-confidence` of a report's
+/// `*_candidates` columns, after the schema version
+const CSV_MIN_CONFIDENCE_PREFIX: &str = "# crate-report min-confidence ";
+
+/// The filename of the last CSV row, holding the crate totals
+const CSV_TOTAL_ROW: &str = "TOTAL";
+
+/// File name of the source read with `--stdin`, unless given `--filename`
+const STDIN_FILENAME: &str = "<stdin>";
+
+/// Warn when a baseline was written by a newer version, its new metrics are ignored
+fn check_schema_version(schema_version: u64) {
+    tracing::debug!("baseline schema version {schema_version}");
+    if schema_version > BASELINE_SCHEMA_VERSION {
+        eprintln!(
+            "Warning: baseline schema version {schema_version} is newer than this version of crate-report supports ({BASELINE_SCHEMA_VERSION}), newer metrics are ignored"
+        );
+    }
+}
+
+/// Read a baseline from a file, stdin (`-`), or an http(s) URL
+fn read_baseline(baseline_file: &str, header: Option<&str>) -> Result<String, String> {
+    if baseline_file == "-" {
+        tracing::info!("reading baseline from stdin");
+        std::io::read_to_string(std::io::stdin()).map_err(|e| e.to_string())
+    } else if baseline_file.starts_with("http://") || baseline_file.starts_with("https://") {
+        tracing::info!("fetching baseline {baseline_file}");
+        let mut request = ureq::get(baseline_file);
+        if let Some(header) = header {
+            let (name, value) = header
+                .split_once(':')
+                .ok_or("--baseline-header must look like \"Name: value\"")?;
+            request = request.header(name.trim(), value.trim());
+        }
+        request
+            .call()
+            .map_err(|e| e.to_string())?
+            .body_mut()
+            .read_to_string()
+            .map_err(|e| e.to_string())
+    } else {
+        std::fs::read_to_string(baseline_file).map_err(|e| e.to_string())
+    }
+}
+
+/// Load every `--baseline`, labelled by file name without the extension
+///
+/// Baselines are narrowed down to the files given with `--files` or
+/// `--files-from` and the `--package`s analyzed, so the other files don't show
+/// as removed. With `--ignore-path-case` their paths are matched up with the
+/// analyzed files' regardless of case, keeping the real paths the files are
+/// read and linked by.
+fn load_baselines(args: &Args) -> Vec<(String, Report)> {
+    let real_paths: Option<BTreeMap<String, String>> = args.ignore_path_case.then(|| {
+        input_files(args)
+            .map(|path| {
+                let filename = relative_filename(&args.crate_root, &path);
+                (filename.to_lowercase(), filename)
+            })
+            .collect()
+    });
+    let is_selected = package_selection(args);
+    let given: Option<BTreeSet<String>> = has_explicit_files(args).then(|| {
+        given_files(args)
+            .iter()
+            .map(|path| relative_filename(&args.crate_root, path))
+            .collect()
+    });
+    args.baseline
+        .iter()
+        .map(|baseline_file| {
+            let mut report = read_baseline(baseline_file, args.baseline_header.as_deref())
+                .and_then(|content| Report::load_baseline(baseline_file, &content))
+                .unwrap_or_else(|err| {
+                    eprintln!("Error: failed to load baseline '{baseline_file}': {err}");
+                    std::process::exit(EXIT_ERROR);
+                });
+            tracing::info!(
+                "loaded baseline {baseline_file}: {} files, unknown metrics: {:?}",
+                report.files.len(),
+                report.unknown_metrics
+            );
+            if let Some(real_paths) = &real_paths {
+                report = report.map_paths(|filename| {
+                    let lowercase = filename.to_lowercase();
+                    real_paths.get(&lowercase).cloned().unwrap_or(lowercase)
+                });
+            }
+            if let Some(is_selected) = &is_selected {
+                report = report.subset(is_selected);
+            }
+            if let Some(given) = &given {
+                report = report.subset(|filename| given.contains(filename));
+            }
+            // counts at another confidence can't be compared
+            if args.count_candidates && report.min_confidence != Some(args.min_confidence) {
+                if let Some(min_confidence) = report.min_confidence {
+                    eprintln!(
+                        "Warning: baseline '{baseline_file}' counted candidates of {min_confidence} confidence and up, not {}, they're shown as unknown",
+                        args.min_confidence
+                    );
+                }
+                report
+                    .unknown_metrics
+                    .extend(candidate_counts::METRICS.map(String::from));
+            }
+            let label = match baseline_file.as_str() {
+                "-" => "stdin".to_string(),
+                _ => Path::new(baseline_file)
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| baseline_file.clone()),
+            };
+            (label, report)
+        })
+        .collect()
+}
+
+/// One line comparing the totals against every baseline, e.g.
+/// `vs v1.2: -14 unsafe fns; vs main: +2 unsafe fns`
+fn format_baseline_comparison(report: &Report, baselines: &[(String, Report)]) -> String {
+    baselines
+        .iter()
+        .map(|(label, baseline)| {
+            let (after, before) = (&report.total, &baseline.total);
+            let changes: Vec<String> = [
+                ("unsafe fns", after.unsafe_fns - before.unsafe_fns),
+                (
+                    "unsafe statements",
+                    after.unsafe_statements - before.unsafe_statements,
+                ),
+                (
+                    "static mut items",
+                    after.static_mut_items - before.static_mut_items,
+                ),
+                ("unwraps", after.unwraps - before.unwraps),
+            ]
+            .into_iter()
+            .filter(|(_, delta)| *delta != 0)
+            .map(|(name, delta)| format!("{} {name}", format_pr_delta(delta)))
+            .collect();
+
+            if changes.is_empty() {
+                format!("vs {label}: no change")
+            } else {
+                format!("vs {label}: {}", changes.join(", "))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// The change column of the PR comment summary, `?` for metrics missing from the baseline
+fn format_pr_change(diff: &DiffReport, metric: &str, delta: isize) -> String {
+    if diff.unknown_metrics.contains(metric) {
+        "?".to_string()
+    } else {
+        format_pr_delta(delta)
+    }
+}
+
+/// A ratio's change between its values shown to two decimal places, `None`
+/// when they're the same
+fn format_density_delta(change: Change<f64>) -> Option<String> {
+    let round = |value: f64| (value * 100.0).round() / 100.0;
+    let delta = round(change.after) - round(change.before);
+    (delta.abs() >= 0.005).then(|| format!("{delta:+.2}"))
+}
+
+fn format_pr_delta(delta: isize) -> String {
+    match delta {
+        0 => "0".to_string(),
+        x if x > 0 => format!("+{}", x),
+        x => format!("{}", x),
+    }
+}
+
+fn format_change_delta(before: isize, after: isize) -> String {
+    let delta = after - before;
+    if delta == 0 {
+        "no change".to_string()
+    } else if delta > 0 {
+        format!("+{}", delta)
+    } else {
+        delta.to_string()
+    }
+}
+
+/// A helper for displaying a table of data
+///
+/// Every row must have the same number of columns as the headers.
+struct Table {
+    headers: Vec<ColoredString>,
+    rows: Vec<Vec<ColoredString>>,
+}
+impl Table {
+    fn with_headers(headers: Vec<ColoredString>) -> Self {
+        Self {
+            headers,
+            rows: Vec::new(),
+        }
+    }
+
+    fn extend_rows<I>(&mut self, rows: I)
+    where
+        I: Iterator<Item = Vec<ColoredString>>,
+    {
+        self.rows.extend(rows)
+    }
+
+    fn to_markdown<W>(&self, mut out: W)
+    where
+        W: std::io::Write,
+    {
+        // a `|` in a cell would end it early
+        let escape = |row: &Vec<ColoredString>| -> Vec<ColoredString> {
+            row.iter()
+                .map(|cell| {
+                    let mut cell = cell.clone();
+                    cell.input = cell.input.replace('|', "\\|");
+                    cell
+                })
+                .collect()
+        };
+        let headers = escape(&self.headers);
+        let rows: Vec<_> = self.rows.iter().map(escape).collect();
+
+        // GFM needs at least one `-` in each delimiter cell
+        let mut column_widths = vec![3; headers.len()];
+        for row in Some(&headers).into_iter().chain(&rows) {
+            for (c, text) in row.iter().enumerate() {
+                column_widths[c] = column_widths[c].max(display_width(text));
+            }
+        }
+
+        // headers
+        Self::write_row(&mut out, &headers, &column_widths);
+
+        // "| -- | -: | -: | -: | -: |\n"
+        {
+            let mut it = column_widths.iter();
+            let width = it.next().unwrap();
+            _ = write!(&mut out, "| {:-<width$} | ", ":");
+
+            // right align other columns
+            for width in it {
+                _ = write!(&mut out, " {:->width$} |", ":");
+            }
+            _ = writeln!(&mut out);
+        }
+
+        for row in &rows {
+            Self::write_row(&mut out, row, &column_widths);
+        }
+    }
+
+    /// Render with box-drawing borders for reading in a terminal
+    fn to_terminal<W>(&self, mut out: W)
+    where
+        W: std::io::Write,
+    {
+        let mut column_widths = vec![0; self.headers.len()];
+        for row in Some(&self.headers).into_iter().chain(&self.rows) {
+            for (c, text) in row.iter().enumerate() {
+                column_widths[c] = column_widths[c].max(display_width(text));
+            }
+        }
+
+        let border = |left: &str, middle: &str, right: &str| {
+            let lines: Vec<String> = column_widths.iter().map(|w| "─".repeat(w + 2)).collect();
+            format!("{left}{}{right}", lines.join(middle))
+        };
+        let write_row = |out: &mut W, row: &[ColoredString]| {
+            _ = write!(out, "│");
+            for (c, (col, width)) in row.iter().zip(&column_widths).enumerate() {
+                let padding = " ".repeat(width.saturating_sub(display_width(col)));
+                // left align first column, right align the others
+                if c == 0 {
+                    _ = write!(out, " {col}{padding} │");
+                } else {
+                    _ = write!(out, " {padding}{col} │");
+                }
+            }
+            _ = writeln!(out);
+        };
+
+        _ = writeln!(out, "{}", border("┌", "┬", "┐"));
+        write_row(&mut out, &self.headers);
+        _ = writeln!(out, "{}", border("├", "┼", "┤"));
+        for row in &self.rows {
+            write_row(&mut out, row);
+        }
+        _ = writeln!(out, "{}", border("└", "┴", "┘"));
+    }
+
+    /// Pad by display width rather than with format width, which counts chars,
+    /// so wide characters (CJK, emoji) stay aligned
+    fn write_row<W>(mut out: W, row: &[ColoredString], column_widths: &[usize])
+    where
+        W: std::io::Write,
+    {
+        let mut it = row.iter().zip(column_widths);
+        let padding = |col: &ColoredString, width: usize| {
+            " ".repeat(width.saturating_sub(display_width(col)))
+        };
+
+        // left align first column
+        let (col, width) = it.next().unwrap();
+        _ = write!(&mut out, "| {col}{} | ", padding(col, *width));
+
+        // right align other columns
+        for (col, width) in it {
+            _ = write!(&mut out, " {}{col} |", padding(col, *width));
+        }
+        _ = writeln!(&mut out);
+    }
+}
+
+/// Replace the middle of `text` with an ellipsis so it's at most `max_width`
+/// wide, keeping the start and the end (usually the filename) of paths
+fn truncate_middle(text: &str, max_width: usize) -> String {
+    use unicode_width::{
+        UnicodeWidthChar,
+        UnicodeWidthStr,
+    };
+
+    if text.width() <= max_width {
+        return text.to_string();
+    }
+
+    // the ellipsis takes one column
+    let budget = max_width.saturating_sub(1);
+    let take = |chars: &mut dyn Iterator<Item = char>, budget: usize| {
+        let mut width = 0;
+        let mut taken = Vec::new();
+        for c in chars {
+            width += c.width().unwrap_or(0);
+            if width > budget {
+                break;
+            }
+            taken.push(c);
+        }
+        taken
+    };
+
+    let tail_budget = budget.div_ceil(2);
+    let mut tail = take(&mut text.chars().rev(), tail_budget);
+    tail.reverse();
+    let head = take(&mut text.chars(), budget - tail_budget);
+
+    head.into_iter()
+        .chain(std::iter::once('…'))
+        .chain(tail)
+        .collect()
+}
+
+/// The terminal width of a cell, the color escape codes of a `ColoredString`
+/// aren't part of its text so they aren't counted
+fn display_width(text: &ColoredString) -> usize {
+    unicode_width::UnicodeWidthStr::width(&**text)
+}
@@ -0,0 +1,47 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::Path,
+};
+
+use syn::{
+    Attribute,
+    visit::Visit,
+};
+
+use super::Report;
+use crate::analysis::allowed_lints;
+
+/// Allows per lint name
+pub type Counts = BTreeMap<String, usize>;
+
+#[derive(Default)]
+struct AllowCollector {
+    counts: Counts,
+}
+
+impl<'ast> Visit<'ast> for AllowCollector {
+    fn visit_attribute(&mut self, i: &'ast Attribute) {
+        for lint in allowed_lints(i) {
+            *self.counts.entry(lint).or_default() += 1;
+        }
+        syn::visit::visit_attribute(self, i);
+    }
+}
+
+/// Count the lints each file of the report allows, by name, those in
+/// attributes inside macro invocations aren't seen
+pub fn census(root: impl AsRef<Path>, report: &Report) -> BTreeMap<String, Counts> {
+    report
+        .files
+        .iter()
+        .filter(|(_, stats)| stats.allowed_lints > 0)
+        .filter_map(|(filename, _)| {
+            let content = fs::read_to_string(root.as_ref().join(filename)).ok()?;
+            let syntax = syn::parse_file(&content).ok()?;
+            let mut collector = AllowCollector::default();
+            collector.visit_file(&syntax);
+            Some((filename.clone(), collector.counts))
+        })
+        .collect()
+}
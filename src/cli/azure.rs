@@ -0,0 +1,109 @@
+use std::env;
+
+use super::PR_COMMENT_FOOTER;
+
+const API_VERSION: &str = "7.1";
+
+/// Read a variable Azure Pipelines defines for every run
+fn pipeline_variable(name: &str) -> Result<String, String> {
+    env::var(name)
+        .ok()
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| format!("{name} isn't set, is this a pull request pipeline run?"))
+}
+
+/// The Authorization header value, from a personal access token in
+/// `AZURE_DEVOPS_PAT` or else the pipeline's `SYSTEM_ACCESSTOKEN`
+fn authorization() -> Result<String, String> {
+    if let Some(pat) = env::var("AZURE_DEVOPS_PAT")
+        .ok()
+        .filter(|pat| !pat.is_empty())
+    {
+        // PATs are sent as the password of a user without a name
+        return Ok(format!("Basic {}", base64(format!(":{pat}").as_bytes())));
+    }
+    env::var("SYSTEM_ACCESSTOKEN")
+        .ok()
+        .filter(|token| !token.is_empty())
+        .map(|token| format!("Bearer {token}"))
+        .ok_or_else(|| {
+            "set AZURE_DEVOPS_PAT, or map SYSTEM_ACCESSTOKEN into the step's env".to_string()
+        })
+}
+
+/// Standard padded base64, for basic auth
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::new();
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &byte)| n | (byte as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Post `content` to the pull request the pipeline is running for, replacing
+/// the comment of a previous run if there is one
+pub fn post_comment(content: &str) -> Result<(), String> {
+    let collection = pipeline_variable("SYSTEM_COLLECTIONURI")?;
+    let project = pipeline_variable("SYSTEM_TEAMPROJECTID")?;
+    let repository = pipeline_variable("BUILD_REPOSITORY_ID")?;
+    let pull_request = pipeline_variable("SYSTEM_PULLREQUEST_PULLREQUESTID")?;
+    let authorization = authorization()?;
+    let threads = format!(
+        "{}/{project}/_apis/git/repositories/{repository}/pullRequests/{pull_request}/threads",
+        collection.trim_end_matches('/')
+    );
+
+    let existing: serde_json::Value = ureq::get(format!("{threads}?api-version={API_VERSION}"))
+        .header("Authorization", &authorization)
+        .call()
+        .map_err(|e| e.to_string())?
+        .body_mut()
+        .read_to_string()
+        .map_err(|e| e.to_string())
+        .and_then(|body| serde_json::from_str(&body).map_err(|e| e.to_string()))?;
+    let previous = existing["value"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find_map(|thread| {
+            let comment = thread["comments"].get(0)?;
+            if !comment["content"].as_str()?.contains(PR_COMMENT_FOOTER) {
+                return None;
+            }
+            Some((thread["id"].as_u64()?, comment["id"].as_u64()?))
+        });
+
+    let request = if let Some((thread, comment)) = previous {
+        tracing::info!("updating comment {comment} of thread {thread}");
+        ureq::patch(format!(
+            "{threads}/{thread}/comments/{comment}?api-version={API_VERSION}"
+        ))
+        .header("Authorization", &authorization)
+        .header("Content-Type", "application/json")
+        .send(serde_json::json!({ "content": content }).to_string())
+    } else {
+        tracing::info!("adding a comment thread to pull request {pull_request}");
+        ureq::post(format!("{threads}?api-version={API_VERSION}"))
+            .header("Authorization", &authorization)
+            .header("Content-Type", "application/json")
+            .send(
+                serde_json::json!({
+                    "comments": [{ "parentCommentId": 0, "content": content, "commentType": "text" }],
+                    "status": "active",
+                })
+                .to_string(),
+            )
+    };
+    request.map(|_| ()).map_err(|e| e.to_string())
+}
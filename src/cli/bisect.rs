@@ -0,0 +1,92 @@
+use std::{
+    collections::BTreeMap,
+    path::Path,
+};
+
+use super::{
+    git,
+    history::totals_at,
+};
+
+/// The commit at which a metric first went over the threshold
+#[derive(Clone, Debug)]
+pub struct Culprit {
+    pub commit: String,
+    /// the metric at the culprit's parent
+    pub before: isize,
+    pub after: isize,
+}
+
+#[derive(Clone, Debug)]
+pub struct Outcome {
+    pub range: String,
+    pub threshold: isize,
+    /// `None` when the metric never exceeds the threshold
+    pub culprit: Option<Culprit>,
+}
+
+/// Binary search the first-parent commits in `from..to` for the first commit
+/// where `metric` exceeds `threshold`, or its value at `from` when not given
+pub fn bisect(
+    crate_root: impl AsRef<Path>,
+    metric: &str,
+    from: &str,
+    to: &str,
+    threshold: Option<isize>,
+) -> Outcome {
+    let crate_root = crate_root.as_ref();
+    let range = format!("{from}..{to}");
+    let Some(commits) = git::first_parent_commits(crate_root, &range) else {
+        eprintln!("Error: failed to list commits in '{range}'");
+        std::process::exit(super::EXIT_ERROR);
+    };
+
+    let mut values: BTreeMap<String, isize> = BTreeMap::new();
+    let mut value_at = |rev: &str| {
+        *values.entry(rev.to_string()).or_insert_with(|| {
+            eprintln!("Analyzing {rev}");
+            totals_at(crate_root, rev)
+                .metric(metric)
+                .unwrap_or_default()
+        })
+    };
+
+    let threshold = threshold.unwrap_or_else(|| value_at(from));
+    let Some(last) = commits.last() else {
+        return Outcome {
+            range,
+            threshold,
+            culprit: None,
+        };
+    };
+    if value_at(last) <= threshold {
+        return Outcome {
+            range,
+            threshold,
+            culprit: None,
+        };
+    }
+
+    // invariant: commits[high] exceeds the threshold, everything before low doesn't
+    let (mut low, mut high) = (0, commits.len() - 1);
+    while low < high {
+        let middle = low + (high - low) / 2;
+        if value_at(&commits[middle]) > threshold {
+            high = middle;
+        } else {
+            low = middle + 1;
+        }
+    }
+
+    let parent = if high == 0 { from } else { &commits[high - 1] };
+    let culprit = Culprit {
+        commit: commits[high].clone(),
+        before: value_at(parent),
+        after: value_at(&commits[high]),
+    };
+    Outcome {
+        range,
+        threshold,
+        culprit: Some(culprit),
+    }
+}
@@ -0,0 +1,184 @@
+use serde::Serialize;
+
+use super::{
+    Args,
+    Report,
+    SiteKind,
+    git,
+    has_violation,
+    metadata,
+    percentage,
+    precommit,
+};
+
+/// Bitbucket Cloud rejects reports with more annotations than this
+const MAX_ANNOTATIONS: usize = 1000;
+
+/// The body of a Code Insights report and its annotations, for
+/// `PUT .../commit/<sha>/reports/crate-report` and
+/// `POST .../commit/<sha>/reports/crate-report/annotations` respectively
+#[derive(Serialize)]
+struct Insights {
+    report: InsightsReport,
+    annotations: Vec<Annotation>,
+}
+
+#[derive(Serialize)]
+struct InsightsReport {
+    title: &'static str,
+    details: String,
+    report_type: &'static str,
+    reporter: &'static str,
+    link: &'static str,
+    /// `FAILED` on a regression or a file at a danger threshold, like `--quiet`
+    result: &'static str,
+    data: Vec<DataItem>,
+}
+
+#[derive(Serialize)]
+struct DataItem {
+    title: String,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    value: serde_json::Value,
+}
+
+#[derive(Serialize)]
+struct Annotation {
+    external_id: String,
+    annotation_type: &'static str,
+    severity: &'static str,
+    summary: String,
+    /// relative to the repository root rather than the crate root
+    path: String,
+    line: usize,
+}
+
+pub fn format_bitbucket_insights(
+    report: &Report,
+    baselines: &[(String, Report)],
+    args: &Args,
+) -> String {
+    let metadata = metadata::Metadata::collect(&args.crate_root, args.deterministic);
+    let total = &report.total;
+    let mut data = vec![
+        DataItem {
+            title: "Unsafe functions".to_string(),
+            kind: "NUMBER",
+            value: total.unsafe_fns.into(),
+        },
+        DataItem {
+            title: "Unsafe fn percentage".to_string(),
+            kind: "PERCENTAGE",
+            value: ((percentage(total.unsafe_fns, total.total_fns) * 10.0).round() / 10.0).into(),
+        },
+        DataItem {
+            title: "Unsafe statements".to_string(),
+            kind: "NUMBER",
+            value: total.unsafe_statements.into(),
+        },
+        DataItem {
+            title: "Static mut items".to_string(),
+            kind: "NUMBER",
+            value: total.static_mut_items.into(),
+        },
+        DataItem {
+            title: "Unwrap calls".to_string(),
+            kind: "NUMBER",
+            value: total.unwraps.into(),
+        },
+    ];
+    // reports hold at most 10 data items, so only the safety metrics get a change
+    if let Some((label, baseline)) = baselines.first() {
+        let diff = report.diff(baseline);
+        for (metric, title) in [
+            ("unsafe_fns", "Unsafe functions"),
+            ("unsafe_statements", "Unsafe statements"),
+            ("static_mut_items", "Static mut items"),
+            ("unwraps", "Unwrap calls"),
+        ] {
+            let change = if diff.unknown_metrics.contains(metric) {
+                "unknown".to_string()
+            } else {
+                let before = diff.before_total.metric(metric).unwrap_or_default();
+                let after = diff.after_total.metric(metric).unwrap_or_default();
+                match after - before {
+                    0 => "0".to_string(),
+                    delta => format!("{delta:+}"),
+                }
+            };
+            data.push(DataItem {
+                title: format!("{title} vs {label}"),
+                kind: "TEXT",
+                value: change.into(),
+            });
+        }
+    }
+
+    // filenames are relative to the crate root, which may be a subdirectory
+    let prefix = git::repo_prefix(&args.crate_root);
+    let mut annotations: Vec<Annotation> = report
+        .sites
+        .iter()
+        .flat_map(|(filename, sites)| {
+            sites
+                .iter()
+                .enumerate()
+                .map(move |(index, site)| (filename, index, site))
+        })
+        .map(|(filename, index, site)| {
+            let (annotation_type, severity) = match site.kind {
+                SiteKind::StaticMut => ("VULNERABILITY", "HIGH"),
+                SiteKind::ThreadUnsafe => ("BUG", "HIGH"),
+                SiteKind::UnsafeBlock | SiteKind::UnsafeFn => ("VULNERABILITY", "MEDIUM"),
+                SiteKind::Allocation
+                | SiteKind::GlobalAllocator
+                | SiteKind::LeakProne
+                | SiteKind::ProcessExit
+                | SiteKind::Recursive { exposed: true } => ("CODE_SMELL", "MEDIUM"),
+                SiteKind::DeprecatedUse
+                | SiteKind::ExternClosure
+                | SiteKind::ExternFn
+                | SiteKind::FfiString
+                | SiteKind::Recursive { exposed: false }
+                | SiteKind::Repr(_)
+                | SiteKind::TrivialExpect
+                | SiteKind::Unwrap => ("CODE_SMELL", "LOW"),
+            };
+            let path = format!("{prefix}{filename}");
+            Annotation {
+                external_id: format!("crate-report-{path}-{index}"),
+                annotation_type,
+                severity,
+                summary: precommit::describe(site),
+                path,
+                line: site.line,
+            }
+        })
+        .collect();
+    if annotations.len() > MAX_ANNOTATIONS {
+        tracing::warn!(
+            "only the first {MAX_ANNOTATIONS} of {} sites are annotated",
+            annotations.len()
+        );
+        annotations.truncate(MAX_ANNOTATIONS);
+    }
+
+    let insights = Insights {
+        report: InsightsReport {
+            title: "Crate Report",
+            details: metadata.to_string(),
+            report_type: "SECURITY",
+            reporter: "crate-report",
+            link: "https://github.com/richardscollin/crate-report",
+            result: if has_violation(report, baselines) {
+                "FAILED"
+            } else {
+                "PASSED"
+            },
+            data,
+        },
+        annotations,
+    };
+    serde_json::to_string_pretty(&insights).unwrap() + "\n"
+}
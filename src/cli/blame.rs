@@ -0,0 +1,96 @@
+use std::{
+    collections::BTreeMap,
+    path::Path,
+};
+
+use super::{
+    Report,
+    SiteKind,
+    git::git,
+};
+
+/// The counted constructs last touched by a single author
+#[derive(Clone, Debug, Default)]
+pub struct AuthorStats {
+    pub unsafe_fns: isize,
+    pub unsafe_blocks: isize,
+    pub static_mut_items: isize,
+    pub unwraps: isize,
+}
+
+impl AuthorStats {
+    pub fn total(&self) -> isize {
+        self.unsafe_fns + self.unsafe_blocks + self.static_mut_items + self.unwraps
+    }
+}
+
+/// Run `git blame --line-porcelain` on a file, returning the author of each line
+fn blame_file(crate_root: &Path, filename: &str) -> Option<BTreeMap<usize, String>> {
+    let output = git(crate_root, ["blame", "--line-porcelain", "--", filename])?;
+
+    let mut authors = BTreeMap::new();
+    let mut line_number = None;
+    for line in output.lines() {
+        if let Some(author) = line.strip_prefix("author ") {
+            if let Some(line_number) = line_number.take() {
+                authors.insert(line_number, author.to_string());
+            }
+        } else if !line.starts_with('\t') {
+            // header lines are `<sha> <original line> <final line> [<group size>]`
+            let mut fields = line.split(' ');
+            if let (Some(sha), Some(_), Some(final_line)) =
+                (fields.next(), fields.next(), fields.next())
+                && sha.len() >= 40
+                && sha.bytes().all(|b| b.is_ascii_hexdigit())
+            {
+                line_number = final_line.parse().ok();
+            }
+        }
+    }
+
+    Some(authors)
+}
+
+/// Attribute every located site in the report to the last author of its line
+///
+/// Files which aren't tracked by git (or when git isn't available) are skipped.
+pub fn attribute_sites(
+    crate_root: impl AsRef<Path>,
+    report: &Report,
+) -> BTreeMap<String, AuthorStats> {
+    let crate_root = crate_root.as_ref();
+    let mut per_author: BTreeMap<String, AuthorStats> = BTreeMap::new();
+
+    for (filename, sites) in report.sites.iter().filter(|(_, sites)| !sites.is_empty()) {
+        let Some(authors) = blame_file(crate_root, filename) else {
+            continue;
+        };
+
+        for site in sites {
+            let Some(author) = authors.get(&site.line) else {
+                continue;
+            };
+            let stats = per_author.entry(author.clone()).or_default();
+            match site.kind {
+                SiteKind::StaticMut => stats.static_mut_items += 1,
+                SiteKind::UnsafeBlock => stats.unsafe_blocks += 1,
+                SiteKind::UnsafeFn => stats.unsafe_fns += 1,
+                SiteKind::Unwrap => stats.unwraps += 1,
+                SiteKind::Allocation
+                | SiteKind::DeprecatedUse
+                | SiteKind::ExternClosure
+                | SiteKind::ExternFn
+                | SiteKind::FfiString
+                | SiteKind::GlobalAllocator
+                | SiteKind::LeakProne
+                | SiteKind::ProcessExit
+                | SiteKind::Recursive { .. }
+                | SiteKind::ThreadUnsafe
+                | SiteKind::TrivialExpect
+                | SiteKind::Repr(_) => {}
+            }
+        }
+    }
+
+    per_author
+}
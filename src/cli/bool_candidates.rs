@@ -1,16 +1,17 @@
-use std::{
-    fs,
-    path::Path,
-};
-
 use syn::{
     Expr,
     ItemFn,
     ReturnType,
     Stmt,
+    Visibility,
     visit::Visit,
 };
-use walkdir::WalkDir;
+
+use super::{
+    Confidence,
+    Suggestion,
+};
+use crate::analysis::is_exported;
 
 #[derive(Clone, Default, Debug)]
 pub struct FileStats {
@@ -18,10 +19,29 @@ pub struct FileStats {
     pub stats: CodeStats,
 }
 
-#[derive(Clone, Default, Debug)]
+#[derive(Clone, Debug)]
 pub struct BoolCandidate {
     pub fn_name: String,
     pub line_number: usize,
+    pub confidence: Confidence,
+}
+
+impl Suggestion for BoolCandidate {
+    fn subject(&self) -> String {
+        self.fn_name.clone()
+    }
+
+    fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    fn confidence(&self) -> Confidence {
+        self.confidence
+    }
+
+    fn detail(&self) -> Option<String> {
+        None
+    }
 }
 
 #[derive(Clone, Default, Debug)]
@@ -200,9 +220,18 @@ impl<'a, 'ast> Visit<'ast> for CodeAnalyzer<'a> {
         {
             // Analyze the function body to see if it only returns 0 or 1
             if check_block_returns_only_zero_or_one(&i.block) {
+                // callers outside the crate, or in C, rely on the signature
+                let confidence = if i.sig.abi.is_some() || is_exported(&i.attrs) {
+                    Confidence::Low
+                } else if matches!(i.vis, Visibility::Public(_)) {
+                    Confidence::Medium
+                } else {
+                    Confidence::High
+                };
                 let candidate = BoolCandidate {
                     fn_name: i.sig.ident.to_string(),
                     line_number: i.span().start().line,
+                    confidence,
                 };
                 self.stats.candidates.push(candidate);
             }
@@ -212,54 +241,34 @@ impl<'a, 'ast> Visit<'ast> for CodeAnalyzer<'a> {
     }
 }
 
-fn analyze_file(path: &Path) -> Option<FileStats> {
-    let content = fs::read_to_string(path).ok()?;
-    let syntax = syn::parse_file(&content).ok()?;
-
+fn analyze_file(syntax: &syn::File) -> CodeStats {
     let mut stats = CodeStats::default();
     let mut visitor = CodeAnalyzer { stats: &mut stats };
-    visitor.visit_file(&syntax);
+    visitor.visit_file(syntax);
 
-    Some(FileStats {
-        filename: path.display().to_string(),
-        stats,
-    })
+    stats
 }
 
 /// Find good candidates for functions to convert from returning i32 to bool
 /// The heuristic is if the function returns i32 and all return statements
-/// and the final expression return literal 0 or 1 values
-pub fn find_candidates(root: impl AsRef<Path>) -> Vec<FileStats> {
-    let root = root.as_ref();
-    let mut file_reports = Vec::new();
-
-    for entry in WalkDir::new(root)
-        .into_iter()
-        .filter_entry(|e| {
-            e.file_name()
-                .to_str()
-                .map(|s| s != "target")
-                .unwrap_or(true)
+/// and the final expression return literal 0 or 1 values, it's high confidence
+/// for private functions and low for exported and `extern` functions
+pub fn find_candidates(
+    files: &[(String, syn::File)],
+    min_confidence: Confidence,
+) -> Vec<FileStats> {
+    files
+        .iter()
+        .map(|(filename, syntax)| {
+            let mut stats = analyze_file(syntax);
+            stats
+                .candidates
+                .retain(|candidate| candidate.confidence >= min_confidence);
+            FileStats {
+                filename: filename.clone(),
+                stats,
+            }
         })
-        .filter_map(|e| e.ok())
-        .filter(|e| e.path().extension().map(|ext| ext == "rs").unwrap_or(false))
-    {
-        let path = entry.path();
-        if let Some(file_stats) = analyze_file(path) {
-            file_reports.push(file_stats);
-        }
-    }
-
-    // Strip common root prefix and find max filename length for alignment
-    let mut max_filename_len = 0;
-    for file_report in &mut file_reports {
-        if let Ok(relative_path) = Path::new(&file_report.filename).strip_prefix(root) {
-            file_report.filename = relative_path.display().to_string();
-        }
-        max_filename_len = max_filename_len.max(file_report.filename.len());
-    }
-
-    file_reports.sort_by(|a, b| a.filename.cmp(&b.filename));
-    file_reports.retain(|r| !r.stats.candidates.is_empty());
-    file_reports
+        .filter(|report| !report.stats.candidates.is_empty())
+        .collect()
 }
@@ -0,0 +1,95 @@
+use std::collections::BTreeMap;
+
+use super::{
+    CodeStats,
+    Confidence,
+    Site,
+    bool_candidates,
+    context_candidates,
+    drop_candidates,
+    get_candidates,
+    iterator_candidates,
+    once_candidates,
+    ref_candidates,
+    safe_candidates,
+    string_candidates,
+    vec_candidates,
+};
+use crate::plugin::{
+    FileInfo,
+    MetricVisitor,
+};
+
+/// The metrics `--count-candidates` adds, named after each detector's module
+pub const METRICS: [&str; 10] = [
+    "bool_candidates",
+    "context_candidates",
+    "drop_candidates",
+    "get_candidates",
+    "iterator_candidates",
+    "once_candidates",
+    "ref_candidates",
+    "safe_candidates",
+    "string_candidates",
+    "vec_candidates",
+];
+
+/// Adds the [`METRICS`] columns, registered with `--count-candidates`
+///
+/// Some detectors match things up across files, so rather than counting file
+/// by file the columns are filled in by [`count`] once every file is parsed.
+pub struct CandidateCounts;
+
+impl MetricVisitor for CandidateCounts {
+    fn metrics(&self) -> Vec<String> {
+        METRICS.map(String::from).to_vec()
+    }
+
+    fn count(&self, _file: &syn::File, _info: FileInfo<'_>) -> BTreeMap<String, isize> {
+        BTreeMap::new()
+    }
+}
+
+/// A detector's metric and how many suggestions it makes in each file
+macro_rules! counts {
+    ($detector:ident, $files:expr, $min_confidence:expr) => {
+        (
+            stringify!($detector),
+            $detector::find_candidates($files, $min_confidence)
+                .into_iter()
+                .map(|report| (report.filename, report.stats.candidates.len()))
+                .collect::<Vec<_>>(),
+        )
+    };
+}
+
+/// Set the `*_candidates` metrics of each analyzed file to how many
+/// suggestions of at least `min_confidence` the `--*-candidates` detectors make
+/// in it
+pub fn count(
+    files: &[(String, syn::File)],
+    min_confidence: Confidence,
+    analyzed: &mut BTreeMap<String, (CodeStats, Vec<Site>)>,
+) {
+    let detectors = [
+        counts!(bool_candidates, files, min_confidence),
+        counts!(context_candidates, files, min_confidence),
+        counts!(drop_candidates, files, min_confidence),
+        counts!(get_candidates, files, min_confidence),
+        counts!(iterator_candidates, files, min_confidence),
+        counts!(once_candidates, files, min_confidence),
+        counts!(ref_candidates, files, min_confidence),
+        counts!(safe_candidates, files, min_confidence),
+        counts!(string_candidates, files, min_confidence),
+        counts!(vec_candidates, files, min_confidence),
+    ];
+    for (metric, files) in detectors {
+        for (filename, count) in files {
+            if let Some((stats, _)) = analyzed.get_mut(&filename)
+                && let Some(field) = stats.metric_mut(metric)
+            {
+                *field = count as isize;
+            }
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use std::{
+    collections::{
+        BTreeMap,
+        BTreeSet,
+    },
+    fs,
+    path::Path,
+};
+
+use serde_json::Value;
+
+/// Count clippy lints per file from `cargo clippy --message-format=json` output
+///
+/// Cargo emits one json object per line, lints are `compiler-message`s whose
+/// code starts with `clippy::`. The same lint is reported once per target
+/// (lib, tests, ...) so duplicates of the same lint at the same location are
+/// only counted once. Paths are matched to the report's relative filenames by
+/// suffix since clippy reports them relative to the workspace root.
+pub fn load<'a>(
+    path: impl AsRef<Path>,
+    filenames: impl Iterator<Item = &'a String>,
+) -> Result<BTreeMap<String, isize>, String> {
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+
+    let mut seen = BTreeSet::new();
+    let mut per_file: BTreeMap<String, isize> = BTreeMap::new();
+
+    for line in content.lines().filter(|line| line.starts_with('{')) {
+        let message: Value = serde_json::from_str(line).map_err(|e| e.to_string())?;
+        if message["reason"] != "compiler-message" {
+            continue;
+        }
+
+        let Some(code) = message["message"]["code"]["code"].as_str() else {
+            continue;
+        };
+        if !code.starts_with("clippy::") {
+            continue;
+        }
+
+        let Some(span) = message["message"]["spans"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|span| span["is_primary"] == true)
+        else {
+            continue;
+        };
+        let (Some(file_name), Some(line_start), Some(column_start)) = (
+            span["file_name"].as_str(),
+            span["line_start"].as_u64(),
+            span["column_start"].as_u64(),
+        ) else {
+            continue;
+        };
+
+        let file_name = file_name.replace('\\', "/");
+        if seen.insert((
+            file_name.clone(),
+            line_start,
+            column_start,
+            code.to_string(),
+        )) {
+            *per_file.entry(file_name).or_default() += 1;
+        }
+    }
+
+    Ok(filenames
+        .filter_map(|filename| {
+            let count: isize = per_file
+                .iter()
+                .filter(|(linted, _)| {
+                    *linted == filename || linted.ends_with(&format!("/{filename}"))
+                })
+                .map(|(_, count)| count)
+                .sum();
+            (count > 0).then(|| (filename.clone(), count))
+        })
+        .collect())
+}
@@ -0,0 +1,157 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use super::{
+    CodeStats,
+    git,
+};
+
+/// The group for files which no CODEOWNERS rule matches
+pub const UNOWNED: &str = "(unowned)";
+
+/// The default CODEOWNERS locations, in the order GitHub looks for them
+const DEFAULT_LOCATIONS: [&str; 3] = [".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// A parsed CODEOWNERS file
+#[derive(Clone, Debug, Default)]
+pub struct CodeOwners {
+    /// (pattern, owners) in file order, later rules take precedence
+    rules: Vec<(String, String)>,
+    /// the crate's path within the repository, see [`git::repo_prefix`]
+    prefix: String,
+}
+
+impl CodeOwners {
+    /// Find the CODEOWNERS file in one of the default locations under the root
+    /// of the crate's repository, or under the crate root outside of one
+    pub fn find(crate_root: impl AsRef<Path>) -> Option<PathBuf> {
+        let root = git::toplevel(&crate_root).unwrap_or_else(|| crate_root.as_ref().into());
+        DEFAULT_LOCATIONS
+            .iter()
+            .map(|location| root.join(location))
+            .find(|path| path.exists())
+    }
+
+    /// Match the crate's files, relative to `crate_root`, against patterns
+    /// relative to the root of its repository
+    pub fn in_crate(self, crate_root: impl AsRef<Path>) -> Self {
+        Self {
+            prefix: git::repo_prefix(crate_root),
+            ..self
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let rules = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                let pattern = fields.next()?.to_string();
+                let owners = fields
+                    .take_while(|owner| !owner.starts_with('#'))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                Some((pattern, owners))
+            })
+            .collect();
+        Ok(Self {
+            rules,
+            prefix: String::new(),
+        })
+    }
+
+    /// The owners of a file, relative to the repository root, as written in CODEOWNERS
+    pub fn owner_of(&self, filename: &str) -> &str {
+        let filename = filename.replace('\\', "/");
+        self.rules
+            .iter()
+            .rev()
+            .find(|(pattern, _)| pattern_matches(pattern, &filename))
+            .map(|(_, owners)| owners.as_str())
+            .filter(|owners| !owners.is_empty())
+            .unwrap_or(UNOWNED)
+    }
+
+    /// Aggregate per-file stats, relative to the crate root, into per-owner stats
+    pub fn group(&self, files: &BTreeMap<String, CodeStats>) -> BTreeMap<String, CodeStats> {
+        let mut per_owner: BTreeMap<String, Vec<CodeStats>> = BTreeMap::new();
+        for (filename, stats) in files {
+            per_owner
+                .entry(
+                    self.owner_of(&format!("{}{filename}", self.prefix))
+                        .to_string(),
+                )
+                .or_default()
+                .push(stats.clone());
+        }
+        per_owner
+            .into_iter()
+            .map(|(owner, stats)| (owner, stats.into_iter().sum()))
+            .collect()
+    }
+}
+
+/// Match a CODEOWNERS (gitignore style) pattern against a relative path
+///
+/// Patterns starting with `/` are anchored to the root, patterns without any
+/// other `/` match at any depth, and any pattern matching a directory also
+/// matches everything beneath it.
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let anchored = pattern.starts_with('/');
+    let pattern = pattern.trim_start_matches('/').trim_end_matches('/');
+    let prefix = if anchored || pattern.contains('/') {
+        ""
+    } else {
+        "**/"
+    };
+
+    [
+        format!("{prefix}{pattern}"),
+        format!("{prefix}{pattern}/**"),
+    ]
+    .iter()
+    .any(|glob| glob_matches(glob.as_bytes(), path.as_bytes()))
+}
+
+/// Minimal glob matching: `**` matches across directories, `*` and `?` don't
+fn glob_matches(glob: &[u8], path: &[u8]) -> bool {
+    match glob {
+        [] => path.is_empty(),
+        [b'*', b'*', rest @ ..] => {
+            let rest = rest.strip_prefix(b"/").unwrap_or(rest);
+            if rest.is_empty() {
+                return true;
+            }
+            // resume matching at the start of any path segment
+            let segment_starts = path
+                .iter()
+                .enumerate()
+                .filter(|(_, b)| **b == b'/')
+                .map(|(i, _)| i + 1);
+            std::iter::once(0)
+                .chain(segment_starts)
+                .any(|i| glob_matches(rest, &path[i..]))
+        }
+        [b'*', rest @ ..] => {
+            let segment_len = path.iter().position(|&b| b == b'/').unwrap_or(path.len());
+            (0..=segment_len).any(|i| glob_matches(rest, &path[i..]))
+        }
+        [b'?', rest @ ..] => match path {
+            [c, path @ ..] if *c != b'/' => glob_matches(rest, path),
+            _ => false,
+        },
+        [c, rest @ ..] => match path {
+            [p, path @ ..] if p == c => glob_matches(rest, path),
+            _ => false,
+        },
+    }
+}
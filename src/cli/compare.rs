@@ -0,0 +1,121 @@
+use std::{
+    cmp::Ordering,
+    path::Path,
+};
+
+use super::{
+    CodeStats,
+    Table,
+    analyze_crate,
+    colorize_ratio,
+    colorize_simple,
+    config,
+    metadata,
+    packages,
+    percentage,
+};
+
+/// Metrics only computed from the others, which can be ranked by too
+const COMPUTED_METRICS: [&str; 2] = ["unsafe_fn_percent", "unsafe_statements_per_kloc"];
+
+/// A crate's name and totals
+pub type Row = (String, CodeStats);
+
+/// Whether rows can be ranked by `metric`
+pub fn is_metric(metric: &str) -> bool {
+    COMPUTED_METRICS.contains(&metric) || CodeStats::default().metric(metric).is_some()
+}
+
+fn value(stats: &CodeStats, metric: &str) -> f64 {
+    match metric {
+        "unsafe_fn_percent" => percentage(stats.unsafe_fns, stats.total_fns),
+        "unsafe_statements_per_kloc" => stats.unsafe_statements_per_kloc(),
+        _ => stats.metric(metric).unwrap_or_default() as f64,
+    }
+}
+
+/// The totals of each crate root, named by their package, or of each package
+/// of the workspace at `crate_root` when none are given
+pub fn rows(crate_root: &str, crates: &[String]) -> Result<Vec<Row>, String> {
+    let check = |root: &str| {
+        if Path::new(root).join("Cargo.toml").exists() {
+            Ok(())
+        } else {
+            Err(format!("no Cargo.toml found in '{root}'"))
+        }
+    };
+    if crates.is_empty() {
+        check(crate_root)?;
+        let packages = packages::Packages::find(crate_root);
+        let report = analyze_crate(crate_root);
+        // loose scripts aren't a crate to rank
+        return Ok(packages
+            .group(&report.files)
+            .into_iter()
+            .filter(|(package, _)| package != packages::UNPACKAGED)
+            .collect());
+    }
+    crates
+        .iter()
+        .map(|root| {
+            check(root)?;
+            let name = metadata::package(root).map_or_else(|| root.clone(), |p| p.name);
+            Ok((name, analyze_crate(root).total))
+        })
+        .collect()
+}
+
+/// Rank rows by `metric`, highest (least safe) first, ties by name
+pub fn rank(rows: &mut [Row], metric: &str) {
+    rows.sort_by(|(a_name, a), (b_name, b)| {
+        value(b, metric)
+            .partial_cmp(&value(a, metric))
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| a_name.cmp(b_name))
+    });
+}
+
+/// A row per crate in the given (ranked) order
+pub fn to_table(rows: &[Row]) -> Table {
+    let mut table = Table::with_headers(vec![
+        "crate".into(),
+        " (unsafe/total) fns".into(),
+        "unsafe fn %".into(),
+        "statements".into(),
+        "unsafe/kloc".into(),
+        "static mut".into(),
+        "unwrap".into(),
+        "lines".into(),
+    ]);
+    table.extend_rows(rows.iter().map(|(name, stats)| {
+        vec![
+            name.as_str().into(),
+            colorize_ratio(stats.unsafe_fns, stats.total_fns),
+            format!("{:.1}", percentage(stats.unsafe_fns, stats.total_fns)).into(),
+            format!("{}/{}", stats.unsafe_statements, stats.total_statements).into(),
+            format!("{:.2}", stats.unsafe_statements_per_kloc()).into(),
+            colorize_simple(
+                stats.static_mut_items,
+                config::threshold("static_mut_items"),
+            ),
+            colorize_simple(stats.unwraps, config::threshold("unwraps")),
+            stats.total_lines.to_string().into(),
+        ]
+    }));
+    table
+}
+
+/// The CSV output's columns, with a `crate` column in place of `filename`
+pub fn write_csv(out: impl std::io::Write, rows: &[Row]) -> Result<(), String> {
+    let mut writer = csv::Writer::from_writer(out);
+    let mut headers = CodeStats::csv_headers();
+    headers[0] = "crate".to_string();
+    headers.extend(CodeStats::COMPUTED_CSV_HEADERS.map(String::from));
+    writer.write_record(headers).map_err(|e| e.to_string())?;
+    for (name, stats) in rows {
+        let mut row = stats.to_csv_row(name.clone());
+        row.extend(stats.computed_csv_row());
+        writer.write_record(row).map_err(|e| e.to_string())?;
+    }
+    writer.flush().map_err(|e| e.to_string())
+}
@@ -0,0 +1,127 @@
+use std::{
+    collections::{
+        BTreeMap,
+        BTreeSet,
+    },
+    fs,
+    path::Path,
+};
+
+use syn::{
+    Expr,
+    ExprCall,
+    ItemUse,
+    UseTree,
+    visit::Visit,
+};
+
+use super::Report;
+
+/// The shared-state primitives a file creates
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Surface {
+    pub mutexes: usize,
+    pub rw_locks: usize,
+    pub condvars: usize,
+    /// any `Atomic*`, like `AtomicUsize` or `AtomicPtr`
+    pub atomics: usize,
+    pub spawns: usize,
+    pub unsafe_cells: usize,
+}
+
+impl Surface {
+    pub fn total(&self) -> usize {
+        self.mutexes
+            + self.rw_locks
+            + self.condvars
+            + self.atomics
+            + self.spawns
+            + self.unsafe_cells
+    }
+}
+
+#[derive(Default)]
+struct SurfaceCollector {
+    /// fns imported with `use std::thread::{...}`, called without `thread::`
+    imported: BTreeSet<String>,
+    surface: Surface,
+}
+
+/// Collect the names a `use` tree imports from a `thread` module, globs can't
+/// be told apart from other calls so they're skipped
+fn thread_names(tree: &UseTree, in_thread: bool, names: &mut BTreeSet<String>) {
+    match tree {
+        UseTree::Path(path) => thread_names(&path.tree, path.ident == "thread", names),
+        UseTree::Name(name) if in_thread => {
+            names.insert(name.ident.to_string());
+        }
+        UseTree::Group(group) => {
+            for tree in &group.items {
+                thread_names(tree, in_thread, names);
+            }
+        }
+        UseTree::Name(_) | UseTree::Rename(_) | UseTree::Glob(_) => {}
+    }
+}
+
+impl<'ast> Visit<'ast> for SurfaceCollector {
+    fn visit_item_use(&mut self, i: &'ast ItemUse) {
+        thread_names(&i.tree, false, &mut self.imported);
+        syn::visit::visit_item_use(self, i);
+    }
+
+    fn visit_expr_call(&mut self, i: &'ast ExprCall) {
+        if let Expr::Path(expr) = &*i.func {
+            let segments: Vec<String> = expr
+                .path
+                .segments
+                .iter()
+                .map(|segment| segment.ident.to_string())
+                .collect();
+            let surface = &mut self.surface;
+            match segments.as_slice() {
+                [.., ty, new] if new == "new" => match ty.as_str() {
+                    "Mutex" => surface.mutexes += 1,
+                    "RwLock" => surface.rw_locks += 1,
+                    "Condvar" => surface.condvars += 1,
+                    "UnsafeCell" => surface.unsafe_cells += 1,
+                    ty if ty.starts_with("Atomic") => surface.atomics += 1,
+                    _ => {}
+                },
+                [.., thread, spawn] if thread == "thread" && spawn == "spawn" => {
+                    surface.spawns += 1;
+                }
+                [spawn] if spawn == "spawn" && self.imported.contains(spawn) => {
+                    surface.spawns += 1;
+                }
+                _ => {}
+            }
+        }
+        syn::visit::visit_expr_call(self, i);
+    }
+}
+
+/// Count the `Mutex`, `RwLock`, `Condvar`, `Atomic*` and `UnsafeCell`
+/// constructions and `thread::spawn` calls in each file of the report, by
+/// their `new` calls so a type named in a signature isn't counted, and those
+/// made inside macro invocations aren't seen
+pub fn census(root: impl AsRef<Path>, report: &Report) -> BTreeMap<String, Surface> {
+    report
+        .files
+        .keys()
+        .filter_map(|filename| {
+            let content = fs::read_to_string(root.as_ref().join(filename)).ok()?;
+            let syntax = syn::parse_file(&content).ok()?;
+            let mut collector = SurfaceCollector::default();
+            // imports can follow the calls they're for, so take the top-level
+            // ones first
+            for item in &syntax.items {
+                if let syn::Item::Use(item) = item {
+                    collector.visit_item_use(item);
+                }
+            }
+            collector.visit_file(&syntax);
+            (collector.surface.total() > 0).then(|| (filename.clone(), collector.surface))
+        })
+        .collect()
+}
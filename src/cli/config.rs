@@ -0,0 +1,284 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+    sync::OnceLock,
+};
+
+use serde::Deserialize;
+
+use super::DecreaseIs;
+
+/// The config file looked for in the crate root when `--config` isn't given
+pub const DEFAULT_CONFIG_FILE: &str = "crate-report.toml";
+
+/// Settings from `crate-report.toml`
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// colorization thresholds keyed by metric name
+    pub thresholds: BTreeMap<String, Threshold>,
+    /// whether a decrease in each metric is `good`, `bad` or `neutral` in diffs
+    pub directions: BTreeMap<String, DecreaseIs>,
+    /// truncate longer filenames in the middle, in the report table
+    pub max_path_width: Option<usize>,
+    /// `total_*` metrics whose changes alone make a file show as changed in
+    /// diffs, which by default only counts the safety metrics
+    pub report_changes: Vec<String>,
+    /// how markdown and pr-comment reports are written
+    pub markdown: Markdown,
+    /// time-series databases the totals and per-directory metrics are pushed to
+    pub export: Export,
+    /// weights of the per-file risk score ranking `--audit-next`
+    pub risk: Risk,
+    /// how much a metric may regress before it's a warning or a failure,
+    /// keyed by metric name
+    pub severity: BTreeMap<String, Tiers>,
+    /// project-specific fns and macros (with a trailing `!`) whose uses are
+    /// counted as an extra metric, keyed by the metric's name
+    pub patterns: BTreeMap<String, Vec<String>>,
+}
+
+/// The regression (growth, or shrinkage for metrics where a decrease is bad)
+/// at which a metric's change is a warning and a failure, either can be left
+/// out to never reach that tier
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Tiers {
+    pub warn: Option<isize>,
+    pub fail: Option<isize>,
+}
+
+/// How much each finding adds to a file's risk score
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Risk {
+    /// per unsafe statement
+    pub unsafe_statements: f64,
+    /// per static mut item
+    pub static_mut_items: f64,
+    /// per call to a `*_unchecked` fn, e.g. `get_unchecked`
+    pub unchecked_calls: f64,
+    /// per unsafe block without a `SAFETY:` comment
+    pub missing_safety_comments: f64,
+    /// for a file without any coverage, scaled down by the lines covered, only
+    /// with `--coverage`
+    pub low_coverage: f64,
+}
+
+impl Default for Risk {
+    fn default() -> Self {
+        Self {
+            unsafe_statements: 1.0,
+            static_mut_items: 5.0,
+            unchecked_calls: 3.0,
+            missing_safety_comments: 2.0,
+            low_coverage: 10.0,
+        }
+    }
+}
+
+/// Where to push metrics after each run, both are optional
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Export {
+    /// an InfluxDB write endpoint taking line protocol, authenticated with the
+    /// `INFLUXDB_TOKEN` environment variable if set
+    pub influxdb: Option<String>,
+    /// a StatsD `host:port`, metrics are sent as gauges over UDP
+    pub statsd: Option<String>,
+    /// the InfluxDB measurement and StatsD name prefix, `crate_report` unless set
+    pub prefix: Option<String>,
+    /// added to every point, as DogStatsD tags for StatsD
+    pub tags: BTreeMap<String, String>,
+}
+
+/// Markdown styling, for renderers which don't support all of GFM
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Markdown {
+    /// by default markdown reports underline their headings and pr-comment
+    /// uses `#`s
+    pub headings: Option<HeadingStyle>,
+    /// the level of the top headings, 1 unless set, nested ones are deeper
+    /// and pr-comment headings start one level down
+    pub heading_level: Option<u8>,
+    /// only ASCII characters, e.g. `->` rather than `→`
+    pub ascii: bool,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HeadingStyle {
+    /// `# Title`
+    Atx,
+    /// `Title` underlined with `=` or `-`
+    Setext,
+}
+
+impl Config {
+    /// Load `path`, or the default config file in the crate root if there is one
+    pub fn load(crate_root: impl AsRef<Path>, path: Option<&str>) -> Result<Self, String> {
+        let path = match path {
+            Some(path) => PathBuf::from(path),
+            None => {
+                let path = crate_root.as_ref().join(DEFAULT_CONFIG_FILE);
+                if !path.exists() {
+                    return Ok(Self::default());
+                }
+                path
+            }
+        };
+        let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        toml::from_str(&content).map_err(|e| e.to_string())
+    }
+}
+
+/// Where a metric turns from green to yellow to red
+///
+/// Values above `warning` are yellow and values at or above `danger` are red.
+/// `unsafe_fns` is a percentage of all fns, every other metric is a count.
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Threshold {
+    pub warning: f64,
+    pub danger: f64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Level {
+    Good,
+    Warning,
+    Danger,
+}
+
+impl Threshold {
+    /// zero is green, single digit is yellow, more than that is red
+    pub const COUNT: Self = Self {
+        warning: 0.0,
+        danger: 10.0,
+    };
+
+    /// none is green, less than half is yellow, more than that is red
+    pub const PERCENT: Self = Self {
+        warning: 0.0,
+        danger: 50.0,
+    };
+
+    pub fn level(&self, value: f64) -> Level {
+        if value >= self.danger {
+            Level::Danger
+        } else if value > self.warning {
+            Level::Warning
+        } else {
+            Level::Good
+        }
+    }
+
+    /// Parse a `--threshold` value like `unwraps=100,1000`
+    pub fn parse_arg(arg: &str) -> Result<(String, Self), String> {
+        let (metric, values) = arg
+            .split_once('=')
+            .ok_or("expected METRIC=WARNING,DANGER")?;
+        let (warning, danger) = values
+            .split_once(',')
+            .ok_or("expected METRIC=WARNING,DANGER")?;
+        let parse = |value: &str| {
+            value
+                .trim()
+                .parse::<f64>()
+                .map_err(|_| format!("invalid threshold '{value}'"))
+        };
+        Ok((
+            metric.trim().to_string(),
+            Self {
+                warning: parse(warning)?,
+                danger: parse(danger)?,
+            },
+        ))
+    }
+}
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// Set the config used for formatting, called once at startup
+pub fn set(config: Config) {
+    _ = CONFIG.set(config);
+}
+
+fn get() -> &'static Config {
+    CONFIG.get_or_init(Config::default)
+}
+
+/// The threshold for a metric, falling back to the built-in defaults
+pub fn threshold(metric: &str) -> Threshold {
+    get()
+        .thresholds
+        .get(metric)
+        .copied()
+        .unwrap_or(if metric == "unsafe_fns" {
+            Threshold::PERCENT
+        } else {
+            Threshold::COUNT
+        })
+}
+
+/// Thresholds set in the config or with `--threshold`, which `--quiet` enforces
+pub fn configured_thresholds() -> impl Iterator<Item = (&'static str, Threshold)> {
+    get()
+        .thresholds
+        .iter()
+        .map(|(metric, threshold)| (metric.as_str(), *threshold))
+}
+
+pub fn max_path_width() -> Option<usize> {
+    get().max_path_width
+}
+
+pub fn markdown() -> &'static Markdown {
+    &get().markdown
+}
+
+pub fn export() -> &'static Export {
+    &get().export
+}
+
+pub fn risk() -> &'static Risk {
+    &get().risk
+}
+
+/// A metric's severity tiers, by default any regression fails
+pub fn tiers(metric: &str) -> Tiers {
+    get().severity.get(metric).copied().unwrap_or(Tiers {
+        warn: Some(1),
+        fail: Some(1),
+    })
+}
+
+/// Whether a change in a metric alone makes a file show as changed in diffs,
+/// totals only do when listed in `report_changes`
+pub fn reports_change(metric: &str) -> bool {
+    !metric.starts_with("total_") || get().report_changes.iter().any(|m| m == metric)
+}
+
+/// What a decrease in a metric means, by default totals and FFI boundaries
+/// are neutral and everything else is good to reduce
+pub fn decrease_is(metric: &str) -> DecreaseIs {
+    if let Some(direction) = get().directions.get(metric) {
+        return *direction;
+    }
+    if let Some(sparse) = super::SPARSE_METRICS
+        .iter()
+        .find(|sparse| sparse.metric == metric)
+    {
+        return sparse.decrease_is;
+    }
+    if metric.starts_with("total_") {
+        DecreaseIs::Neutral
+    } else {
+        DecreaseIs::Good
+    }
+}
@@ -0,0 +1,230 @@
+use std::collections::{
+    BTreeMap,
+    BTreeSet,
+};
+
+use syn::{
+    ExprPath,
+    ImplItemFn,
+    ItemFn,
+    ItemStatic,
+    StaticMutability,
+    spanned::Spanned,
+    visit::Visit,
+};
+
+use super::{
+    Confidence,
+    Suggestion,
+};
+
+#[derive(Clone, Default, Debug)]
+pub struct FileStats {
+    pub filename: String,
+    pub stats: CodeStats,
+}
+
+#[derive(Clone, Debug)]
+pub struct ContextCandidate {
+    /// where the group's first `static mut` is declared
+    pub line_number: usize,
+    /// the context struct's fields, as `name: Type`
+    pub fields: Vec<String>,
+    /// the functions which would take the context, `file.rs:name` when
+    /// they're in another file than the first static
+    pub functions: Vec<String>,
+    pub confidence: Confidence,
+}
+
+impl Suggestion for ContextCandidate {
+    fn subject(&self) -> String {
+        format!("struct {{ {} }}", self.fields.join(", "))
+    }
+
+    fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    fn confidence(&self) -> Confidence {
+        self.confidence
+    }
+
+    fn detail(&self) -> Option<String> {
+        let functions: Vec<String> = self
+            .functions
+            .iter()
+            .map(|function| format!("`{function}`"))
+            .collect();
+        Some(format!("used by {}", functions.join(", ")))
+    }
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct CodeStats {
+    pub candidates: Vec<ContextCandidate>,
+}
+
+/// A `static mut` item
+pub struct Global {
+    pub ty: String,
+    pub filename: String,
+    pub line_number: usize,
+}
+
+/// Collects the crate's `static mut` items
+#[derive(Default)]
+struct Globals {
+    filename: String,
+    globals: BTreeMap<String, Global>,
+}
+
+impl<'ast> Visit<'ast> for Globals {
+    fn visit_item_static(&mut self, i: &'ast ItemStatic) {
+        if matches!(i.mutability, StaticMutability::Mut(_)) {
+            self.globals.insert(
+                i.ident.to_string(),
+                Global {
+                    ty: i.ty.span().source_text().unwrap_or_else(|| "_".to_string()),
+                    filename: self.filename.clone(),
+                    line_number: i.ident.span().start().line,
+                },
+            );
+        }
+        syn::visit::visit_item_static(self, i);
+    }
+}
+
+/// Collects the functions, as filename and name, using each global
+struct Accesses<'a> {
+    globals: &'a BTreeMap<String, Global>,
+    filename: String,
+    /// the innermost fn
+    fn_name: Option<String>,
+    accessors: BTreeMap<String, BTreeSet<(String, String)>>,
+}
+
+impl<'ast> Visit<'ast> for Accesses<'_> {
+    fn visit_item_fn(&mut self, i: &'ast ItemFn) {
+        let outer = self.fn_name.replace(i.sig.ident.to_string());
+        syn::visit::visit_item_fn(self, i);
+        self.fn_name = outer;
+    }
+
+    fn visit_impl_item_fn(&mut self, i: &'ast ImplItemFn) {
+        let outer = self.fn_name.replace(i.sig.ident.to_string());
+        syn::visit::visit_impl_item_fn(self, i);
+        self.fn_name = outer;
+    }
+
+    fn visit_expr_path(&mut self, i: &'ast ExprPath) {
+        if let Some(fn_name) = &self.fn_name
+            && let Some(segment) = i.path.segments.last()
+        {
+            let name = segment.ident.to_string();
+            if self.globals.contains_key(&name) {
+                self.accessors
+                    .entry(name)
+                    .or_default()
+                    .insert((self.filename.clone(), fn_name.clone()));
+            }
+        }
+        syn::visit::visit_expr_path(self, i);
+    }
+}
+
+/// The `static mut` items of all the files, by name
+pub fn static_muts(files: &[(String, syn::File)]) -> BTreeMap<String, Global> {
+    let mut globals = Globals::default();
+    for (filename, syntax) in files {
+        globals.filename.clone_from(filename);
+        globals.visit_file(syntax);
+    }
+    globals.globals
+}
+
+/// Find `static mut` items which could be the fields of a context struct
+/// The heuristic is two or more statics used by exactly the same functions,
+/// statics are matched by name so a local of the same name counts as a use
+/// It's high confidence when the statics and functions are all in one file,
+/// and medium when the context would be passed between files
+pub fn find_candidates(
+    files: &[(String, syn::File)],
+    min_confidence: Confidence,
+) -> Vec<FileStats> {
+    // statics are used from other files than their own
+    let globals = static_muts(files);
+    let mut accesses = Accesses {
+        globals: &globals,
+        filename: String::new(),
+        fn_name: None,
+        accessors: BTreeMap::new(),
+    };
+    for (filename, syntax) in files {
+        accesses.filename.clone_from(filename);
+        accesses.visit_file(syntax);
+    }
+
+    let mut groups: BTreeMap<&BTreeSet<(String, String)>, Vec<&String>> = BTreeMap::new();
+    for (name, functions) in &accesses.accessors {
+        groups.entry(functions).or_default().push(name);
+    }
+
+    let mut file_reports: BTreeMap<String, CodeStats> = BTreeMap::new();
+    for (functions, names) in groups {
+        if names.len() < 2 {
+            continue;
+        }
+        let first = names
+            .iter()
+            .map(|name| &globals[*name])
+            .min_by_key(|global| (&global.filename, global.line_number))
+            .expect("groups aren't empty");
+        let in_one_file = names
+            .iter()
+            .map(|name| &globals[*name].filename)
+            .chain(functions.iter().map(|(filename, _)| filename))
+            .all(|filename| *filename == first.filename);
+        let confidence = if in_one_file {
+            Confidence::High
+        } else {
+            Confidence::Medium
+        };
+        if confidence < min_confidence {
+            continue;
+        }
+        let fields = names
+            .iter()
+            .map(|name| format!("{}: {}", name.to_lowercase(), globals[*name].ty))
+            .collect();
+        let functions = functions
+            .iter()
+            .map(|(filename, fn_name)| {
+                if *filename == first.filename {
+                    fn_name.clone()
+                } else {
+                    format!("{filename}:{fn_name}")
+                }
+            })
+            .collect();
+        file_reports
+            .entry(first.filename.clone())
+            .or_default()
+            .candidates
+            .push(ContextCandidate {
+                line_number: first.line_number,
+                fields,
+                functions,
+                confidence,
+            });
+    }
+
+    file_reports
+        .into_iter()
+        .map(|(filename, mut stats)| {
+            stats
+                .candidates
+                .sort_by_key(|candidate| candidate.line_number);
+            FileStats { filename, stats }
+        })
+        .collect()
+}
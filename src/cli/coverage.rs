@@ -0,0 +1,126 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::Path,
+};
+
+use serde_json::Value;
+
+/// Line hit counts for a single source file
+#[derive(Clone, Debug, Default)]
+pub struct FileCoverage {
+    /// line number -> execution count, only for lines which are instrumented
+    lines: BTreeMap<usize, u64>,
+}
+
+impl FileCoverage {
+    /// Percentage of instrumented lines which were executed
+    pub fn percent(&self) -> Option<f64> {
+        Self::percent_of(self.lines.values())
+    }
+
+    /// Percentage of instrumented lines within `start..=end` which were executed
+    pub fn percent_in(&self, start: usize, end: usize) -> Option<f64> {
+        Self::percent_of(self.lines.range(start..=end).map(|(_, hits)| hits))
+    }
+
+    fn percent_of<'a>(hits: impl Iterator<Item = &'a u64>) -> Option<f64> {
+        let (covered, total) = hits.fold((0, 0), |(covered, total), &hits| {
+            (covered + (hits > 0) as usize, total + 1)
+        });
+        (total > 0).then(|| covered as f64 / total as f64 * 100.0)
+    }
+}
+
+/// Parse an lcov tracefile (`SF:`, `DA:` and `end_of_record` lines)
+fn parse_lcov(content: &str) -> BTreeMap<String, FileCoverage> {
+    let mut files = BTreeMap::new();
+    let mut current: Option<(String, FileCoverage)> = None;
+
+    for line in content.lines() {
+        if let Some(path) = line.strip_prefix("SF:") {
+            current = Some((path.to_string(), FileCoverage::default()));
+        } else if let Some(data) = line.strip_prefix("DA:")
+            && let Some((_, coverage)) = &mut current
+        {
+            let mut fields = data.split(',');
+            if let (Some(Ok(line)), Some(Ok(hits))) = (
+                fields.next().map(str::parse::<usize>),
+                fields.next().map(str::parse::<u64>),
+            ) {
+                *coverage.lines.entry(line).or_default() += hits;
+            }
+        } else if line == "end_of_record"
+            && let Some((path, coverage)) = current.take()
+        {
+            files.insert(path, coverage);
+        }
+    }
+
+    files
+}
+
+/// Parse the output of `cargo llvm-cov --json` / `llvm-cov export`
+///
+/// Each segment is `[line, col, count, has_count, is_region_entry, is_gap]`, a
+/// line is considered executed if any counted segment starting on it was.
+fn parse_llvm_cov_json(content: &str) -> Result<BTreeMap<String, FileCoverage>, String> {
+    let json: Value = serde_json::from_str(content).map_err(|e| e.to_string())?;
+    let mut files = BTreeMap::new();
+
+    let exports = json["data"].as_array().ok_or("missing `data` array")?;
+    for file in exports
+        .iter()
+        .flat_map(|export| export["files"].as_array())
+        .flatten()
+    {
+        let Some(filename) = file["filename"].as_str() else {
+            continue;
+        };
+        let mut coverage = FileCoverage::default();
+        for segment in file["segments"].as_array().into_iter().flatten() {
+            let (Some(line), Some(count), Some(true)) = (
+                segment[0].as_u64(),
+                segment[2].as_u64(),
+                segment[3].as_bool(),
+            ) else {
+                continue;
+            };
+            let hits = coverage.lines.entry(line as usize).or_default();
+            *hits = (*hits).max(count);
+        }
+        files.insert(filename.to_string(), coverage);
+    }
+
+    Ok(files)
+}
+
+/// Load a coverage file and match its entries to the report's relative filenames
+///
+/// Coverage tools usually record absolute paths, so an entry is matched to a
+/// report file if its path ends with the report's relative path.
+pub fn load<'a>(
+    path: impl AsRef<Path>,
+    filenames: impl Iterator<Item = &'a String>,
+) -> Result<BTreeMap<String, FileCoverage>, String> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+
+    let is_json = path.extension().map(|ext| ext == "json").unwrap_or(false)
+        || content.trim_start().starts_with('{');
+    let coverage = if is_json {
+        parse_llvm_cov_json(&content)?
+    } else {
+        parse_lcov(&content)
+    };
+
+    Ok(filenames
+        .filter_map(|filename| {
+            let (_, file_coverage) = coverage.iter().find(|(covered, _)| {
+                let covered = covered.replace('\\', "/");
+                covered == *filename || covered.ends_with(&format!("/{filename}"))
+            })?;
+            Some((filename.clone(), file_coverage.clone()))
+        })
+        .collect())
+}
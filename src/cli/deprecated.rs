@@ -0,0 +1,198 @@
+use std::{
+    collections::BTreeSet,
+    fs,
+};
+
+use syn::{
+    Attribute,
+    ExprMethodCall,
+    Ident,
+    ImplItem,
+    Item,
+    ItemImpl,
+    Path,
+    TraitItem,
+    Variant,
+    visit::Visit,
+};
+
+use super::{
+    CodeStats,
+    Site,
+    SiteKind,
+};
+
+fn is_deprecated(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| attr.path().is_ident("deprecated"))
+}
+
+/// Collects the names of the items marked `#[deprecated]`
+#[derive(Default)]
+struct DefinitionCollector {
+    names: BTreeSet<String>,
+}
+
+impl DefinitionCollector {
+    fn check(&mut self, attrs: &[Attribute], ident: &Ident) {
+        if is_deprecated(attrs) {
+            self.names.insert(ident.to_string());
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for DefinitionCollector {
+    fn visit_item(&mut self, i: &'ast Item) {
+        match i {
+            Item::Const(item) => self.check(&item.attrs, &item.ident),
+            Item::Enum(item) => self.check(&item.attrs, &item.ident),
+            Item::Fn(item) => self.check(&item.attrs, &item.sig.ident),
+            Item::Macro(item) => {
+                if let Some(ident) = &item.ident {
+                    self.check(&item.attrs, ident);
+                }
+            }
+            Item::Mod(item) => self.check(&item.attrs, &item.ident),
+            Item::Static(item) => self.check(&item.attrs, &item.ident),
+            Item::Struct(item) => self.check(&item.attrs, &item.ident),
+            Item::Trait(item) => self.check(&item.attrs, &item.ident),
+            Item::Type(item) => self.check(&item.attrs, &item.ident),
+            Item::Union(item) => self.check(&item.attrs, &item.ident),
+            _ => {}
+        }
+        syn::visit::visit_item(self, i);
+    }
+
+    fn visit_impl_item(&mut self, i: &'ast ImplItem) {
+        match i {
+            ImplItem::Const(item) => self.check(&item.attrs, &item.ident),
+            ImplItem::Fn(item) => self.check(&item.attrs, &item.sig.ident),
+            ImplItem::Type(item) => self.check(&item.attrs, &item.ident),
+            _ => {}
+        }
+        syn::visit::visit_impl_item(self, i);
+    }
+
+    fn visit_trait_item(&mut self, i: &'ast TraitItem) {
+        match i {
+            TraitItem::Const(item) => self.check(&item.attrs, &item.ident),
+            TraitItem::Fn(item) => self.check(&item.attrs, &item.sig.ident),
+            TraitItem::Type(item) => self.check(&item.attrs, &item.ident),
+            _ => {}
+        }
+        syn::visit::visit_trait_item(self, i);
+    }
+
+    fn visit_variant(&mut self, i: &'ast Variant) {
+        self.check(&i.attrs, &i.ident);
+        syn::visit::visit_variant(self, i);
+    }
+}
+
+/// Finds the paths and method calls naming a deprecated item, `use`
+/// declarations aren't counted
+struct UseCollector<'a> {
+    names: &'a BTreeSet<String>,
+    sites: Vec<Site>,
+}
+
+impl UseCollector<'_> {
+    fn push(&mut self, name: &Ident) {
+        let span = name.span();
+        self.sites.push(Site {
+            kind: SiteKind::DeprecatedUse,
+            name: name.to_string(),
+            line: span.start().line,
+            end_line: span.end().line,
+        });
+    }
+}
+
+impl<'ast> Visit<'ast> for UseCollector<'_> {
+    fn visit_path(&mut self, i: &'ast Path) {
+        // the first deprecated segment, `old::new_fn` and `Old::method` are
+        // one use each
+        if let Some(segment) = i
+            .segments
+            .iter()
+            .find(|segment| self.names.contains(&segment.ident.to_string()))
+        {
+            self.push(&segment.ident);
+        }
+        syn::visit::visit_path(self, i);
+    }
+
+    fn visit_expr_method_call(&mut self, i: &'ast ExprMethodCall) {
+        if self.names.contains(&i.method.to_string()) {
+            self.push(&i.method);
+        }
+        syn::visit::visit_expr_method_call(self, i);
+    }
+
+    /// The type an impl is for is part of its definition rather than a use,
+    /// only the trait and the items are looked at
+    fn visit_item_impl(&mut self, i: &'ast ItemImpl) {
+        if let Some((_, path, _)) = &i.trait_ {
+            self.visit_path(path);
+        }
+        for item in &i.items {
+            self.visit_impl_item(item);
+        }
+    }
+
+    /// Attribute paths, like `#[allow(deprecated)]`, aren't uses
+    fn visit_attribute(&mut self, _i: &'ast Attribute) {}
+}
+
+/// The names of the items marked `#[deprecated]` in any of the sources, only
+/// those mentioning `deprecated` are parsed
+pub fn definitions<'a>(sources: impl IntoIterator<Item = &'a str>) -> BTreeSet<String> {
+    let mut definitions = DefinitionCollector::default();
+    for source in sources {
+        if source.contains("deprecated")
+            && let Ok(syntax) = syn::parse_file(source)
+        {
+            definitions.visit_file(&syntax);
+        }
+    }
+    definitions.names
+}
+
+/// The [`definitions`] in the files at `paths`
+pub fn definitions_in(
+    paths: impl IntoIterator<Item = impl AsRef<std::path::Path>>,
+) -> BTreeSet<String> {
+    let sources: Vec<String> = paths
+        .into_iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .filter(|source| source.contains("deprecated"))
+        .collect();
+    definitions(sources.iter().map(String::as_str))
+}
+
+/// Whether a source mentions one of the deprecated `names`, so it may use it
+pub fn is_mentioned(names: &BTreeSet<String>, source: &str) -> bool {
+    names.iter().any(|name| source.contains(name.as_str()))
+}
+
+/// Set a file's `deprecated_uses` to its uses of the crate's `#[deprecated]`
+/// items, by their `names`, adding a site per use
+///
+/// Definitions are matched by their attribute and uses by name alone, so a
+/// method with the name of a deprecated one counts too.
+pub fn count_uses(
+    names: &BTreeSet<String>,
+    source: &str,
+    syntax: &syn::File,
+    (stats, sites): &mut (CodeStats, Vec<Site>),
+) {
+    if !is_mentioned(names, source) {
+        return;
+    }
+    let mut uses = UseCollector {
+        names,
+        sites: Vec::new(),
+    };
+    uses.visit_file(syntax);
+    stats.deprecated_uses = uses.sites.len() as isize;
+    sites.extend(uses.sites);
+}
@@ -0,0 +1,178 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use super::{
+    Args,
+    CSV_TOTAL_ROW,
+    CodeStats,
+    Diff,
+    DiffReport,
+    Report,
+    Versioned,
+    config,
+    display_diff,
+};
+
+/// A metric compared against the baseline, `before` and `delta` are `null`
+/// when the baseline didn't have the metric
+#[derive(Serialize)]
+struct MetricChange {
+    before: Option<isize>,
+    after: isize,
+    delta: Option<isize>,
+    /// whether it moved in the direction configured as bad
+    regression: bool,
+}
+
+#[derive(Serialize)]
+struct FileChange {
+    status: &'static str,
+    metrics: BTreeMap<String, MetricChange>,
+}
+
+#[derive(Serialize)]
+struct DiffOutput {
+    /// the first baseline's label, its file stem
+    baseline: String,
+    totals: BTreeMap<String, MetricChange>,
+    /// only the files which were added, removed or had a metric change, and the
+    /// `relocated` and `unchanged` ones with `--suppress-churn` and
+    /// `--show-unchanged`
+    files: BTreeMap<String, FileChange>,
+    unknown_metrics: Vec<String>,
+}
+
+fn first_diff(
+    report: &Report,
+    baselines: &[(String, Report)],
+    args: &Args,
+) -> (String, DiffReport) {
+    let (label, baseline) = baselines
+        .first()
+        .expect("--baseline is checked for diff formats");
+    (label.clone(), display_diff(report, baseline, args))
+}
+
+fn metric_changes(
+    before: &CodeStats,
+    after: &CodeStats,
+    diff: &DiffReport,
+) -> BTreeMap<String, MetricChange> {
+    CodeStats::csv_headers()
+        .into_iter()
+        .skip(1)
+        .map(|metric| {
+            let after = after.metric(&metric).unwrap_or_default();
+            let change = if diff.unknown_metrics.contains(&metric) {
+                MetricChange {
+                    before: None,
+                    after,
+                    delta: None,
+                    regression: false,
+                }
+            } else {
+                let before = before.metric(&metric).unwrap_or_default();
+                MetricChange {
+                    before: Some(before),
+                    after,
+                    delta: Some(after - before),
+                    regression: config::decrease_is(&metric).is_regression(before, after),
+                }
+            };
+            (metric, change)
+        })
+        .collect()
+}
+
+/// A file's status, and its stats before and after, zero when it was added or
+/// removed
+fn file_change(change: &Diff) -> (&'static str, CodeStats, CodeStats) {
+    match change {
+        Diff::Added(after) => ("added", CodeStats::default(), after.clone()),
+        Diff::Changed(change) => ("changed", change.before.clone(), change.after.clone()),
+        Diff::Removed(before) => ("removed", before.clone(), CodeStats::default()),
+    }
+}
+
+fn to_output(label: String, diff: &DiffReport) -> DiffOutput {
+    DiffOutput {
+        baseline: label,
+        totals: metric_changes(&diff.before_total, &diff.after_total, diff),
+        files: diff
+            .changes
+            .iter()
+            .map(|(filename, change)| {
+                let (status, before, after) = file_change(change);
+                let metrics = metric_changes(&before, &after, diff);
+                (filename.clone(), FileChange { status, metrics })
+            })
+            .chain(diff.relocated.iter().map(|(filename, change)| {
+                let (_, before, after) = file_change(change);
+                let metrics = metric_changes(&before, &after, diff);
+                (
+                    filename.clone(),
+                    FileChange {
+                        status: "relocated",
+                        metrics,
+                    },
+                )
+            }))
+            .chain(diff.unchanged.iter().map(|(filename, stats)| {
+                let metrics = metric_changes(stats, stats, diff);
+                (
+                    filename.clone(),
+                    FileChange {
+                        status: "unchanged",
+                        metrics,
+                    },
+                )
+            }))
+            .collect(),
+        unknown_metrics: diff.unknown_metrics.iter().cloned().collect(),
+    }
+}
+
+/// The comparison against the first baseline as JSON, before, after and delta
+/// per metric for the totals and each changed file
+pub fn format_diff_json(report: &Report, baselines: &[(String, Report)], args: &Args) -> String {
+    let (label, diff) = first_diff(report, baselines, args);
+    serde_json::to_string_pretty(&Versioned::new(to_output(label, &diff))).unwrap() + "\n"
+}
+
+/// The comparison against the first baseline as CSV, a row per file and metric
+/// with the totals last, in the `TOTAL` rows
+pub fn format_diff_csv(report: &Report, baselines: &[(String, Report)], args: &Args) -> String {
+    let (label, diff) = first_diff(report, baselines, args);
+    let output = to_output(label, &diff);
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    _ = writer.write_record([
+        "filename",
+        "status",
+        "metric",
+        "before",
+        "after",
+        "delta",
+        "regression",
+    ]);
+    let rows = output
+        .files
+        .iter()
+        .map(|(filename, file)| (filename.as_str(), file.status, &file.metrics))
+        .chain([(CSV_TOTAL_ROW, "", &output.totals)]);
+    for (filename, status, metrics) in rows {
+        for (metric, change) in metrics {
+            let optional = |value: Option<isize>| value.map(|v| v.to_string()).unwrap_or_default();
+            _ = writer.write_record([
+                filename,
+                status,
+                metric,
+                &optional(change.before),
+                &change.after.to_string(),
+                &optional(change.delta),
+                &change.regression.to_string(),
+            ]);
+        }
+    }
+    String::from_utf8(writer.into_inner().unwrap()).unwrap()
+}
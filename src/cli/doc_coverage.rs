@@ -0,0 +1,139 @@
+use std::collections::BTreeMap;
+
+use syn::{
+    Attribute,
+    Fields,
+    ImplItem,
+    Item,
+    TraitItem,
+    Visibility,
+    visit::Visit,
+};
+
+use crate::plugin::{
+    FileInfo,
+    MetricVisitor,
+};
+
+/// The metric `--doc-coverage` adds
+const METRIC: &str = "undocumented_pub_items";
+
+/// Counts `pub` items without doc comments, registered with `--doc-coverage`
+///
+/// Like rustdoc's `missing_docs` this looks at each item's own visibility, so
+/// a `pub fn` in a private module still counts, as do named `pub` fields and
+/// the variants (and their named fields) of a `pub` enum. `pub(crate)` and
+/// other restricted visibilities aren't public API, and `pub mod foo;`
+/// declarations are skipped since their docs are usually `//!` comments in the
+/// module's own file.
+pub struct DocCoverage;
+
+#[derive(Default)]
+struct UndocumentedCounter {
+    count: isize,
+}
+
+fn has_docs(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| attr.path().is_ident("doc"))
+}
+
+impl UndocumentedCounter {
+    fn check(&mut self, vis: &Visibility, attrs: &[Attribute]) {
+        if matches!(vis, Visibility::Public(_)) && !has_docs(attrs) {
+            self.count += 1;
+        }
+    }
+
+    /// Check the named fields, like `missing_docs` tuple fields are skipped
+    fn check_fields(&mut self, fields: &Fields) {
+        if let Fields::Named(fields) = fields {
+            for field in &fields.named {
+                self.check(&field.vis, &field.attrs);
+            }
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for UndocumentedCounter {
+    fn visit_item(&mut self, i: &'ast Item) {
+        match i {
+            Item::Const(item) => self.check(&item.vis, &item.attrs),
+            Item::Enum(item) => {
+                self.check(&item.vis, &item.attrs);
+                // a pub enum's variants and their fields are as public as it is
+                if matches!(item.vis, Visibility::Public(_)) {
+                    for variant in &item.variants {
+                        self.check(&item.vis, &variant.attrs);
+                        if let Fields::Named(fields) = &variant.fields {
+                            for field in &fields.named {
+                                self.check(&item.vis, &field.attrs);
+                            }
+                        }
+                    }
+                }
+            }
+            // items in a fn body aren't reachable from outside it
+            Item::Fn(item) => {
+                self.check(&item.vis, &item.attrs);
+                return;
+            }
+            Item::Mod(item) if item.content.is_some() => self.check(&item.vis, &item.attrs),
+            Item::Static(item) => self.check(&item.vis, &item.attrs),
+            Item::Struct(item) => {
+                self.check(&item.vis, &item.attrs);
+                self.check_fields(&item.fields);
+            }
+            Item::Trait(item) => {
+                self.check(&item.vis, &item.attrs);
+                // a pub trait's items are as public as it is
+                if matches!(item.vis, Visibility::Public(_)) {
+                    for item in &item.items {
+                        let attrs = match item {
+                            TraitItem::Const(item) => &item.attrs,
+                            TraitItem::Fn(item) => &item.attrs,
+                            TraitItem::Type(item) => &item.attrs,
+                            _ => continue,
+                        };
+                        if !has_docs(attrs) {
+                            self.count += 1;
+                        }
+                    }
+                }
+                return;
+            }
+            Item::Type(item) => self.check(&item.vis, &item.attrs),
+            Item::Union(item) => {
+                self.check(&item.vis, &item.attrs);
+                for field in &item.fields.named {
+                    self.check(&field.vis, &field.attrs);
+                }
+            }
+            // trait impls are documented by their trait
+            Item::Impl(item) if item.trait_.is_none() => {
+                for item in &item.items {
+                    match item {
+                        ImplItem::Const(item) => self.check(&item.vis, &item.attrs),
+                        ImplItem::Fn(item) => self.check(&item.vis, &item.attrs),
+                        ImplItem::Type(item) => self.check(&item.vis, &item.attrs),
+                        _ => {}
+                    }
+                }
+                return;
+            }
+            _ => {}
+        }
+        syn::visit::visit_item(self, i);
+    }
+}
+
+impl MetricVisitor for DocCoverage {
+    fn metrics(&self) -> Vec<String> {
+        vec![METRIC.to_string()]
+    }
+
+    fn count(&self, file: &syn::File, _info: FileInfo<'_>) -> BTreeMap<String, isize> {
+        let mut counter = UndocumentedCounter::default();
+        counter.visit_file(file);
+        BTreeMap::from([(METRIC.to_string(), counter.count)])
+    }
+}
@@ -0,0 +1,331 @@
+use std::collections::{
+    BTreeMap,
+    BTreeSet,
+};
+
+use syn::{
+    Expr,
+    ExprCall,
+    ExprMethodCall,
+    FnArg,
+    ImplItemFn,
+    ItemFn,
+    ItemImpl,
+    ItemStruct,
+    Signature,
+    Type,
+    spanned::Spanned,
+    visit::Visit,
+};
+
+use super::{
+    Confidence,
+    Suggestion,
+};
+
+#[derive(Clone, Default, Debug)]
+pub struct FileStats {
+    pub filename: String,
+    pub stats: CodeStats,
+}
+
+#[derive(Clone, Debug)]
+pub struct DropCandidate {
+    /// the fn freeing the type, or the one freeing a field
+    pub fn_name: String,
+    pub line_number: usize,
+    pub reason: String,
+    pub confidence: Confidence,
+}
+
+impl Suggestion for DropCandidate {
+    fn subject(&self) -> String {
+        self.fn_name.clone()
+    }
+
+    fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    fn confidence(&self) -> Confidence {
+        self.confidence
+    }
+
+    fn detail(&self) -> Option<String> {
+        Some(self.reason.clone())
+    }
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct CodeStats {
+    pub candidates: Vec<DropCandidate>,
+}
+
+/// Whether a fn is named like a C-style destructor, `foo_free` or `foo_destroy`
+fn is_free_fn_name(name: &str) -> bool {
+    name.ends_with("_free") || name.ends_with("_destroy")
+}
+
+/// The type a value of type `ty` is or points to, like `Foo` for `*mut Foo`,
+/// `&mut Foo` or `Box<Foo>`
+fn pointee_name(ty: &Type) -> Option<String> {
+    match ty {
+        Type::Ptr(ptr) => pointee_name(&ptr.elem),
+        Type::Reference(reference) => pointee_name(&reference.elem),
+        Type::Paren(paren) => pointee_name(&paren.elem),
+        Type::Path(path) => {
+            let segment = path.path.segments.last()?;
+            if segment.ident == "Box"
+                && let syn::PathArguments::AngleBracketed(args) = &segment.arguments
+                && let Some(syn::GenericArgument::Type(ty)) = args.args.first()
+            {
+                return pointee_name(ty);
+            }
+            Some(segment.ident.to_string())
+        }
+        _ => None,
+    }
+}
+
+/// A `*_free` or `*_destroy` fn and the type it frees
+struct FreeFn {
+    type_name: String,
+    filename: String,
+    line_number: usize,
+}
+
+/// Collects the crate's free fns, its structs, the types implementing `Drop`
+/// and how often each fn or method name is called
+#[derive(Default)]
+struct Definitions {
+    filename: String,
+    free_fns: BTreeMap<String, FreeFn>,
+    structs: BTreeSet<String>,
+    drop_types: BTreeSet<String>,
+    calls: BTreeMap<String, usize>,
+    /// the self type of the impl being visited
+    impl_type: Option<String>,
+}
+
+impl Definitions {
+    fn check_fn(&mut self, sig: &Signature, self_type: Option<String>) {
+        let name = sig.ident.to_string();
+        if !is_free_fn_name(&name) {
+            return;
+        }
+        // a method frees its own type, a free fn the one its first argument
+        // points to
+        let type_name = match sig.inputs.first() {
+            Some(FnArg::Receiver(_)) => self_type,
+            Some(FnArg::Typed(arg)) => pointee_name(&arg.ty),
+            None => None,
+        };
+        if let Some(type_name) = type_name {
+            self.free_fns.insert(
+                name,
+                FreeFn {
+                    type_name,
+                    filename: self.filename.clone(),
+                    line_number: sig.ident.span().start().line,
+                },
+            );
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for Definitions {
+    fn visit_item_fn(&mut self, i: &'ast ItemFn) {
+        self.check_fn(&i.sig, None);
+        syn::visit::visit_item_fn(self, i);
+    }
+
+    fn visit_item_struct(&mut self, i: &'ast ItemStruct) {
+        self.structs.insert(i.ident.to_string());
+        syn::visit::visit_item_struct(self, i);
+    }
+
+    fn visit_item_impl(&mut self, i: &'ast ItemImpl) {
+        let self_type = pointee_name(&i.self_ty);
+        if let Some((_, path, _)) = &i.trait_
+            && path
+                .segments
+                .last()
+                .is_some_and(|segment| segment.ident == "Drop")
+            && let Some(self_type) = &self_type
+        {
+            self.drop_types.insert(self_type.clone());
+        }
+        let outer = std::mem::replace(&mut self.impl_type, self_type);
+        syn::visit::visit_item_impl(self, i);
+        self.impl_type = outer;
+    }
+
+    fn visit_impl_item_fn(&mut self, i: &'ast ImplItemFn) {
+        self.check_fn(&i.sig, self.impl_type.clone());
+        syn::visit::visit_impl_item_fn(self, i);
+    }
+
+    fn visit_expr_call(&mut self, i: &'ast ExprCall) {
+        if let Expr::Path(path) = &*i.func
+            && let Some(segment) = path.path.segments.last()
+        {
+            *self.calls.entry(segment.ident.to_string()).or_default() += 1;
+        }
+        syn::visit::visit_expr_call(self, i);
+    }
+
+    fn visit_expr_method_call(&mut self, i: &'ast ExprMethodCall) {
+        *self.calls.entry(i.method.to_string()).or_default() += 1;
+        syn::visit::visit_expr_method_call(self, i);
+    }
+}
+
+/// The field a pointer passed to `free` comes from, through casts and
+/// `.cast()`s, like `s.buf` in `libc::free(s.buf as *mut c_void)`
+fn freed_field(expr: &Expr) -> Option<&Expr> {
+    match expr {
+        Expr::Field(_) => Some(expr),
+        Expr::Cast(cast) => freed_field(&cast.expr),
+        Expr::Paren(paren) => freed_field(&paren.expr),
+        Expr::MethodCall(call) if call.method == "cast" || call.method == "cast_mut" => {
+            freed_field(&call.receiver)
+        }
+        _ => None,
+    }
+}
+
+/// Finds `libc::free` calls on a struct's field outside of destructors
+struct FieldFrees<'a> {
+    stats: &'a mut CodeStats,
+    /// the innermost fn, unless it's a free fn or in a `Drop` impl
+    fn_name: Option<String>,
+}
+
+impl<'ast> Visit<'ast> for FieldFrees<'_> {
+    fn visit_item_fn(&mut self, i: &'ast ItemFn) {
+        let name = i.sig.ident.to_string();
+        let outer = std::mem::replace(
+            &mut self.fn_name,
+            Some(name).filter(|name| !is_free_fn_name(name)),
+        );
+        syn::visit::visit_item_fn(self, i);
+        self.fn_name = outer;
+    }
+
+    fn visit_item_impl(&mut self, i: &'ast ItemImpl) {
+        let is_drop = i.trait_.as_ref().is_some_and(|(_, path, _)| {
+            path.segments
+                .last()
+                .is_some_and(|segment| segment.ident == "Drop")
+        });
+        if !is_drop {
+            syn::visit::visit_item_impl(self, i);
+        }
+    }
+
+    fn visit_impl_item_fn(&mut self, i: &'ast ImplItemFn) {
+        let name = i.sig.ident.to_string();
+        let outer = std::mem::replace(
+            &mut self.fn_name,
+            Some(name).filter(|name| !is_free_fn_name(name)),
+        );
+        syn::visit::visit_impl_item_fn(self, i);
+        self.fn_name = outer;
+    }
+
+    fn visit_expr_call(&mut self, i: &'ast ExprCall) {
+        if let Some(fn_name) = &self.fn_name
+            && let Expr::Path(path) = &*i.func
+            && let segments = path
+                .path
+                .segments
+                .iter()
+                .map(|segment| segment.ident.to_string())
+                .collect::<Vec<_>>()
+            && matches!(
+                segments.iter().map(String::as_str).collect::<Vec<_>>()[..],
+                ["free"] | ["libc", "free"]
+            )
+            && let Some(field) = i.args.first().and_then(freed_field)
+        {
+            let field = field
+                .span()
+                .source_text()
+                .unwrap_or_else(|| "a field".to_string());
+            self.stats.candidates.push(DropCandidate {
+                fn_name: fn_name.clone(),
+                line_number: i.func.span().start().line,
+                reason: format!("calls `free` on `{field}`, which the struct's `Drop` could own"),
+                confidence: Confidence::Low,
+            });
+        }
+        syn::visit::visit_expr_call(self, i);
+    }
+}
+
+/// Find types freed by hand which could implement `Drop` instead
+/// The heuristic is a `*_free` or `*_destroy` fn for a type without a `Drop`
+/// impl, called from more than one site, or a `libc::free` of a struct's
+/// field outside of such fns and `Drop` impls
+/// It's high confidence when the freed type is a struct of the crate, which
+/// a `Drop` impl can be added to, and low for the field frees
+pub fn find_candidates(
+    files: &[(String, syn::File)],
+    min_confidence: Confidence,
+) -> Vec<FileStats> {
+    // free fns and their calls can be in different files
+    let mut definitions = Definitions::default();
+    for (filename, syntax) in files {
+        definitions.filename.clone_from(filename);
+        definitions.visit_file(syntax);
+    }
+
+    let mut file_reports: BTreeMap<String, CodeStats> = BTreeMap::new();
+    for (name, free_fn) in &definitions.free_fns {
+        let calls = definitions.calls.get(name).copied().unwrap_or(0);
+        if calls < 2 || definitions.drop_types.contains(&free_fn.type_name) {
+            continue;
+        }
+        let type_name = &free_fn.type_name;
+        // a type from another crate, or a typedef, can't implement `Drop` here
+        let confidence = if definitions.structs.contains(type_name) {
+            Confidence::High
+        } else {
+            Confidence::Medium
+        };
+        file_reports
+            .entry(free_fn.filename.clone())
+            .or_default()
+            .candidates
+            .push(DropCandidate {
+                fn_name: name.clone(),
+                line_number: free_fn.line_number,
+                reason: format!(
+                    "frees `{type_name}` and is called from {calls} sites, it could be `impl Drop for {type_name}`"
+                ),
+                confidence,
+            });
+    }
+    for (filename, syntax) in files {
+        let stats = file_reports.entry(filename.clone()).or_default();
+        let mut visitor = FieldFrees {
+            stats,
+            fn_name: None,
+        };
+        visitor.visit_file(syntax);
+    }
+
+    file_reports
+        .into_iter()
+        .map(|(filename, mut stats)| {
+            stats
+                .candidates
+                .retain(|candidate| candidate.confidence >= min_confidence);
+            stats
+                .candidates
+                .sort_by_key(|candidate| candidate.line_number);
+            FileStats { filename, stats }
+        })
+        .filter(|report| !report.stats.candidates.is_empty())
+        .collect()
+}
@@ -0,0 +1,310 @@
+/// What each metric counts, keyed by its csv column name
+pub const METRICS: [(&str, &str); 30] = [
+    (
+        "allowed_lints",
+        "Lints named in `#[allow(...)]` and `#![allow(...)]` attributes, each lint
+counted once per attribute, including `clippy::` and other tool lints.
+`--allows` breaks them down by lint name. Allows through `cfg_attr` aren't
+counted.
+
+    #[allow(dead_code)]                 // 1
+    #[allow(unused, clippy::too_many_lines)]  // 2
+    #[expect(dead_code)]                // 0",
+    ),
+    (
+        "bool_candidates",
+        "Fns returning `i32` whose returns are all literal `0` or `1`, which could
+return `bool`, as listed by `--bool-candidates`. Only counted with
+`--count-candidates`, at or above `--min-confidence`.
+
+    fn is_ready() -> i32 { if ok { 1 } else { 0 } }  // 1
+    fn status() -> i32 { -1 }           // 0",
+    ),
+    (
+        "clippy_lints",
+        "Clippy lints reported for the file, only when given --clippy-json output.
+The same lint at the same location is counted once, even when clippy reports it
+for several targets (lib, tests, ...).",
+    ),
+    (
+        "context_candidates",
+        "Groups of two or more `static mut` items used by exactly the same fns,
+which could be fields of a context struct passed to them, as listed by
+`--context-candidates`. A group is counted once, in the file of its first
+static. Only counted with `--count-candidates`, at or above
+`--min-confidence`.
+
+    static mut COUNT: usize = 0;
+    static mut TOTAL: u64 = 0;          // 1, when both are used by the same fns",
+    ),
+    (
+        "deprecated_uses",
+        "Uses of the crate's own `#[deprecated]` items: paths naming one, in
+expressions, types or patterns, and method calls with a deprecated method's
+name. Definitions are found across the whole crate and uses are matched by
+name alone, `use` declarations aren't counted.
+
+    #[deprecated] pub fn old_parse() {}
+    old_parse();                        // 1
+    use crate::old_parse;               // 0",
+    ),
+    (
+        "drop_candidates",
+        "Types freed by hand which could implement `Drop`, as listed by
+`--drop-candidates`: a `*_free` or `*_destroy` fn for a type without a `Drop`
+impl called from more than one site, counted in the free fn's file, and
+`libc::free` of a struct's field outside of such fns. Only counted with
+`--count-candidates`, at or above `--min-confidence`.
+
+    unsafe fn buf_free(b: *mut Buf) {}  // 1, when called twice
+    libc::free((*b).data as *mut _);    // 1",
+    ),
+    (
+        "extern_callbacks",
+        "Callbacks that can be called across the FFI boundary: fns and methods with
+a foreign ABI (any `extern` but `extern \"Rust\"`), and closures cast or
+assigned to a foreign fn pointer. Declarations in `extern { }` blocks aren't
+counted.
+
+    extern \"C\" fn on_event(data: *mut c_void) {}  // 1
+    let cb = (|| {}) as extern \"C\" fn();        // 1
+    extern \"C\" { fn register(cb: extern \"C\" fn()); }  // 0",
+    ),
+    (
+        "ffi_string_conversions",
+        "Where strings cross the FFI boundary: calls of `CString::new` and
+`CStr::from_ptr`, `as_ptr` on a C string and every `to_string_lossy` (whatever
+the receiver, like unwraps). A receiver is a C string when it's built by one of
+those calls, a `c\"...\"` literal, or a binding or parameter of the fn typed or
+initialized as one.
+
+    let name = CString::new(\"x\")?;     // 1
+    call(name.as_ptr());                // 2
+    CStr::from_ptr(raw).to_string_lossy()  // 2",
+    ),
+    (
+        "get_candidates",
+        "Indexing in fns returning `Option` or `Result` which could use `get` with
+`?` instead of panicking, as listed by `--get-candidates`. Indexing in closures
+and async blocks isn't counted. Only counted with `--count-candidates`, at or
+above `--min-confidence`.
+
+    fn first(v: &[u8]) -> Option<u8> { Some(v[0]) }  // 1
+    fn first(v: &[u8]) -> u8 { v[0] }   // 0",
+    ),
+    (
+        "iterator_candidates",
+        "Index loops which could walk an iterator instead, as listed by
+`--iterator-candidates`: `for i in 0..n`, or `while i < n` stepping with
+`i += 1`, whose body indexes something with exactly `i`. Only counted with
+`--count-candidates`, at or above `--min-confidence`.
+
+    for i in 0..a.len() { sum += a[i]; }  // 1
+    for i in 0..n { sum += a[i + 1]; }  // 0",
+    ),
+    (
+        "leak_prone_calls",
+        "Calls which skip a value's destructor: `mem::forget` (by path, or imported
+with `use`), `Box::leak`, `ManuallyDrop::new` and `into_raw` on any type,
+called as a method or by path.
+
+    std::mem::forget(guard);            // 1
+    let ptr = Box::into_raw(boxed);     // 1
+    let raw = c_string.into_raw();      // 1
+    let s: &'static str = Box::leak(b); // 1",
+    ),
+    (
+        "manual_allocations",
+        "Memory managed by hand: calls of `alloc`, `alloc_zeroed`, `dealloc` and
+`realloc` from an `alloc` module (by path, or imported with `use` before the
+call), calls of `Layout` constructors and `#[global_allocator]` statics.
+Methods like `GlobalAlloc::alloc` aren't counted.
+
+    let layout = Layout::array::<u8>(n)?;   // 1
+    let ptr = std::alloc::alloc(layout);    // 2
+    dealloc(ptr, layout);                   // 3, with `use std::alloc::dealloc`",
+    ),
+    (
+        "once_candidates",
+        "`static mut` items read and written at most once, which could be a
+`static`, `LazyLock` or `OnceLock`, as listed by `--once-candidates`. Only
+counted with `--count-candidates`, at or above `--min-confidence`.
+
+    static mut CONFIG: Option<Config> = None;  // 1, when only `init` sets it
+    static mut COUNT: usize = 0;        // 0, when incremented in several fns",
+    ),
+    (
+        "process_exits",
+        "Calls which end the process without unwinding: `process::exit` and
+`process::abort`, and libc's `exit`, `_exit` and `abort`, by path or imported
+with `use`. A library shouldn't make any, the default direction fails a PR
+which adds one.
+
+    std::process::exit(1);   // 1
+    process::abort();        // 1
+    unsafe { libc::exit(1) } // 1",
+    ),
+    (
+        "recursive_fns",
+        "Fns and methods which call themselves directly: a fn by its bare name,
+a method as `self.name()` or `Self::name()`. Recursion through other fns
+isn't followed. `--locations exposed-recursive` lists only the unsafe,
+`extern` and `#[no_mangle]` ones, where a stack overflow is hardest to rule
+out.
+
+    fn depth(node: &Node) -> usize {
+        node.children.iter().map(depth).max().unwrap_or(0) + 1  // 0, not a call
+    }
+    fn walk(node: &Node) { for c in &node.children { walk(c) } }  // 1",
+    ),
+    (
+        "ref_candidates",
+        "Raw pointer parameters of unsafe fns which are only dereferenced, and maybe
+checked for null, which could be references, as listed by `--ref-candidates`.
+Only counted with `--count-candidates`, at or above `--min-confidence`.
+
+    unsafe fn get(p: *const Foo) -> u32 { (*p).x }  // 1
+    unsafe fn next(p: *const Foo) -> *const Foo { p.add(1) }  // 0",
+    ),
+    (
+        "repr_types",
+        "Structs, enums and unions whose layout is part of the ABI: those with
+`#[repr(C)]`, `#[repr(transparent)]` or `#[repr(packed)]`, in any combination
+with other reprs. Each type is counted once, `--locations repr-types` lists
+them as packed if they're packed at all.
+
+    #[repr(C)] struct Point { x: i32, y: i32 }   // 1
+    #[repr(C, packed)] struct Header(u8, u32);   // 1, listed as packed
+    #[repr(u8)] enum Tag { A, B }                // 0",
+    ),
+    (
+        "safe_candidates",
+        "Unsafe fns without raw pointer parameters or a `# Safety` doc section, which
+might not need to be unsafe, as listed by `--safe-candidates`. Only counted
+with `--count-candidates`, at or above `--min-confidence`.
+
+    unsafe fn add(a: u32, b: u32) -> u32 { a + b }  // 1
+    unsafe fn read(p: *const u32) -> u32 { *p }  // 0",
+    ),
+    (
+        "static_mut_items",
+        "`static mut` items, at any nesting depth (including inside fns).
+
+    static mut COUNTER: u32 = 0;        // 1
+    static LIMIT: u32 = 10;             // 0, not mutable",
+    ),
+    (
+        "string_candidates",
+        "Fns building C strings with `strcpy`, `strcat`, `snprintf` and other libc
+fns copying into, appending to or formatting a buffer, which could build a
+`String` instead, as listed by `--string-candidates`. A fn is counted once
+however many calls it makes. Only counted with `--count-candidates`, at or
+above `--min-confidence`.
+
+    strcpy(buf, name); strcat(buf, ext);  // 1",
+    ),
+    (
+        "thread_unsafe_calls",
+        "Calls which race with other threads: `env::set_var` and `env::remove_var`,
+unsafe since the 2024 edition, and libc's `setenv`, `unsetenv`, `putenv`,
+`strtok`, `strerror`, `localtime` and `gmtime`, by path or imported with
+`use`. Counted by default, other APIs a crate forbids can be counted with
+`[patterns]`.
+
+    unsafe { env::set_var(\"RUST_LOG\", \"debug\") } // 1
+    unsafe { libc::setenv(key, value, 1) }          // 1",
+    ),
+    (
+        "total_fns",
+        "Free functions (`fn` items), at any nesting depth. Methods in `impl` and
+`trait` blocks aren't counted.
+
+    fn parse() {}                       // 1
+    impl Foo { fn new() -> Self {} }    // 0, a method",
+    ),
+    (
+        "total_lines",
+        "Lines in the file, including blank lines and comments.",
+    ),
+    (
+        "total_statements",
+        "Statements anywhere in the file: `let` bindings, items, expressions
+followed by a semicolon and the tail expressions of blocks.
+
+    fn f() {
+        let x = 1;                      // 1
+        g(x);                           // 2
+        x + 1                           // 3, the tail expression
+    }",
+    ),
+    (
+        "trivial_expects",
+        "`expect` calls whose message is a string literal that's empty or a
+single word, which says no more in a panic than `unwrap` would. Messages
+built at runtime, like `&format!(...)`, aren't counted.
+
+    config.expect(\"\");                  // 1
+    config.expect(\"failed\");            // 1
+    config.expect(\"config was loaded\"); // 0",
+    ),
+    (
+        "undocumented_pub_items",
+        "`pub` items without a doc comment, only with --doc-coverage: fns, types,
+traits and their items, consts, statics, inline modules, the `pub` items of
+inherent impls, named `pub` fields, and the variants (and their named fields)
+of `pub` enums. `pub(crate)` items, tuple fields, trait impls and `pub mod
+foo;` declarations aren't counted.
+
+    /// Parses a header.
+    pub fn parse() {}                   // 0
+    pub struct Header;                  // 1
+    pub struct Span { pub start: u32 }  // 2
+    pub(crate) fn helper() {}           // 0, not public",
+    ),
+    (
+        "unsafe_fns",
+        "Free functions declared `unsafe fn`. Unsafe methods aren't counted, like
+total_fns. Tables show it as a percentage of total_fns.
+
+    unsafe fn read(ptr: *const u8) {}   // 1",
+    ),
+    (
+        "unsafe_statements",
+        "Statements directly inside `unsafe { }` blocks. Statements inside a nested
+block (e.g. the body of an `if`) count as the one statement containing them,
+and an unsafe block nested in another is counted again. The body of an
+`unsafe fn` isn't counted unless it's in an unsafe block.
+
+    unsafe {
+        let value = *ptr;               // 1
+        if value > 0 {                  // 2
+            write(ptr, 0);              // not counted
+        }
+    }",
+    ),
+    (
+        "unwraps",
+        "Calls to a method named `unwrap`, whatever the receiver's type. Path calls
+like `Option::unwrap(x)`, `expect`, `unwrap_or` and friends aren't counted.
+
+    let n = input.parse::<u32>().unwrap();  // 1
+    let m = input.parse::<u32>().expect(\"a number\");  // 0",
+    ),
+    (
+        "vec_candidates",
+        "Buffers from the C allocator used as growable arrays, which could be a
+`Vec`, as listed by `--vec-candidates`: a pointer passed to `realloc`, or
+allocated and then indexed with `add`, `offset` or `from_raw_parts`. Only
+counted with `--count-candidates`, at or above `--min-confidence`.
+
+    let p = libc::malloc(n) as *mut u32;
+    *p.add(i) = 0;                      // 1",
+    ),
+];
+
+pub fn explanation(metric: &str) -> Option<&'static str> {
+    METRICS
+        .iter()
+        .find(|(name, _)| *name == metric)
+        .map(|(_, explanation)| *explanation)
+}
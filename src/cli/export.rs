@@ -0,0 +1,131 @@
+use std::{
+    collections::BTreeMap,
+    env,
+    net::UdpSocket,
+    path::Path,
+};
+
+use super::{
+    CodeStats,
+    Report,
+    config,
+    metadata,
+};
+
+/// The InfluxDB measurement and StatsD prefix unless configured
+const DEFAULT_PREFIX: &str = "crate_report";
+
+/// The tags identifying a set of metrics, and the metrics
+type Point = (BTreeMap<String, String>, CodeStats);
+
+/// The totals then each directory's metrics
+fn points(crate_root: impl AsRef<Path>, report: &Report) -> Vec<Point> {
+    let mut tags = config::export().tags.clone();
+    if let Some(package) = metadata::package(crate_root) {
+        tags.entry("crate".to_string()).or_insert(package.name);
+    }
+
+    let mut points = vec![(tags.clone(), report.total.clone())];
+    for (directory, stats) in report.directories() {
+        let mut tags = tags.clone();
+        tags.insert("directory".to_string(), directory);
+        points.push((tags, stats));
+    }
+    points
+}
+
+fn metrics(stats: &CodeStats) -> Vec<(String, isize)> {
+    CodeStats::csv_headers()
+        .into_iter()
+        .skip(1)
+        .map(|metric| {
+            let value = stats.metric(&metric).unwrap_or_default();
+            (metric, value)
+        })
+        .collect()
+}
+
+/// Escape a line protocol tag key or value, or field key
+fn escape_influx(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(',', "\\,")
+        .replace('=', "\\=")
+        .replace(' ', "\\ ")
+}
+
+/// One line per point, e.g.
+/// `crate_report,crate=foo,directory=src unsafe_fns=3i,unwraps=12i`
+fn influx_lines(prefix: &str, points: &[Point]) -> String {
+    points
+        .iter()
+        .map(|(tags, stats)| {
+            let tags: String = tags
+                .iter()
+                .map(|(key, value)| format!(",{}={}", escape_influx(key), escape_influx(value)))
+                .collect();
+            let fields: Vec<String> = metrics(stats)
+                .into_iter()
+                .map(|(metric, value)| format!("{}={value}i", escape_influx(&metric)))
+                .collect();
+            format!("{}{tags} {}\n", escape_influx(prefix), fields.join(","))
+        })
+        .collect()
+}
+
+/// One gauge per metric and point, e.g.
+/// `crate_report.unsafe_fns:3|g|#crate:foo,directory:src`
+fn statsd_lines(prefix: &str, points: &[Point]) -> Vec<String> {
+    points
+        .iter()
+        .flat_map(|(tags, stats)| {
+            // `|`, `,` and `#` delimit DogStatsD tags
+            let clean = |value: &str| value.replace(['|', ',', '#'], "_");
+            let tags: Vec<String> = tags
+                .iter()
+                .map(|(key, value)| format!("{}:{}", clean(key), clean(value)))
+                .collect();
+            let tags = if tags.is_empty() {
+                String::new()
+            } else {
+                format!("|#{}", tags.join(","))
+            };
+            metrics(stats)
+                .into_iter()
+                .map(move |(metric, value)| format!("{prefix}.{metric}:{value}|g{tags}"))
+        })
+        .collect()
+}
+
+/// Push the totals and per-directory metrics to the InfluxDB and StatsD
+/// endpoints in the config, if any
+pub fn push(crate_root: impl AsRef<Path>, report: &Report) -> Result<(), String> {
+    let export = config::export();
+    if export.influxdb.is_none() && export.statsd.is_none() {
+        return Ok(());
+    }
+    let prefix = export.prefix.as_deref().unwrap_or(DEFAULT_PREFIX);
+    let points = points(crate_root, report);
+
+    if let Some(url) = &export.influxdb {
+        tracing::info!("writing {} points to {url}", points.len());
+        let mut request = ureq::post(url).header("Content-Type", "text/plain; charset=utf-8");
+        if let Ok(token) = env::var("INFLUXDB_TOKEN") {
+            request = request.header("Authorization", &format!("Token {token}"));
+        }
+        request
+            .send(influx_lines(prefix, &points))
+            .map_err(|e| format!("InfluxDB: {e}"))?;
+    }
+
+    if let Some(address) = &export.statsd {
+        tracing::info!("sending {} points to {address}", points.len());
+        let socket = UdpSocket::bind("0.0.0.0:0").map_err(|e| format!("StatsD: {e}"))?;
+        for line in statsd_lines(prefix, &points) {
+            socket
+                .send_to(line.as_bytes(), address.as_str())
+                .map_err(|e| format!("StatsD: {e}"))?;
+        }
+    }
+    Ok(())
+}
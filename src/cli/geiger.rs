@@ -0,0 +1,137 @@
+use std::{
+    fs,
+    path::Path,
+    time::UNIX_EPOCH,
+};
+
+use serde::Deserialize;
+
+use super::{
+    CodeStats,
+    history::HistoryEntry,
+};
+
+/// The unsafe usage cargo-geiger found in a single package
+#[derive(Clone, Debug, Default)]
+pub struct GeigerPackage {
+    pub name: String,
+    pub version: String,
+    /// unsafe functions and methods
+    pub unsafe_fns: isize,
+    pub unsafe_exprs: isize,
+    /// unsafe impls and unsafe traits
+    pub unsafe_items: isize,
+    pub forbids_unsafe: bool,
+}
+
+impl GeigerPackage {
+    /// Whether this is the package `name`, which cargo treats the same with
+    /// `-` or `_`
+    pub fn is(&self, name: &str) -> bool {
+        self.name.replace('-', "_") == name.replace('-', "_")
+    }
+}
+
+/// The parts of `cargo geiger --output-format Json` output which are imported
+#[derive(Deserialize)]
+struct GeigerReport {
+    packages: Vec<PackageEntry>,
+}
+
+#[derive(Deserialize)]
+struct PackageEntry {
+    package: PackageInfo,
+    unsafety: Unsafety,
+}
+
+#[derive(Deserialize)]
+struct PackageInfo {
+    id: PackageId,
+}
+
+#[derive(Deserialize)]
+struct PackageId {
+    name: String,
+    version: String,
+}
+
+#[derive(Deserialize)]
+struct Unsafety {
+    used: CounterBlock,
+    #[serde(default)]
+    forbids_unsafe: bool,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct CounterBlock {
+    functions: Count,
+    exprs: Count,
+    item_impls: Count,
+    item_traits: Count,
+    methods: Count,
+}
+
+/// One of geiger's `{ "safe": n, "unsafe_": n }` counters
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct Count {
+    unsafe_: isize,
+}
+
+/// Load the output of `cargo geiger --output-format Json`
+///
+/// Only the `used` counts are imported, code geiger found but which isn't
+/// compiled for the current target is ignored.
+pub fn load(path: impl AsRef<Path>) -> Result<Vec<GeigerPackage>, String> {
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let report: GeigerReport = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+
+    let mut packages: Vec<GeigerPackage> = report
+        .packages
+        .into_iter()
+        .map(|PackageEntry { package, unsafety }| {
+            let used = unsafety.used;
+            GeigerPackage {
+                name: package.id.name,
+                version: package.id.version,
+                unsafe_fns: used.functions.unsafe_ + used.methods.unsafe_,
+                unsafe_exprs: used.exprs.unsafe_,
+                unsafe_items: used.item_impls.unsafe_ + used.item_traits.unsafe_,
+                forbids_unsafe: unsafety.forbids_unsafe,
+            }
+        })
+        .collect();
+
+    packages.sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+    Ok(packages)
+}
+
+/// A history entry for the package `name` from a cargo-geiger output file,
+/// dated by when the file was last modified
+///
+/// Geiger's unsafe fns and methods become `unsafe_fns` and its unsafe exprs
+/// `unsafe_statements`, the metrics it has no counterpart for are zero.
+pub fn history_entry(path: impl AsRef<Path>, name: &str) -> Result<HistoryEntry, String> {
+    let path = path.as_ref();
+    let package = load(path)?
+        .into_iter()
+        .find(|package| package.is(name))
+        .ok_or_else(|| format!("no package '{name}' in it"))?;
+    let timestamp = fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .map_err(|e| e.to_string())?
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+
+    Ok(HistoryEntry {
+        timestamp,
+        commit: String::new(),
+        totals: CodeStats {
+            unsafe_fns: package.unsafe_fns,
+            unsafe_statements: package.unsafe_exprs,
+            ..CodeStats::default()
+        },
+    })
+}
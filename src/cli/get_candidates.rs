@@ -0,0 +1,254 @@
+use syn::{
+    BinOp,
+    Expr,
+    ExprAssign,
+    ExprAsync,
+    ExprBinary,
+    ExprClosure,
+    ExprField,
+    ExprIndex,
+    ExprMethodCall,
+    ExprReference,
+    ImplItemFn,
+    ItemFn,
+    TraitItemFn,
+    spanned::Spanned,
+    visit::Visit,
+};
+
+use super::{
+    Confidence,
+    Suggestion,
+    question_mark::{
+        FnScope,
+        InFnScope,
+        Returns,
+    },
+};
+
+#[derive(Clone, Default, Debug)]
+pub struct FileStats {
+    pub filename: String,
+    pub stats: CodeStats,
+}
+
+#[derive(Clone, Debug)]
+pub struct GetCandidate {
+    pub fn_name: String,
+    pub line_number: usize,
+    /// a replacement for the indexing with `get` or `get_mut`, when its source
+    /// is known
+    pub suggestion: Option<String>,
+    pub confidence: Confidence,
+}
+
+impl Suggestion for GetCandidate {
+    fn subject(&self) -> String {
+        self.fn_name.clone()
+    }
+
+    fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    fn confidence(&self) -> Confidence {
+        self.confidence
+    }
+
+    fn detail(&self) -> Option<String> {
+        self.suggestion
+            .as_ref()
+            .map(|suggestion| format!("`{suggestion}`"))
+    }
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct CodeStats {
+    pub candidates: Vec<GetCandidate>,
+}
+
+pub struct CodeAnalyzer<'a> {
+    stats: &'a mut CodeStats,
+    scope: Option<FnScope>,
+}
+
+impl InFnScope for CodeAnalyzer<'_> {
+    fn scope(&mut self) -> &mut Option<FnScope> {
+        &mut self.scope
+    }
+}
+
+impl CodeAnalyzer<'_> {
+    /// `auto_deref` is for a method call's receiver, a field access' or an
+    /// index' base, which don't need `get`'s reference dereferenced
+    fn check_index(&mut self, i: &ExprIndex, mutable: bool, auto_deref: bool) {
+        let Some(FnScope {
+            name,
+            returns: Some(returns),
+        }) = &self.scope
+        else {
+            return;
+        };
+        let suggestion = i
+            .expr
+            .span()
+            .source_text()
+            .zip(i.index.span().source_text())
+            .map(|(expr, index)| {
+                let get = if mutable { "get_mut" } else { "get" };
+                let propagate = match returns {
+                    Returns::Option => "?",
+                    Returns::Result => ".ok_or(...)?",
+                };
+                // `v[i]` is a place, `get` a reference to it
+                let deref = if auto_deref { "" } else { "*" };
+                format!("{deref}{expr}.{get}({index}){propagate}")
+            });
+        // a `Result` needs an error made up for the missing element
+        let confidence = match (&suggestion, returns) {
+            (None, _) => Confidence::Low,
+            (Some(_), Returns::Result) => Confidence::Medium,
+            (Some(_), Returns::Option) => Confidence::High,
+        };
+        self.stats.candidates.push(GetCandidate {
+            fn_name: name.clone(),
+            line_number: i.bracket_token.span.open().start().line,
+            suggestion,
+            confidence,
+        });
+    }
+
+    /// Visit an expression which is written to, like the left of an `=`
+    fn visit_place(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Index(index) => {
+                self.check_index(index, true, false);
+                self.visit_base(&index.expr, true);
+                self.visit_expr(&index.index);
+            }
+            Expr::Field(field) => self.visit_base(&field.base, true),
+            _ => self.visit_expr(expr),
+        }
+    }
+
+    /// Visit a method call's receiver, or the base of a field access or index
+    fn visit_base(&mut self, expr: &Expr, mutable: bool) {
+        if let Expr::Index(index) = expr {
+            self.check_index(index, mutable, true);
+            self.visit_base(&index.expr, mutable);
+            self.visit_expr(&index.index);
+        } else {
+            self.visit_expr(expr);
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for CodeAnalyzer<'_> {
+    fn visit_item_fn(&mut self, i: &'ast ItemFn) {
+        self.visit_fn_scope(&i.sig, |this| syn::visit::visit_item_fn(this, i));
+    }
+
+    fn visit_impl_item_fn(&mut self, i: &'ast ImplItemFn) {
+        self.visit_fn_scope(&i.sig, |this| syn::visit::visit_impl_item_fn(this, i));
+    }
+
+    fn visit_trait_item_fn(&mut self, i: &'ast TraitItemFn) {
+        self.visit_fn_scope(&i.sig, |this| syn::visit::visit_trait_item_fn(this, i));
+    }
+
+    fn visit_expr_closure(&mut self, i: &'ast ExprClosure) {
+        self.visit_scope(None, |this| syn::visit::visit_expr_closure(this, i));
+    }
+
+    fn visit_expr_async(&mut self, i: &'ast ExprAsync) {
+        self.visit_scope(None, |this| syn::visit::visit_expr_async(this, i));
+    }
+
+    fn visit_expr_index(&mut self, i: &'ast ExprIndex) {
+        self.check_index(i, false, false);
+        self.visit_base(&i.expr, false);
+        self.visit_expr(&i.index);
+    }
+
+    fn visit_expr_field(&mut self, i: &'ast ExprField) {
+        self.visit_base(&i.base, false);
+    }
+
+    fn visit_expr_method_call(&mut self, i: &'ast ExprMethodCall) {
+        self.visit_base(&i.receiver, false);
+        for arg in &i.args {
+            self.visit_expr(arg);
+        }
+    }
+
+    fn visit_expr_assign(&mut self, i: &'ast ExprAssign) {
+        self.visit_place(&i.left);
+        self.visit_expr(&i.right);
+    }
+
+    fn visit_expr_binary(&mut self, i: &'ast ExprBinary) {
+        let assigns = matches!(
+            i.op,
+            BinOp::AddAssign(_)
+                | BinOp::SubAssign(_)
+                | BinOp::MulAssign(_)
+                | BinOp::DivAssign(_)
+                | BinOp::RemAssign(_)
+                | BinOp::BitXorAssign(_)
+                | BinOp::BitAndAssign(_)
+                | BinOp::BitOrAssign(_)
+                | BinOp::ShlAssign(_)
+                | BinOp::ShrAssign(_)
+        );
+        if assigns {
+            self.visit_place(&i.left);
+            self.visit_expr(&i.right);
+        } else {
+            syn::visit::visit_expr_binary(self, i);
+        }
+    }
+
+    fn visit_expr_reference(&mut self, i: &'ast ExprReference) {
+        if i.mutability.is_some() {
+            self.visit_place(&i.expr);
+        } else {
+            syn::visit::visit_expr_reference(self, i);
+        }
+    }
+}
+
+fn analyze_file(syntax: &syn::File) -> CodeStats {
+    let mut stats = CodeStats::default();
+    let mut visitor = CodeAnalyzer {
+        stats: &mut stats,
+        scope: None,
+    };
+    visitor.visit_file(syntax);
+
+    stats
+}
+
+/// Find indexing which could use `get` with `?` instead of panicking
+/// The heuristic is if the indexing is in a function returning `Option` or
+/// `Result`, outside of closures and async blocks, it's high confidence in
+/// functions returning `Option` and medium in ones returning `Result`, which
+/// need an error for the missing element
+pub fn find_candidates(
+    files: &[(String, syn::File)],
+    min_confidence: Confidence,
+) -> Vec<FileStats> {
+    files
+        .iter()
+        .map(|(filename, syntax)| {
+            let mut stats = analyze_file(syntax);
+            stats
+                .candidates
+                .retain(|candidate| candidate.confidence >= min_confidence);
+            FileStats {
+                filename: filename.clone(),
+                stats,
+            }
+        })
+        .filter(|report| !report.stats.candidates.is_empty())
+        .collect()
+}
@@ -0,0 +1,130 @@
+use std::{
+    path::{
+        Path,
+        PathBuf,
+    },
+    process::Command,
+};
+
+/// Run a git command in `repo`, returning its stdout if it succeeded
+pub fn git<I, S>(repo: impl AsRef<Path>, args: I) -> Option<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<std::ffi::OsStr>,
+{
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo.as_ref())
+        .args(args)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// The non-merge commits in a range like `v1.0..HEAD`, oldest first
+pub fn commits_in_range(repo: impl AsRef<Path>, range: &str) -> Option<Vec<String>> {
+    let output = git(repo, ["rev-list", "--reverse", "--no-merges", range])?;
+    Some(output.lines().map(str::to_string).collect())
+}
+
+/// The first-parent commits in a range like `v1.0..HEAD`, oldest first
+pub fn first_parent_commits(repo: impl AsRef<Path>, range: &str) -> Option<Vec<String>> {
+    let output = git(repo, ["rev-list", "--reverse", "--first-parent", range])?;
+    Some(output.lines().map(str::to_string).collect())
+}
+
+/// The author name of a commit
+pub fn author(repo: impl AsRef<Path>, commit: &str) -> Option<String> {
+    let output = git(repo, ["show", "-s", "--format=%aN", commit])?;
+    Some(output.trim().to_string())
+}
+
+/// A one line description of a commit: abbreviated hash, author and subject
+pub fn subject(repo: impl AsRef<Path>, commit: &str) -> Option<String> {
+    let output = git(repo, ["show", "-s", "--format=%h %aN: %s", commit])?;
+    Some(output.trim().to_string())
+}
+
+/// The `.rs` files a commit changed, relative to `repo`
+pub fn changed_rust_files(repo: impl AsRef<Path>, commit: &str) -> Option<Vec<String>> {
+    let output = git(
+        repo,
+        [
+            "diff-tree",
+            "--root",
+            "--no-commit-id",
+            "--name-only",
+            "--relative",
+            "-r",
+            commit,
+        ],
+    )?;
+    Some(
+        output
+            .lines()
+            .filter(|path| path.ends_with(".rs"))
+            .map(str::to_string)
+            .collect(),
+    )
+}
+
+/// The `.rs` files added, copied, modified or renamed in the index, relative to `repo`
+pub fn staged_rust_files(repo: impl AsRef<Path>) -> Option<Vec<String>> {
+    let output = git(
+        repo,
+        [
+            "diff",
+            "--cached",
+            "--name-only",
+            "--diff-filter=ACMR",
+            "--relative",
+        ],
+    )?;
+    Some(
+        output
+            .lines()
+            .filter(|path| path.ends_with(".rs"))
+            .map(str::to_string)
+            .collect(),
+    )
+}
+
+/// The contents of a file (relative to `repo`) at a revision, `None` if it didn't exist
+pub fn show_file(repo: impl AsRef<Path>, rev: &str, path: &str) -> Option<String> {
+    git(repo, ["show", &format!("{rev}:./{path}")])
+}
+
+/// The path of `dir` within its repository with a trailing `/`, empty at the
+/// root or outside a repository
+pub fn repo_prefix(dir: impl AsRef<Path>) -> String {
+    git(dir, ["rev-parse", "--show-prefix"])
+        .map(|prefix| prefix.trim().to_string())
+        .unwrap_or_default()
+}
+
+/// The root of the repository `dir` is in, `None` outside a repository
+pub fn toplevel(dir: impl AsRef<Path>) -> Option<PathBuf> {
+    git(dir, ["rev-parse", "--show-toplevel"]).map(|root| PathBuf::from(root.trim()))
+}
+
+/// The unix timestamp of a commit's committer date
+pub fn commit_timestamp(repo: impl AsRef<Path>, commit: &str) -> Option<u64> {
+    let output = git(repo, ["show", "-s", "--format=%ct", commit])?;
+    output.trim().parse().ok()
+}
+
+/// The `.rs` files (relative to `repo`) in the tree of a revision, skipping `target` directories
+pub fn rust_files_at(repo: impl AsRef<Path>, rev: &str) -> Option<Vec<String>> {
+    let output = git(repo, ["ls-tree", "-r", "--name-only", rev])?;
+    Some(
+        output
+            .lines()
+            .filter(|path| path.ends_with(".rs"))
+            .filter(|path| !path.split('/').any(|component| component == "target"))
+            .map(str::to_string)
+            .collect(),
+    )
+}
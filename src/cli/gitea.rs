@@ -0,0 +1,79 @@
+use std::env;
+
+use super::PR_COMMENT_FOOTER;
+
+/// The first of `names` which is set, e.g. a Gitea variable then the
+/// GitHub-compatible one Gitea and Forgejo Actions define
+fn variable(names: &[&str]) -> Option<String> {
+    names
+        .iter()
+        .find_map(|name| env::var(name).ok().filter(|value| !value.is_empty()))
+}
+
+fn required(names: &[&str]) -> Result<String, String> {
+    variable(names).ok_or_else(|| format!("set {}", names.join(" or ")))
+}
+
+/// The pull request number, from `GITEA_PULL_REQUEST` or an Actions ref like
+/// `refs/pull/12/head`
+fn pull_request() -> Result<String, String> {
+    if let Some(number) = variable(&["GITEA_PULL_REQUEST"]) {
+        return Ok(number);
+    }
+    variable(&["GITHUB_REF"])
+        .as_deref()
+        .and_then(|git_ref| git_ref.strip_prefix("refs/pull/"))
+        .and_then(|rest| rest.split('/').next())
+        .map(str::to_string)
+        .ok_or_else(|| "set GITEA_PULL_REQUEST, or run for a pull request event".to_string())
+}
+
+/// Post `content` to a Gitea or Forgejo pull request, replacing the comment of
+/// a previous run if there is one
+pub fn post_comment(content: &str) -> Result<(), String> {
+    let base_url = required(&["GITEA_URL", "GITHUB_SERVER_URL"])?;
+    let repository = required(&["GITEA_REPOSITORY", "GITHUB_REPOSITORY"])?;
+    let pull_request = pull_request()?;
+    let authorization = format!("token {}", required(&["GITEA_TOKEN", "GITHUB_TOKEN"])?);
+    let api = format!(
+        "{}/api/v1/repos/{repository}",
+        base_url.trim_end_matches('/')
+    );
+
+    // pull requests share their comments with the issue of the same number
+    let comments = format!("{api}/issues/{pull_request}/comments");
+    let existing: serde_json::Value = ureq::get(&comments)
+        .header("Authorization", &authorization)
+        .call()
+        .map_err(|e| e.to_string())?
+        .body_mut()
+        .read_to_string()
+        .map_err(|e| e.to_string())
+        .and_then(|body| serde_json::from_str(&body).map_err(|e| e.to_string()))?;
+    let previous = existing
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|comment| {
+            comment["body"]
+                .as_str()
+                .is_some_and(|body| body.contains(PR_COMMENT_FOOTER))
+        })
+        .and_then(|comment| comment["id"].as_u64());
+
+    let body = serde_json::json!({ "body": content }).to_string();
+    let request = if let Some(comment) = previous {
+        tracing::info!("updating comment {comment}");
+        ureq::patch(format!("{api}/issues/comments/{comment}"))
+            .header("Authorization", &authorization)
+            .header("Content-Type", "application/json")
+            .send(body)
+    } else {
+        tracing::info!("commenting on pull request {pull_request}");
+        ureq::post(&comments)
+            .header("Authorization", &authorization)
+            .header("Content-Type", "application/json")
+            .send(body)
+    };
+    request.map(|_| ()).map_err(|e| e.to_string())
+}
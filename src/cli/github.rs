@@ -0,0 +1,178 @@
+use std::{
+    env,
+    fs,
+};
+
+use serde::Serialize;
+
+use super::{
+    Args,
+    Report,
+    SiteKind,
+    format_pr_comment_report,
+    git,
+    new_sites,
+    precommit,
+    severity::{
+        self,
+        Severity,
+    },
+};
+
+/// The check's name in the PR's checks list and branch protection settings
+const CHECK_NAME: &str = "crate-report";
+
+/// GitHub takes at most this many annotations per request, more are added by
+/// updating the check run
+const ANNOTATIONS_PER_REQUEST: usize = 50;
+
+#[derive(Serialize)]
+struct Annotation {
+    path: String,
+    start_line: usize,
+    end_line: usize,
+    annotation_level: &'static str,
+    title: &'static str,
+    message: String,
+}
+
+fn variable(name: &str) -> Result<String, String> {
+    env::var(name)
+        .ok()
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| format!("{name} isn't set, is this a GitHub Actions run?"))
+}
+
+/// The commit the check is attached to, the PR head for pull request events
+/// (where `GITHUB_SHA` is a merge commit which isn't shown on the PR)
+fn head_sha() -> Result<String, String> {
+    let event: Option<serde_json::Value> = env::var("GITHUB_EVENT_PATH")
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|event| serde_json::from_str(&event).ok());
+    match event
+        .as_ref()
+        .and_then(|event| event["pull_request"]["head"]["sha"].as_str())
+    {
+        Some(sha) => Ok(sha.to_string()),
+        None => variable("GITHUB_SHA"),
+    }
+}
+
+/// An annotation per unsafe site, unwrap and FFI boundary new compared to the
+/// first baseline, see [`new_sites`]
+fn annotations(report: &Report, baselines: &[(String, Report)], args: &Args) -> Vec<Annotation> {
+    let Some((_, baseline)) = baselines.first() else {
+        return Vec::new();
+    };
+    // annotation paths are relative to the repository root
+    let prefix = git::repo_prefix(&args.crate_root);
+    new_sites(report, &report.diff(baseline), args)
+        .into_iter()
+        .map(|(filename, site)| {
+            let (annotation_level, title) = match site.kind {
+                SiteKind::Unwrap => ("notice", "New unwrap"),
+                SiteKind::FfiString => ("notice", "New FFI string conversion"),
+                SiteKind::ExternClosure | SiteKind::ExternFn => ("notice", "New extern callback"),
+                SiteKind::Repr(_) => ("notice", "New repr type"),
+                SiteKind::Allocation | SiteKind::GlobalAllocator => {
+                    ("notice", "New manual allocation")
+                }
+                SiteKind::LeakProne => ("warning", "New leak-prone call"),
+                SiteKind::ProcessExit => ("warning", "New process exit"),
+                SiteKind::DeprecatedUse => ("notice", "New use of a deprecated item"),
+                SiteKind::Recursive { exposed: false } => ("notice", "New recursive fn"),
+                SiteKind::Recursive { exposed: true } => {
+                    ("warning", "New recursive unsafe or exported fn")
+                }
+                SiteKind::ThreadUnsafe => ("warning", "New thread-unsafe call"),
+                SiteKind::TrivialExpect => ("notice", "New expect with a trivial message"),
+                _ => ("warning", "New unsafe code"),
+            };
+            Annotation {
+                path: format!("{prefix}{filename}"),
+                start_line: site.line,
+                end_line: site.line,
+                annotation_level,
+                title,
+                message: precommit::describe(&site),
+            }
+        })
+        .collect()
+}
+
+/// Create a completed check run on the commit being built, failing on a
+/// regression or a file at a danger threshold like `--quiet` does, with the PR
+/// comment as its summary
+pub fn create_check_run(
+    report: &Report,
+    baselines: &[(String, Report)],
+    args: &Args,
+) -> Result<(), String> {
+    let api_url = env::var("GITHUB_API_URL").unwrap_or_else(|_| "https://api.github.com".into());
+    let repository = variable("GITHUB_REPOSITORY")?;
+    let token = variable("GITHUB_TOKEN")?;
+    let head_sha = head_sha()?;
+
+    // warnings don't block a merge, but show on the PR
+    let (conclusion, title) = match severity::assess(&severity::findings(report, baselines)) {
+        Severity::Fail => ("failure", "Regressed, or a file is over a danger threshold"),
+        Severity::Warn => ("neutral", "Regressed within the warning tier"),
+        Severity::Info => ("success", "No regressions"),
+    };
+    let summary = format_pr_comment_report(report, baselines, args);
+    let annotations = annotations(report, baselines, args);
+    let mut batches = annotations.chunks(ANNOTATIONS_PER_REQUEST);
+    let output = |annotations: &[Annotation]| {
+        serde_json::json!({
+            "title": title,
+            "summary": summary,
+            "annotations": annotations,
+        })
+    };
+
+    let check_runs = format!(
+        "{}/repos/{repository}/check-runs",
+        api_url.trim_end_matches('/')
+    );
+    let send = |request: ureq::RequestBuilder<ureq::typestate::WithBody>,
+                body: serde_json::Value| {
+        request
+            .header("Authorization", &format!("Bearer {token}"))
+            .header("Accept", "application/vnd.github+json")
+            .header("Content-Type", "application/json")
+            .send(body.to_string())
+            .map_err(|e| e.to_string())?
+            .body_mut()
+            .read_to_string()
+            .map_err(|e| e.to_string())
+    };
+
+    tracing::info!(
+        "creating check run on {head_sha} with {} annotations",
+        annotations.len()
+    );
+    let created: serde_json::Value = serde_json::from_str(&send(
+        ureq::post(&check_runs),
+        serde_json::json!({
+            "name": CHECK_NAME,
+            "head_sha": head_sha,
+            "status": "completed",
+            "conclusion": conclusion,
+            "output": output(batches.next().unwrap_or_default()),
+        }),
+    )?)
+    .map_err(|e| e.to_string())?;
+    let id = created["id"]
+        .as_u64()
+        .ok_or("the created check run has no id")?;
+
+    // annotations added by later updates are appended to the earlier ones
+    for batch in batches {
+        send(
+            ureq::patch(format!("{check_runs}/{id}")),
+            serde_json::json!({ "output": output(batch) }),
+        )?;
+    }
+    Ok(())
+}
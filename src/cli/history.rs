@@ -0,0 +1,235 @@
+use std::{
+    io,
+    path::Path,
+    time::{
+        SystemTime,
+        UNIX_EPOCH,
+    },
+};
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use super::{
+    CodeStats,
+    Versioned,
+    analyze_source_with,
+    deprecated,
+    git,
+};
+
+/// The crate totals at one point in time
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// seconds since the unix epoch
+    pub timestamp: u64,
+    /// the commit the totals were computed for, empty if unknown
+    #[serde(default)]
+    pub commit: String,
+    #[serde(flatten)]
+    pub totals: CodeStats,
+}
+
+/// The JSON history format, an array of entries before schema version 4
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum JsonHistory {
+    Versioned(Versioned<Entries>),
+    Unversioned(Vec<HistoryEntry>),
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entries {
+    entries: Vec<HistoryEntry>,
+}
+
+/// History files ending in `.json` are JSON, anything else CSV
+fn is_json(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension == "json")
+}
+
+impl HistoryEntry {
+    pub fn now(totals: CodeStats) -> Self {
+        Self {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            commit: String::new(),
+            totals,
+        }
+    }
+}
+
+fn csv_headers() -> Vec<String> {
+    ["timestamp".to_string(), "commit".to_string()]
+        .into_iter()
+        .chain(CodeStats::csv_headers().into_iter().skip(1))
+        .collect()
+}
+
+fn to_csv_row(entry: &HistoryEntry) -> Vec<String> {
+    [entry.timestamp.to_string(), entry.commit.clone()]
+        .into_iter()
+        .chain(entry.totals.to_csv_row(String::new()).into_iter().skip(1))
+        .collect()
+}
+
+/// Load a history file, oldest entries first
+///
+/// Like baselines, columns are matched by name so history written before a
+/// metric was added can still be read, with the missing metric as zero.
+pub fn load(path: impl AsRef<Path>) -> Result<Vec<HistoryEntry>, String> {
+    let path = path.as_ref();
+    if is_json(path) {
+        let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let mut entries = match serde_json::from_str(&content).map_err(|e| e.to_string())? {
+            JsonHistory::Versioned(history) => {
+                super::check_schema_version(history.schema_version);
+                history.content.entries
+            }
+            JsonHistory::Unversioned(entries) => entries,
+        };
+        entries.sort_by_key(|entry| entry.timestamp);
+        return Ok(entries);
+    }
+
+    let mut reader = csv::Reader::from_path(path).map_err(|e| e.to_string())?;
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(str::to_string)
+        .collect();
+
+    let mut entries = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| e.to_string())?;
+        let mut entry = HistoryEntry::default();
+        for (header, value) in headers.iter().zip(&record) {
+            match header.as_str() {
+                "timestamp" => entry.timestamp = value.parse().map_err(|_| "invalid timestamp")?,
+                "commit" => entry.commit = value.to_string(),
+                metric => {
+                    *entry
+                        .totals
+                        .metric_mut(metric)
+                        .ok_or_else(|| format!("unknown column '{metric}'"))? =
+                        value.parse().map_err(|_| format!("invalid {metric}"))?
+                }
+            }
+        }
+        entries.push(entry);
+    }
+
+    entries.sort_by_key(|entry| entry.timestamp);
+    Ok(entries)
+}
+
+/// Write a complete history file
+pub fn save(path: impl AsRef<Path>, entries: &[HistoryEntry]) -> Result<(), String> {
+    let path = path.as_ref();
+    if is_json(path) {
+        return std::fs::write(path, to_json(entries) + "\n").map_err(|e| e.to_string());
+    }
+    let file = std::fs::File::create(path).map_err(|e| e.to_string())?;
+    write_csv(file, entries)
+}
+
+/// Write history entries as CSV, in the history file format
+pub fn write_csv(out: impl io::Write, entries: &[HistoryEntry]) -> Result<(), String> {
+    let mut writer = csv::Writer::from_writer(out);
+    writer
+        .write_record(csv_headers())
+        .map_err(|e| e.to_string())?;
+    for entry in entries {
+        writer
+            .write_record(to_csv_row(entry))
+            .map_err(|e| e.to_string())?;
+    }
+    writer.flush().map_err(|e| e.to_string())
+}
+
+/// Format history entries as JSON, in the history file format
+pub fn to_json(entries: &[HistoryEntry]) -> String {
+    let history = JsonHistory::Versioned(Versioned::new(Entries {
+        entries: entries.to_vec(),
+    }));
+    serde_json::to_string_pretty(&history).unwrap()
+}
+
+/// The crate totals at a revision, files which don't parse count as empty
+pub fn totals_at(crate_root: &Path, rev: &str) -> CodeStats {
+    let contents: Vec<String> = git::rust_files_at(crate_root, rev)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|path| git::show_file(crate_root, rev, path))
+        .collect();
+    let deprecated = deprecated::definitions(contents.iter().map(String::as_str));
+    contents
+        .iter()
+        .filter_map(|content| analyze_source_with(content, &deprecated))
+        .map(|(stats, _)| stats)
+        .sum()
+}
+
+/// Analyze every `step`th commit of a git range, ending at its last commit
+///
+/// Files are read from each commit's tree with `git show`, so the working tree
+/// is never checked out. Files which don't parse at a commit count as empty.
+pub fn backfill(crate_root: impl AsRef<Path>, range: &str, step: usize) -> Vec<HistoryEntry> {
+    let crate_root = crate_root.as_ref();
+    let Some(commits) = git::commits_in_range(crate_root, range) else {
+        eprintln!("Error: failed to list commits in '{range}'");
+        std::process::exit(super::EXIT_ERROR);
+    };
+
+    let mut entries: Vec<HistoryEntry> = commits
+        .iter()
+        .rev()
+        .step_by(step.max(1))
+        .map(|commit| HistoryEntry {
+            timestamp: git::commit_timestamp(crate_root, commit).unwrap_or_default(),
+            commit: commit.clone(),
+            totals: totals_at(crate_root, commit),
+        })
+        .collect();
+    entries.reverse();
+    entries
+}
+
+/// Append an entry to a history file, creating it if it doesn't exist yet
+pub fn append(path: impl AsRef<Path>, entry: HistoryEntry) -> Result<(), String> {
+    let path = path.as_ref();
+    let mut entries = if path.exists() {
+        load(path)?
+    } else {
+        Vec::new()
+    };
+    entries.push(entry);
+    save(path, &entries)
+}
+
+/// Format seconds since the unix epoch as a `YYYY-MM-DD` date (UTC)
+pub fn format_date(timestamp: u64) -> String {
+    // https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let days = (timestamp / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+
+    format!("{year:04}-{month:02}-{day:02}")
+}
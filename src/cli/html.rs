@@ -0,0 +1,1211 @@
+use super::{
+    Args,
+    CodeStats,
+    Diff,
+    DiffReport,
+    Report,
+    Site,
+    config::{
+        self,
+        Level,
+        Threshold,
+    },
+    format_change_delta,
+    format_density_delta,
+    links,
+    plugin,
+};
+
+pub fn format_html_report(report: &Report, baselines: &[(String, Report)], args: &Args) -> String {
+    let mut html = String::new();
+
+    // HTML document structure with embedded CSS
+    html.push_str(r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>Crate Safety Report</title>
+    <style>
+        * { box-sizing: border-box; margin: 0; padding: 0; }
+        body { font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif; line-height: 1.6; color: #333; background: #f8f9fa; }
+        .container { max-width: 1200px; margin: 0 auto; padding: 20px; }
+        .header { background: white; border-radius: 8px; padding: 30px; margin-bottom: 30px; box-shadow: 0 2px 4px rgba(0,0,0,0.1); }
+        .header h1 { color: #2c3e50; margin-bottom: 10px; }
+        .header .subtitle { color: #7f8c8d; }
+        .summary { display: grid; grid-template-columns: repeat(auto-fit, minmax(200px, 1fr)); gap: 20px; margin-bottom: 30px; }
+        .metric { background: white; border-radius: 8px; padding: 20px; box-shadow: 0 2px 4px rgba(0,0,0,0.1); text-align: center; }
+        .metric-value { font-size: 2em; font-weight: bold; margin-bottom: 5px; }
+        .metric-label { color: #7f8c8d; font-size: 0.9em; }
+        .safe { color: #27ae60; }
+        .warning { color: #f39c12; }
+        .danger { color: #e74c3c; }
+        .neutral { color: #7f8c8d; }
+        table { width: 100%; background: white; border-radius: 8px; overflow: hidden; box-shadow: 0 2px 4px rgba(0,0,0,0.1); border-collapse: collapse; }
+        th, td { padding: 12px 15px; text-align: left; border-bottom: 1px solid #ecf0f1; }
+        th { background: #34495e; color: white; font-weight: 600; position: sticky; top: 0; cursor: pointer; user-select: none; }
+        th:hover { background: #2c3e50; }
+        tr:hover { background: #f8f9fa; }
+        .perfect-file { color: #27ae60 !important; }
+        .diff-section { background: white; border-radius: 8px; padding: 20px; margin-top: 30px; box-shadow: 0 2px 4px rgba(0,0,0,0.1); }
+        .diff-summary { margin-bottom: 20px; }
+        .diff-change { margin: 10px 0; padding: 10px; border-radius: 4px; background: #f8f9fa; }
+        .detail-section { background: white; border-radius: 8px; padding: 20px; margin-top: 30px; box-shadow: 0 2px 4px rgba(0,0,0,0.1); }
+        .detail-section h3 { margin-top: 15px; font-family: monospace; }
+        .detail-section ul { margin-left: 20px; }
+        .sortable { position: relative; }
+        .sortable:after { content: ' ↕'; opacity: 0.5; }
+        .sort-asc:after { content: ' ↑'; opacity: 1; }
+        .sort-desc:after { content: ' ↓'; opacity: 1; }
+    </style>
+</head>
+<body>
+    <div class="container">
+        <div class="header">
+            <h1>🦀 Crate Safety Report</h1>
+            <div class="subtitle">Analysis of unsafe code usage in Rust crate</div>
+"#);
+    html.push_str(&format!(
+        r#"            <div class="subtitle">{}</div>
+        </div>
+"#,
+        escape(super::metadata::Metadata::collect(
+            &args.crate_root,
+            args.deterministic
+        ))
+    ));
+
+    // Summary metrics
+    let CodeStats {
+        total_lines,
+        total_fns,
+        unsafe_fns,
+        unsafe_statements,
+        static_mut_items,
+        unwraps,
+        ..
+    } = &report.total;
+
+    let unsafe_fn_percentage = if *total_fns > 0 {
+        (*unsafe_fns as f64 / *total_fns as f64) * 100.0
+    } else {
+        0.0
+    };
+
+    html.push_str(&format!(
+        r#"
+        <div class="summary">
+            <div class="metric">
+                <div class="metric-value neutral">{}</div>
+                <div class="metric-label">Total Lines</div>
+            </div>
+            <div class="metric">
+                <div class="metric-value {}">{:.1}%</div>
+                <div class="metric-label">Unsafe Functions</div>
+            </div>
+            <div class="metric">
+                <div class="metric-value {}">{}</div>
+                <div class="metric-label">Unsafe Statements</div>
+            </div>
+            <div class="metric">
+                <div class="metric-value {}">{}</div>
+                <div class="metric-label">Static Mut Items</div>
+            </div>
+            <div class="metric">
+                <div class="metric-value {}">{}</div>
+                <div class="metric-label">Unwrap Calls</div>
+            </div>
+"#,
+        total_lines,
+        get_safety_class(*unsafe_fns, *total_fns),
+        unsafe_fn_percentage,
+        get_count_class(*unsafe_statements, config::threshold("unsafe_statements")),
+        unsafe_statements,
+        get_count_class(*static_mut_items, config::threshold("static_mut_items")),
+        static_mut_items,
+        get_count_class(*unwraps, config::threshold("unwraps")),
+        unwraps
+    ));
+    let plugin_metrics = plugin::metrics();
+    for metric in &plugin_metrics {
+        let value = report.total.metric(metric).unwrap_or_default();
+        html.push_str(&format!(
+            r#"            <div class="metric">
+                <div class="metric-value {}">{value}</div>
+                <div class="metric-label">{}</div>
+            </div>
+"#,
+            get_count_class(value, config::threshold(metric)),
+            escape(metric),
+        ));
+    }
+    html.push_str(
+        r#"        </div>
+"#,
+    );
+
+    // File details table
+    html.push_str(
+        r#"
+        <table id="fileTable">
+            <thead>
+                <tr>
+                    <th class="sortable" onclick="sortTable(0)">File</th>
+                    <th class="sortable" onclick="sortTable(1)">Unsafe/Total Functions</th>
+                    <th class="sortable" onclick="sortTable(2)">Unsafe Statements</th>
+                    <th class="sortable" onclick="sortTable(3)">Static Mut</th>
+                    <th class="sortable" onclick="sortTable(4)">Unwraps</th>
+"#,
+    );
+    if !report.coverage.is_empty() {
+        html.push_str(
+            r#"                    <th class="sortable" onclick="sortTable(5)">Coverage</th>
+"#,
+        );
+    }
+    let first_plugin_column = if report.coverage.is_empty() { 5 } else { 6 };
+    for (i, metric) in plugin_metrics.iter().enumerate() {
+        html.push_str(&format!(
+            r#"                    <th class="sortable" onclick="sortTable({})">{}</th>
+"#,
+            first_plugin_column + i,
+            escape(metric)
+        ));
+    }
+    html.push_str(
+        r#"                </tr>
+            </thead>
+            <tbody>
+"#,
+    );
+
+    for (filename, stats) in &report.files {
+        let file_class = if stats.is_perfect() {
+            "perfect-file"
+        } else {
+            ""
+        };
+        html.push_str(&format!(
+            r#"
+                <tr>
+                    <td class="{}">{}</td>
+                    <td class="{}">{}/{}</td>
+                    <td class="{}">{}</td>
+                    <td class="{}">{}</td>
+                    <td class="{}">{}</td>
+"#,
+            file_class,
+            html_link(filename, links::file_url(filename)),
+            get_safety_class(stats.unsafe_fns, stats.total_fns),
+            stats.unsafe_fns,
+            stats.total_fns,
+            get_count_class(
+                stats.unsafe_statements,
+                config::threshold("unsafe_statements")
+            ),
+            stats.unsafe_statements,
+            get_count_class(
+                stats.static_mut_items,
+                config::threshold("static_mut_items")
+            ),
+            stats.static_mut_items,
+            get_count_class(stats.unwraps, config::threshold("unwraps")),
+            stats.unwraps
+        ));
+        if !report.coverage.is_empty() {
+            html.push_str(&format!(
+                r#"                    <td class="neutral">{}</td>
+"#,
+                super::format_coverage(report.file_coverage(filename))
+            ));
+        }
+        for metric in &plugin_metrics {
+            let value = stats.metric(metric).unwrap_or_default();
+            html.push_str(&format!(
+                r#"                    <td class="{}">{value}</td>
+"#,
+                get_count_class(value, config::threshold(metric))
+            ));
+        }
+        html.push_str(
+            r#"                </tr>
+"#,
+        );
+    }
+
+    html.push_str(
+        r#"
+            </tbody>
+        </table>
+"#,
+    );
+
+    // Add baseline comparison if provided
+    if let Some((_, old_report)) = baselines.first() {
+        let diff = super::display_diff(report, old_report, args);
+        html.push_str(&format_html_diff(&diff));
+    }
+
+    if args.list_unsafe_fns {
+        html.push_str(&format_html_unsafe_fn_list(report));
+    }
+
+    if !report.coverage.is_empty() {
+        html.push_str(&format_html_least_covered(report));
+    }
+
+    if let Some(geiger_file) = &args.geiger {
+        let packages = super::load_geiger(geiger_file);
+        html.push_str(&format_html_geiger(report, &packages, &args.crate_root));
+    }
+
+    if args.migration {
+        html.push_str(&format_html_migration(report, args));
+    }
+
+    if let Some(owners) = super::load_codeowners(args) {
+        html.push_str(&format_html_owners(&owners.group(&report.files)));
+    }
+
+    if let Some(range) = &args.leaderboard {
+        let contributors = super::leaderboard::rank_contributors(&args.crate_root, range);
+        html.push_str(&format_html_leaderboard(&contributors));
+    }
+
+    if args.blame {
+        let authors = super::blame::attribute_sites(&args.crate_root, report);
+        html.push_str(&format_html_blame(&authors));
+    }
+
+    if args.reachability {
+        let entry_points = super::reachability::find_unsafe_entry_points(&args.crate_root);
+        html.push_str(&format_html_entry_points(&entry_points));
+    }
+
+    if args.untested_unsafe {
+        let untested = super::untested::find_untested_unsafe_fns(&args.crate_root, report);
+        html.push_str(&format_html_untested_unsafe_fns(&untested));
+    }
+
+    if args.unwrap_contexts {
+        let census = super::unwrap_contexts::census(&args.crate_root, report);
+        html.push_str(&format_html_unwrap_contexts(&census));
+    }
+
+    if args.audit_next {
+        let ranked = super::risk::rank(&args.crate_root, report);
+        html.push_str(&format_html_audit_next(&ranked));
+    }
+
+    if args.libc_calls {
+        let census = super::libc_calls::census(&args.crate_root, report);
+        html.push_str(&format_html_libc_calls(&census));
+    }
+
+    if args.allows {
+        let census = super::allows::census(&args.crate_root, report);
+        html.push_str(&format_html_allows(&census));
+    }
+
+    if args.concurrency {
+        let census = super::concurrency::census(&args.crate_root, report);
+        html.push_str(&format_html_concurrency(&census));
+    }
+
+    if args.panic_sites {
+        let census = super::panic_sites::census(&args.crate_root, report);
+        html.push_str(&format_html_panic_sites(&census));
+    }
+
+    for kind in args
+        .locations
+        .iter()
+        .copied()
+        .collect::<std::collections::BTreeSet<_>>()
+    {
+        html.push_str(&format_html_locations(report, kind));
+    }
+
+    // JavaScript for table sorting
+    html.push_str(
+        r#"
+    </div>
+    <script>
+        let sortDirections = {};
+
+        function sortTable(column) {
+            const table = document.getElementById('fileTable');
+            const tbody = table.getElementsByTagName('tbody')[0];
+            const rows = Array.from(tbody.getElementsByTagName('tr'));
+
+            const direction = sortDirections[column] === 'asc' ? 'desc' : 'asc';
+            sortDirections[column] = direction;
+
+            // Clear all sort indicators
+            document.querySelectorAll('th').forEach(th => {
+                th.className = th.className.replace(/sort-(asc|desc)/, '');
+                if (!th.className.includes('sortable')) th.className += ' sortable';
+            });
+
+            // Add sort indicator to current column
+            const th = table.getElementsByTagName('th')[column];
+            th.className = th.className.replace('sortable', `sortable sort-${direction}`);
+
+            rows.sort((a, b) => {
+                let aVal = a.cells[column].textContent.trim();
+                let bVal = b.cells[column].textContent.trim();
+
+                // Handle numeric columns
+                if (column > 0) {
+                    if (column === 1) {
+                        // Unsafe/Total format
+                        aVal = parseInt(aVal.split('/')[0]) || 0;
+                        bVal = parseInt(bVal.split('/')[0]) || 0;
+                    } else {
+                        aVal = parseInt(aVal) || 0;
+                        bVal = parseInt(bVal) || 0;
+                    }
+                }
+
+                if (direction === 'asc') {
+                    return aVal > bVal ? 1 : -1;
+                } else {
+                    return aVal < bVal ? 1 : -1;
+                }
+            });
+
+            rows.forEach(row => tbody.appendChild(row));
+        }
+    </script>
+</body>
+</html>
+"#,
+    );
+
+    html
+}
+
+fn get_level_class(level: Level) -> &'static str {
+    match level {
+        Level::Good => "safe",
+        Level::Warning => "warning",
+        Level::Danger => "danger",
+    }
+}
+
+fn get_safety_class(unsafe_count: isize, total_count: isize) -> &'static str {
+    if total_count == 0 {
+        "neutral"
+    } else {
+        get_level_class(
+            config::threshold("unsafe_fns").level(super::percentage(unsafe_count, total_count)),
+        )
+    }
+}
+
+/// Escape text for use in HTML content or a quoted attribute, file paths and
+/// item names can contain anything
+fn escape(text: impl std::fmt::Display) -> String {
+    let text = text.to_string();
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// `text` linking to `url`, or as is without one
+fn html_link(text: impl std::fmt::Display, url: Option<String>) -> String {
+    match url {
+        Some(url) => format!(r#"<a href="{}">{}</a>"#, escape(url), escape(text)),
+        None => escape(text),
+    }
+}
+
+fn get_count_class(count: isize, threshold: Threshold) -> &'static str {
+    get_level_class(threshold.level(count as f64))
+}
+
+fn format_html_diff(diff: &DiffReport) -> String {
+    if diff.changes.is_empty() && diff.unchanged.is_empty() && diff.relocated.is_empty() {
+        return String::new();
+    }
+
+    let mut html = String::new();
+    html.push_str(
+        r#"
+        <div class="diff-section">
+            <h2>📊 Changes from Baseline</h2>
+            <div class="diff-summary">
+"#,
+    );
+
+    html.push_str(&format!(
+        r#"
+                <div class="diff-change">
+                    <strong>Summary Changes:</strong><br>
+                    Unsafe functions: {} → {} ({})<br>
+                    Unsafe statements: {} → {} ({})<br>
+                    Static mut items: {} → {} ({})<br>
+                    Unwrap calls: {} → {} ({})
+                </div>
+"#,
+        diff.before_total.unsafe_fns,
+        diff.after_total.unsafe_fns,
+        format_change_delta(diff.before_total.unsafe_fns, diff.after_total.unsafe_fns),
+        diff.before_total.unsafe_statements,
+        diff.after_total.unsafe_statements,
+        format_change_delta(
+            diff.before_total.unsafe_statements,
+            diff.after_total.unsafe_statements
+        ),
+        diff.before_total.static_mut_items,
+        diff.after_total.static_mut_items,
+        format_change_delta(
+            diff.before_total.static_mut_items,
+            diff.after_total.static_mut_items
+        ),
+        diff.before_total.unwraps,
+        diff.after_total.unwraps,
+        format_change_delta(diff.before_total.unwraps, diff.after_total.unwraps)
+    ));
+    if diff.has_clippy_lints() {
+        html.push_str(&format!(
+            r#"
+                <div class="diff-change">
+                    Clippy lints: {} → {} ({})
+                </div>
+"#,
+            diff.before_total.clippy_lints,
+            diff.after_total.clippy_lints,
+            format_change_delta(
+                diff.before_total.clippy_lints,
+                diff.after_total.clippy_lints
+            )
+        ));
+    }
+    if let Some([(_, fn_percent), (_, per_kloc)]) = diff.densities() {
+        html.push_str(&format!(
+            r#"
+                <div class="diff-change">
+                    Unsafe function %: {:.2}% → {:.2}% ({})<br>
+                    Unsafe statements per KLOC: {:.2} → {:.2} ({})
+                </div>
+"#,
+            fn_percent.before,
+            fn_percent.after,
+            format_density_delta(fn_percent).unwrap_or("no change".into()),
+            per_kloc.before,
+            per_kloc.after,
+            format_density_delta(per_kloc).unwrap_or("no change".into())
+        ));
+    }
+
+    for (filename, change) in &diff.changes {
+        match change {
+            Diff::Added(stats) => {
+                html.push_str(&format!(
+                    r#"
+                    <div class="diff-change" style="border-left: 4px solid #27ae60;">
+                        <strong>📄 {} [NEW FILE]</strong><br>
+                        Unsafe functions: {}, Unsafe statements: {}, Unwraps: {}
+                    </div>
+"#,
+                    escape(filename),
+                    stats.unsafe_fns,
+                    stats.unsafe_statements,
+                    stats.unwraps
+                ));
+            }
+            Diff::Removed(stats) => {
+                html.push_str(&format!(
+                    r#"
+                    <div class="diff-change" style="border-left: 4px solid #e74c3c;">
+                        <strong>🗑️ {} [REMOVED]</strong><br>
+                        Had {} unsafe functions, {} unsafe statements, {} unwraps
+                    </div>
+"#,
+                    escape(filename),
+                    stats.unsafe_fns,
+                    stats.unsafe_statements,
+                    stats.unwraps
+                ));
+            }
+            Diff::Changed(change) => {
+                html.push_str(&format!(
+                    r#"
+                    <div class="diff-change" style="border-left: 4px solid #f39c12;">
+                        <strong>📝 {} [MODIFIED]</strong><br>
+                        Unsafe functions: {} → {} ({})<br>
+                        Unsafe statements: {} → {} ({})<br>
+                        Unwraps: {} → {} ({})
+                    </div>
+"#,
+                    escape(filename),
+                    change.before.unsafe_fns,
+                    change.after.unsafe_fns,
+                    format_change_delta(change.before.unsafe_fns, change.after.unsafe_fns),
+                    change.before.unsafe_statements,
+                    change.after.unsafe_statements,
+                    format_change_delta(
+                        change.before.unsafe_statements,
+                        change.after.unsafe_statements
+                    ),
+                    change.before.unwraps,
+                    change.after.unwraps,
+                    format_change_delta(change.before.unwraps, change.after.unwraps)
+                ));
+            }
+        }
+    }
+
+    if let Some(note) = diff.relocated_note() {
+        html.push_str(&format!(
+            r#"
+                    <div class="diff-change" style="border-left: 4px solid #95a5a6;">
+                        {}
+                    </div>
+"#,
+            escape(note)
+        ));
+    }
+
+    for (filename, stats) in &diff.unchanged {
+        html.push_str(&format!(
+            r#"
+                    <div class="diff-change" style="border-left: 4px solid #95a5a6;">
+                        <strong>{} [UNCHANGED]</strong><br>
+                        Unsafe functions: {}, Unsafe statements: {}, Unwraps: {}
+                    </div>
+"#,
+            escape(filename),
+            stats.unsafe_fns,
+            stats.unsafe_statements,
+            stats.unwraps
+        ));
+    }
+
+    html.push_str(
+        r#"
+            </div>
+        </div>
+"#,
+    );
+
+    html
+}
+
+fn format_html_unsafe_fn_list(report: &Report) -> String {
+    let mut html = String::new();
+    html.push_str(
+        r#"
+        <div class="detail-section">
+            <h2>Unsafe Functions</h2>
+"#,
+    );
+
+    for (filename, unsafe_fns) in report.unsafe_fns_by_file() {
+        html.push_str(&format!(
+            r#"
+            <h3>{}</h3>
+            <ul>
+"#,
+            escape(filename)
+        ));
+        for site in unsafe_fns {
+            html.push_str(&format!(
+                r#"                <li><code>{}</code> {}</li>
+"#,
+                escape(&site.name),
+                html_link(
+                    format!("{filename}:{}", site.line),
+                    links::line_url(filename, site.line)
+                )
+            ));
+        }
+        html.push_str(
+            r#"            </ul>
+"#,
+        );
+    }
+
+    html.push_str(
+        r#"
+        </div>
+"#,
+    );
+
+    html
+}
+
+fn format_html_entry_points(entry_points: &[super::reachability::UnsafeEntryPoint]) -> String {
+    let mut html = String::new();
+    html.push_str(&format!(
+        r#"
+        <div class="detail-section">
+            <h2>Effectively Unsafe API</h2>
+            <p>{} public safe functions reach unsafe code</p>
+            <ul>
+"#,
+        entry_points.len()
+    ));
+
+    for entry_point in entry_points {
+        html.push_str(&format!(
+            r#"                <li><code>{}</code> {} (depth {}: {})</li>
+"#,
+            escape(&entry_point.fn_name),
+            html_link(
+                format!("{}:{}", entry_point.filename, entry_point.line_number),
+                links::line_url(&entry_point.filename, entry_point.line_number)
+            ),
+            entry_point.depth,
+            (entry_point.path.iter().map(escape))
+                .collect::<Vec<_>>()
+                .join(" &rarr; ")
+        ));
+    }
+
+    html.push_str(
+        r#"            </ul>
+        </div>
+"#,
+    );
+
+    html
+}
+
+fn format_html_untested_unsafe_fns(untested: &[(String, Site)]) -> String {
+    let mut html = String::new();
+    html.push_str(&format!(
+        r#"
+        <div class="detail-section">
+            <h2>Untested Unsafe Functions</h2>
+            <p>{} unsafe functions are not referenced by any test</p>
+            <ul>
+"#,
+        untested.len()
+    ));
+
+    for (filename, site) in untested {
+        html.push_str(&format!(
+            r#"                <li><code>{}</code> {}</li>
+"#,
+            escape(&site.name),
+            html_link(
+                format!("{filename}:{}", site.line),
+                links::line_url(filename, site.line)
+            )
+        ));
+    }
+
+    html.push_str(
+        r#"            </ul>
+        </div>
+"#,
+    );
+
+    html
+}
+
+fn format_html_audit_next(ranked: &[super::risk::FileRisk]) -> String {
+    let mut html = String::new();
+    html.push_str(
+        r#"
+        <div class="detail-section">
+            <h2>Audit Next</h2>
+            <table>
+                <thead>
+                    <tr><th>File</th><th>Risk</th><th>Unsafe statements</th><th>Static mut</th><th>Unchecked calls</th><th>No SAFETY comment</th><th>Coverage</th></tr>
+                </thead>
+                <tbody>
+"#,
+    );
+    for file in ranked.iter().take(super::AUDIT_NEXT_LIMIT) {
+        html.push_str(&format!(
+            r#"                    <tr><td>{}</td><td>{:.1}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>
+"#,
+            html_link(&file.filename, links::file_url(&file.filename)),
+            file.score,
+            file.unsafe_statements,
+            file.static_mut_items,
+            file.unchecked_calls,
+            file.missing_safety_comments,
+            file.coverage
+                .map_or("-".to_string(), |percent| format!("{percent:.1}%")),
+        ));
+    }
+    html.push_str(
+        r#"                </tbody>
+            </table>
+        </div>
+"#,
+    );
+    html
+}
+
+fn format_html_libc_calls(
+    census: &std::collections::BTreeMap<String, super::libc_calls::Counts>,
+) -> String {
+    let mut html = String::new();
+    html.push_str(
+        r#"
+        <div class="detail-section">
+            <h2>libc Calls</h2>
+            <table>
+                <thead>
+                    <tr><th>File</th><th>Function</th><th>Calls</th></tr>
+                </thead>
+                <tbody>
+"#,
+    );
+    for (filename, counts) in census {
+        for (function, count) in counts {
+            html.push_str(&format!(
+                r#"                    <tr><td>{}</td><td><code>libc::{}</code></td><td>{}</td></tr>
+"#,
+                html_link(filename, links::file_url(filename)),
+                escape(function),
+                count,
+            ));
+        }
+    }
+    html.push_str(
+        r#"                </tbody>
+            </table>
+        </div>
+"#,
+    );
+    html
+}
+
+fn format_html_unwrap_contexts(
+    census: &std::collections::BTreeMap<String, super::unwrap_contexts::Contexts>,
+) -> String {
+    let mut html = String::new();
+    html.push_str(
+        r#"
+        <div class="detail-section">
+            <h2>Unwraps by Context</h2>
+            <table>
+                <thead>
+                    <tr><th>File</th><th>Library</th><th>main()</th><th>Tests</th><th>Examples</th><th>Build Script</th></tr>
+                </thead>
+                <tbody>
+"#,
+    );
+    for (filename, contexts) in census {
+        html.push_str(&format!(
+            r#"                    <tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>
+"#,
+            html_link(filename, links::file_url(filename)),
+            contexts.library,
+            contexts.main,
+            contexts.tests,
+            contexts.examples,
+            contexts.build_scripts,
+        ));
+    }
+    html.push_str(
+        r#"                </tbody>
+            </table>
+        </div>
+"#,
+    );
+    html
+}
+
+fn format_html_allows(
+    census: &std::collections::BTreeMap<String, super::allows::Counts>,
+) -> String {
+    let mut html = String::new();
+    html.push_str(
+        r#"
+        <div class="detail-section">
+            <h2>Allowed Lints</h2>
+            <table>
+                <thead>
+                    <tr><th>File</th><th>Lint</th><th>Allows</th></tr>
+                </thead>
+                <tbody>
+"#,
+    );
+    for (filename, counts) in census {
+        for (lint, count) in counts {
+            html.push_str(&format!(
+                r#"                    <tr><td>{}</td><td><code>{}</code></td><td>{}</td></tr>
+"#,
+                html_link(filename, links::file_url(filename)),
+                escape(lint),
+                count,
+            ));
+        }
+    }
+    html.push_str(
+        r#"                </tbody>
+            </table>
+        </div>
+"#,
+    );
+    html
+}
+
+fn format_html_concurrency(
+    census: &std::collections::BTreeMap<String, super::concurrency::Surface>,
+) -> String {
+    let mut html = String::new();
+    html.push_str(
+        r#"
+        <div class="detail-section">
+            <h2>Concurrency Surface</h2>
+            <table>
+                <thead>
+                    <tr><th>File</th><th>Mutex</th><th>RwLock</th><th>Condvar</th><th>Atomics</th><th>Spawns</th><th>UnsafeCell</th></tr>
+                </thead>
+                <tbody>
+"#,
+    );
+    for (filename, surface) in census {
+        html.push_str(&format!(
+            r#"                    <tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>
+"#,
+            html_link(filename, links::file_url(filename)),
+            surface.mutexes,
+            surface.rw_locks,
+            surface.condvars,
+            surface.atomics,
+            surface.spawns,
+            surface.unsafe_cells,
+        ));
+    }
+    html.push_str(
+        r#"                </tbody>
+            </table>
+        </div>
+"#,
+    );
+    html
+}
+
+fn format_html_panic_sites(
+    census: &std::collections::BTreeMap<String, Vec<super::panic_sites::PanicSite>>,
+) -> String {
+    let mut html = String::new();
+    html.push_str(
+        r#"
+        <div class="detail-section">
+            <h2>Panic Sites</h2>
+            <ul>
+"#,
+    );
+    for (filename, sites) in census {
+        for site in sites {
+            html.push_str(&format!(
+                r#"                <li>{} {}</li>
+"#,
+                html_link(
+                    format!("{filename}:{}", site.line),
+                    links::line_url(filename, site.line)
+                ),
+                escape(&site.kind),
+            ));
+        }
+    }
+    html.push_str(
+        r#"            </ul>
+        </div>
+"#,
+    );
+    html
+}
+
+fn format_html_locations(report: &Report, kind: super::LocationKind) -> String {
+    let mut html = format!(
+        r#"
+        <div class="detail-section">
+            <h2>{}</h2>
+            <ul>
+"#,
+        kind.title()
+    );
+    for (filename, site) in super::locations(report, kind) {
+        html.push_str(&format!(
+            r#"                <li>{} {}</li>
+"#,
+            html_link(
+                format!("{filename}:{}", site.line),
+                links::line_url(filename, site.line)
+            ),
+            escape(super::precommit::describe(site)),
+        ));
+    }
+    html.push_str(
+        r#"            </ul>
+        </div>
+"#,
+    );
+    html
+}
+
+fn format_html_least_covered(report: &Report) -> String {
+    let mut html = String::new();
+    html.push_str(
+        r#"
+        <div class="detail-section">
+            <h2>Least-Covered Unsafe Code</h2>
+            <ul>
+"#,
+    );
+
+    for (filename, site, percent) in report
+        .least_covered_unsafe_fns()
+        .into_iter()
+        .take(super::LEAST_COVERED_LIMIT)
+    {
+        html.push_str(&format!(
+            r#"                <li><code>{}</code> {} ({:.1}%)</li>
+"#,
+            escape(&site.name),
+            html_link(
+                format!("{filename}:{}", site.line),
+                links::line_url(filename, site.line)
+            ),
+            percent
+        ));
+    }
+
+    html.push_str(
+        r#"            </ul>
+        </div>
+"#,
+    );
+
+    html
+}
+
+fn format_html_geiger(
+    report: &Report,
+    packages: &[super::geiger::GeigerPackage],
+    crate_root: &str,
+) -> String {
+    let mut html = String::new();
+    html.push_str(
+        r#"
+        <div class="detail-section">
+            <h2>cargo-geiger</h2>
+"#,
+    );
+
+    if let Some(name) = super::metadata::package(crate_root).map(|package| package.name)
+        && let Some(package) = packages.iter().find(|p| p.is(&name))
+    {
+        html.push_str(&format!(
+            r#"            <p>{}: {} unsafe fns (geiger: {}), {} unsafe statements (geiger exprs: {})</p>
+"#,
+            escape(&name),
+            report.total.unsafe_fns,
+            package.unsafe_fns,
+            report.total.unsafe_statements,
+            package.unsafe_exprs
+        ));
+    }
+
+    html.push_str(
+        r#"            <table>
+                <thead>
+                    <tr><th>Package</th><th>Unsafe Functions</th><th>Unsafe Exprs</th><th>Unsafe Impls</th><th>Forbids Unsafe</th></tr>
+                </thead>
+                <tbody>
+"#,
+    );
+    for package in packages {
+        html.push_str(&format!(
+            r#"                    <tr><td>{} {}</td><td class="{}">{}</td><td class="{}">{}</td><td class="{}">{}</td><td>{}</td></tr>
+"#,
+            escape(&package.name),
+            escape(&package.version),
+            get_count_class(package.unsafe_fns, Threshold::COUNT),
+            package.unsafe_fns,
+            get_count_class(package.unsafe_exprs, Threshold::COUNT),
+            package.unsafe_exprs,
+            get_count_class(package.unsafe_items, Threshold::COUNT),
+            package.unsafe_items,
+            if package.forbids_unsafe { "yes" } else { "no" }
+        ));
+    }
+    html.push_str(
+        r#"                </tbody>
+            </table>
+        </div>
+"#,
+    );
+
+    html
+}
+
+fn format_html_blame(
+    authors: &std::collections::BTreeMap<String, super::blame::AuthorStats>,
+) -> String {
+    let mut html = String::new();
+    html.push_str(
+        r#"
+        <div class="detail-section">
+            <h2>Ownership</h2>
+            <table>
+                <thead>
+                    <tr><th>Author</th><th>Unsafe Functions</th><th>Unsafe Blocks</th><th>Static Mut</th><th>Unwraps</th></tr>
+                </thead>
+                <tbody>
+"#,
+    );
+    for (author, stats) in super::sorted_authors(authors) {
+        html.push_str(&format!(
+            r#"                    <tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>
+"#,
+            escape(author),
+            stats.unsafe_fns,
+            stats.unsafe_blocks,
+            stats.static_mut_items,
+            stats.unwraps
+        ));
+    }
+    html.push_str(
+        r#"                </tbody>
+            </table>
+        </div>
+"#,
+    );
+
+    html
+}
+
+fn format_html_owners(owners: &std::collections::BTreeMap<String, CodeStats>) -> String {
+    let mut html = String::new();
+    html.push_str(
+        r#"
+        <div class="detail-section">
+            <h2>Owners</h2>
+            <table>
+                <thead>
+                    <tr><th>Owner</th><th>Unsafe/Total Functions</th><th>Unsafe Statements</th><th>Static Mut</th><th>Unwraps</th></tr>
+                </thead>
+                <tbody>
+"#,
+    );
+    for (owner, stats) in owners {
+        html.push_str(&format!(
+            r#"                    <tr><td>{}</td><td class="{}">{}/{}</td><td class="{}">{}</td><td class="{}">{}</td><td class="{}">{}</td></tr>
+"#,
+            escape(owner),
+            get_safety_class(stats.unsafe_fns, stats.total_fns),
+            stats.unsafe_fns,
+            stats.total_fns,
+            get_count_class(stats.unsafe_statements, config::threshold("unsafe_statements")),
+            stats.unsafe_statements,
+            get_count_class(stats.static_mut_items, config::threshold("static_mut_items")),
+            stats.static_mut_items,
+            get_count_class(stats.unwraps, config::threshold("unwraps")),
+            stats.unwraps
+        ));
+    }
+    html.push_str(
+        r#"                </tbody>
+            </table>
+        </div>
+"#,
+    );
+
+    html
+}
+
+fn format_html_leaderboard(contributors: &[(String, super::leaderboard::Reductions)]) -> String {
+    let mut html = String::new();
+    html.push_str(
+        r#"
+        <div class="detail-section">
+            <h2>🏆 Unsafe Reduction Leaderboard</h2>
+            <table>
+                <thead>
+                    <tr><th>Contributor</th><th>Unsafe Functions</th><th>Unsafe Statements</th><th>Unwraps</th><th>Total</th></tr>
+                </thead>
+                <tbody>
+"#,
+    );
+    for (author, removed) in contributors {
+        html.push_str(&format!(
+            r#"                    <tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td class="safe">{}</td></tr>
+"#,
+            escape(author),
+            removed.unsafe_fns,
+            removed.unsafe_statements,
+            removed.unwraps,
+            removed.total()
+        ));
+    }
+    html.push_str(
+        r#"                </tbody>
+            </table>
+        </div>
+"#,
+    );
+
+    html
+}
+
+fn format_html_migration(report: &Report, args: &Args) -> String {
+    let overall = super::migration::Progress::new("total".to_string(), &report.total);
+    let mut html = String::new();
+    html.push_str(&format!(
+        r#"
+        <div class="detail-section">
+            <h2>Migration Progress</h2>
+            <p>Complete: {:.1}% ({} unsafe fns and static mut items remaining)</p>
+"#,
+        overall.percent_complete(),
+        overall.remaining,
+    ));
+    if !args.deterministic {
+        html.push_str(&format!(
+            r#"            <p>Estimated completion: {}</p>
+"#,
+            super::migration_eta(args).unwrap_or_else(|| "unknown".into())
+        ));
+    }
+    html.push_str(
+        r#"            <table>
+                <thead>
+                    <tr><th>Directory</th><th>Complete</th><th>Remaining</th></tr>
+                </thead>
+                <tbody>
+"#,
+    );
+    for progress in super::migration::per_directory(report) {
+        html.push_str(&format!(
+            r#"                    <tr><td>{}</td><td class="{}">{:.1}%</td><td>{}</td></tr>
+"#,
+            escape(&progress.name),
+            if progress.remaining == 0 {
+                "safe"
+            } else {
+                "warning"
+            },
+            progress.percent_complete(),
+            progress.remaining
+        ));
+    }
+    html.push_str(
+        r#"                </tbody>
+            </table>
+        </div>
+"#,
+    );
+
+    html
+}
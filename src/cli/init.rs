@@ -0,0 +1,125 @@
+use std::{
+    fs,
+    path::Path,
+};
+
+use super::config::DEFAULT_CONFIG_FILE;
+
+/// The baseline written by `init`, which CI falls back to without a cached one
+pub const BASELINE_FILE: &str = "crate-report-baseline.csv";
+
+const WORKFLOW_FILE: &str = ".github/workflows/crate-report.yml";
+
+const CONFIG_TEMPLATE: &str = r#"# crate-report configuration, see https://github.com/richardscollin/crate-report
+
+# Shorten long paths in the report table (or --max-path-width)
+# max_path_width = 60
+
+# Values above `warning` are yellow, values at or above `danger` are red.
+# unsafe_fns is the percentage of unsafe fns, other metrics are counts.
+[thresholds.unsafe_fns]
+warning = 0
+danger = 50
+
+[thresholds.unwraps]
+warning = 0
+danger = 10
+
+[directions]
+# whether a decrease is `good` (green), `bad` (red) or `neutral` in diffs,
+# by default totals are neutral and everything else is good
+# total_fns = "neutral"
+"#;
+
+/// Pushes to main cache their report as the baseline for pull requests, which
+/// fall back to the committed baseline until the first push is analyzed.
+const WORKFLOW_TEMPLATE: &str = r#"name: Crate Report
+
+on:
+  push:
+    branches: [ main ]
+  pull_request:
+    branches: [ main ]
+    paths:
+      - '**/*.rs'
+
+permissions:
+  contents: read
+  pull-requests: write
+
+jobs:
+  crate-report:
+    runs-on: ubuntu-latest
+    steps:
+      - uses: actions/checkout@v5
+
+      - name: Install crate-report
+        run: |
+          mkdir -p ~/.local/bin
+          curl -L --proto '=https' --tlsv1.2 -sSf https://github.com/richardscollin/crate-report/releases/download/v{version}/crate-report -o ~/.local/bin/crate-report
+          chmod +x ~/.local/bin/crate-report
+
+      - name: Save baseline
+        if: ${{ github.event_name == 'push' }}
+        run: crate-report --format csv --output {baseline}
+
+      - name: Cache baseline
+        if: ${{ github.event_name == 'push' }}
+        uses: actions/cache/save@v4
+        with:
+          path: {baseline}
+          key: crate-report-baseline-${{ github.sha }}
+
+      - name: Restore baseline
+        if: ${{ github.event_name == 'pull_request' }}
+        uses: actions/cache/restore@v4
+        with:
+          path: {baseline}
+          key: crate-report-baseline-${{ github.event.pull_request.base.sha }}
+          restore-keys: crate-report-baseline-
+
+      - name: Generate report
+        if: ${{ github.event_name == 'pull_request' }}
+        run: crate-report --baseline {baseline} --format pr-comment --output pr-comment.md
+
+      - name: Comment on PR
+        if: ${{ github.event_name == 'pull_request' }}
+        run: |
+          COMMENT_ID=$(gh pr view ${{ github.event.number }} --json comments --jq '.comments[] | select(.author.login == "github-actions[bot]" and (.body | contains("Safety Analysis"))) | .id' | head -1)
+          if [ -n "$COMMENT_ID" ]; then
+            gh api repos/${{ github.repository }}/issues/comments/$COMMENT_ID --method PATCH --field body=@pr-comment.md
+          else
+            gh pr comment ${{ github.event.number }} --body-file pr-comment.md
+          fi
+        env:
+          GITHUB_TOKEN: ${{ secrets.GITHUB_TOKEN }}
+"#;
+
+fn workflow() -> String {
+    WORKFLOW_TEMPLATE
+        .replace("{version}", env!("CARGO_PKG_VERSION"))
+        .replace("{baseline}", BASELINE_FILE)
+}
+
+/// Write a starter config, `baseline` as the baseline and a GitHub Actions
+/// workflow into the crate root, leaving existing files alone unless `force`
+pub fn init(crate_root: impl AsRef<Path>, baseline: &str, force: bool) -> std::io::Result<()> {
+    let crate_root = crate_root.as_ref();
+    for (path, content) in [
+        (DEFAULT_CONFIG_FILE, CONFIG_TEMPLATE.to_string()),
+        (BASELINE_FILE, baseline.to_string()),
+        (WORKFLOW_FILE, workflow()),
+    ] {
+        let full_path = crate_root.join(path);
+        if full_path.exists() && !force {
+            println!("skipped {path}, it already exists (use --force to overwrite)");
+            continue;
+        }
+        if let Some(parent) = full_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&full_path, content)?;
+        println!("created {path}");
+    }
+    Ok(())
+}
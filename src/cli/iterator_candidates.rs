@@ -0,0 +1,452 @@
+use std::collections::BTreeMap;
+
+use syn::{
+    BinOp,
+    Expr,
+    ExprAssign,
+    ExprBinary,
+    ExprForLoop,
+    ExprIndex,
+    ExprMethodCall,
+    ExprPath,
+    ExprReference,
+    ExprWhile,
+    ImplItemFn,
+    ItemFn,
+    Lit,
+    Pat,
+    RangeLimits,
+    TraitItemFn,
+    UnOp,
+    spanned::Spanned,
+    visit::Visit,
+};
+
+use super::{
+    Confidence,
+    Suggestion,
+    once_candidates::is_mutating_method,
+};
+
+#[derive(Clone, Default, Debug)]
+pub struct FileStats {
+    pub filename: String,
+    pub stats: CodeStats,
+}
+
+#[derive(Clone, Debug)]
+pub struct IteratorCandidate {
+    pub fn_name: String,
+    pub line_number: usize,
+    /// the loop's index variable
+    pub index: String,
+    /// the iterator the loop could walk instead, like `a.iter().enumerate()`
+    pub suggestion: String,
+    pub confidence: Confidence,
+}
+
+impl Suggestion for IteratorCandidate {
+    fn subject(&self) -> String {
+        self.fn_name.clone()
+    }
+
+    fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    fn confidence(&self) -> Confidence {
+        self.confidence
+    }
+
+    fn detail(&self) -> Option<String> {
+        Some(format!("`{}` -> `{}`", self.index, self.suggestion))
+    }
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct CodeStats {
+    pub candidates: Vec<IteratorCandidate>,
+}
+
+pub struct CodeAnalyzer<'a> {
+    stats: &'a mut CodeStats,
+    /// the innermost fn, loops outside of fns aren't looked at
+    fn_name: Option<String>,
+}
+
+/// The name of a variable expression, like `i`
+fn variable(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Path(path) => path.path.get_ident().map(|ident| ident.to_string()),
+        _ => None,
+    }
+}
+
+fn is_int_literal(expr: &Expr, value: u128) -> bool {
+    match expr {
+        Expr::Lit(lit) => match &lit.lit {
+            Lit::Int(int) => int.base10_parse::<u128>().is_ok_and(|int| int == value),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Whether a binary operator assigns to its left, like `+=`
+fn is_compound_assign(op: &BinOp) -> bool {
+    matches!(
+        op,
+        BinOp::AddAssign(_)
+            | BinOp::SubAssign(_)
+            | BinOp::MulAssign(_)
+            | BinOp::DivAssign(_)
+            | BinOp::RemAssign(_)
+            | BinOp::BitXorAssign(_)
+            | BinOp::BitAndAssign(_)
+            | BinOp::BitOrAssign(_)
+            | BinOp::ShlAssign(_)
+            | BinOp::ShrAssign(_)
+    )
+}
+
+/// How a loop body uses its index variable
+struct IndexUses<'a> {
+    var: &'a str,
+    /// the expressions indexed by exactly the variable, and whether they're
+    /// written through it
+    indexed: BTreeMap<String, bool>,
+    /// uses other than indexing and `var += 1`
+    other_uses: usize,
+    increments: usize,
+}
+
+impl IndexUses<'_> {
+    fn check_index(&mut self, i: &ExprIndex, mutable: bool) -> bool {
+        if variable(&i.index).as_deref() != Some(self.var) {
+            return false;
+        }
+        let Some(expr) = i.expr.span().source_text() else {
+            return false;
+        };
+        *self.indexed.entry(expr).or_default() |= mutable;
+        self.visit_expr(&i.expr);
+        true
+    }
+
+    /// The iterator over what's indexed, zipped together when it's several,
+    /// and enumerated when the index is used for anything else
+    fn suggestion(&self) -> String {
+        let mut iterators = self.indexed.iter().map(|(expr, mutable)| {
+            let iter = if *mutable { "iter_mut" } else { "iter" };
+            format!("{expr}.{iter}()")
+        });
+        let mut suggestion = iterators.next().unwrap_or_default();
+        for iterator in iterators {
+            suggestion = format!("{suggestion}.zip({iterator})");
+        }
+        if self.other_uses > 0 {
+            suggestion.push_str(".enumerate()");
+        }
+        suggestion
+    }
+
+    /// High for a `for` loop up to the `len()` of the one thing it indexes,
+    /// and using the index and what it indexes for nothing else, medium for
+    /// other `for` loops and low for `while` loops, whose index may change in
+    /// ways not looked at
+    fn confidence(
+        &self,
+        bound: Option<&Expr>,
+        is_while: bool,
+        indexed_uses: &IndexedUses,
+    ) -> Confidence {
+        if is_while {
+            return Confidence::Low;
+        }
+        let bound = bound.and_then(|bound| bound.span().source_text());
+        match (self.indexed.keys().next(), bound) {
+            (Some(expr), Some(bound))
+                if self.indexed.len() == 1
+                    && self.other_uses == 0
+                    && indexed_uses.other_uses == 0
+                    && bound == format!("{expr}.len()") =>
+            {
+                Confidence::High
+            }
+            _ => Confidence::Medium,
+        }
+    }
+
+    /// Visit an expression which is written to, like the left of an `=`
+    fn visit_place(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Index(index) if self.check_index(index, true) => {}
+            _ => self.visit_expr(expr),
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for IndexUses<'_> {
+    fn visit_expr_index(&mut self, i: &'ast ExprIndex) {
+        if !self.check_index(i, false) {
+            syn::visit::visit_expr_index(self, i);
+        }
+    }
+
+    fn visit_expr_path(&mut self, i: &'ast ExprPath) {
+        if i.path.is_ident(self.var) {
+            self.other_uses += 1;
+        }
+        syn::visit::visit_expr_path(self, i);
+    }
+
+    fn visit_expr_assign(&mut self, i: &'ast ExprAssign) {
+        self.visit_place(&i.left);
+        self.visit_expr(&i.right);
+    }
+
+    fn visit_expr_binary(&mut self, i: &'ast ExprBinary) {
+        if matches!(i.op, BinOp::AddAssign(_))
+            && variable(&i.left).as_deref() == Some(self.var)
+            && is_int_literal(&i.right, 1)
+        {
+            self.increments += 1;
+            return;
+        }
+        if is_compound_assign(&i.op) {
+            self.visit_place(&i.left);
+            self.visit_expr(&i.right);
+        } else {
+            syn::visit::visit_expr_binary(self, i);
+        }
+    }
+
+    fn visit_expr_reference(&mut self, i: &'ast ExprReference) {
+        if i.mutability.is_some() {
+            self.visit_place(&i.expr);
+        } else {
+            syn::visit::visit_expr_reference(self, i);
+        }
+    }
+}
+
+/// How a loop body uses what its index variable indexes, other than
+/// indexing it with the variable
+struct IndexedUses<'a> {
+    var: &'a str,
+    indexed: &'a BTreeMap<String, bool>,
+    /// calls of methods like `push`, assignments and `&mut` borrows, which
+    /// an iterator borrowing it would conflict with
+    mutations: usize,
+    other_uses: usize,
+}
+
+impl IndexedUses<'_> {
+    fn is_indexed(&self, expr: &Expr) -> bool {
+        expr.span()
+            .source_text()
+            .is_some_and(|text| self.indexed.contains_key(&text))
+    }
+
+    /// Visit an expression which is written to, like the left of an `=`
+    fn visit_place(&mut self, expr: &Expr) {
+        match expr {
+            _ if self.is_indexed(expr) => self.mutations += 1,
+            // `*v = ...` through a `&mut Vec`
+            Expr::Unary(unary) if matches!(unary.op, UnOp::Deref(_)) => {
+                self.visit_place(&unary.expr)
+            }
+            Expr::Paren(paren) => self.visit_place(&paren.expr),
+            _ => self.visit_expr(expr),
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for IndexedUses<'_> {
+    fn visit_expr(&mut self, i: &'ast Expr) {
+        if self.is_indexed(i) {
+            self.other_uses += 1;
+        } else {
+            syn::visit::visit_expr(self, i);
+        }
+    }
+
+    fn visit_expr_index(&mut self, i: &'ast ExprIndex) {
+        if variable(&i.index).as_deref() != Some(self.var) || !self.is_indexed(&i.expr) {
+            syn::visit::visit_expr_index(self, i);
+        }
+    }
+
+    fn visit_expr_method_call(&mut self, i: &'ast ExprMethodCall) {
+        if !self.is_indexed(&i.receiver) {
+            syn::visit::visit_expr_method_call(self, i);
+            return;
+        }
+        if is_mutating_method(&i.method.to_string()) {
+            self.mutations += 1;
+        } else {
+            self.other_uses += 1;
+        }
+        for arg in &i.args {
+            self.visit_expr(arg);
+        }
+    }
+
+    fn visit_expr_assign(&mut self, i: &'ast ExprAssign) {
+        self.visit_place(&i.left);
+        self.visit_expr(&i.right);
+    }
+
+    fn visit_expr_binary(&mut self, i: &'ast ExprBinary) {
+        if is_compound_assign(&i.op) {
+            self.visit_place(&i.left);
+            self.visit_expr(&i.right);
+        } else {
+            syn::visit::visit_expr_binary(self, i);
+        }
+    }
+
+    fn visit_expr_reference(&mut self, i: &'ast ExprReference) {
+        if i.mutability.is_some() {
+            self.visit_place(&i.expr);
+        } else {
+            syn::visit::visit_expr_reference(self, i);
+        }
+    }
+}
+
+impl CodeAnalyzer<'_> {
+    fn push(
+        &mut self,
+        var: &str,
+        bound: Option<&Expr>,
+        body: &syn::Block,
+        line_number: usize,
+        is_while: bool,
+    ) {
+        let Some(fn_name) = &self.fn_name else {
+            return;
+        };
+        let mut uses = IndexUses {
+            var,
+            indexed: BTreeMap::new(),
+            other_uses: 0,
+            increments: 0,
+        };
+        uses.visit_block(body);
+        // a while loop has to step through the indices one by one
+        if uses.indexed.is_empty() || (is_while && uses.increments != 1) {
+            return;
+        }
+        let mut indexed_uses = IndexedUses {
+            var,
+            indexed: &uses.indexed,
+            mutations: 0,
+            other_uses: 0,
+        };
+        indexed_uses.visit_block(body);
+        // growing or replacing what's iterated over can't be done while an
+        // iterator borrows it
+        if indexed_uses.mutations > 0 {
+            return;
+        }
+        self.stats.candidates.push(IteratorCandidate {
+            fn_name: fn_name.clone(),
+            line_number,
+            index: var.to_string(),
+            suggestion: uses.suggestion(),
+            confidence: uses.confidence(bound, is_while, &indexed_uses),
+        });
+    }
+}
+
+impl<'ast> Visit<'ast> for CodeAnalyzer<'_> {
+    fn visit_item_fn(&mut self, i: &'ast ItemFn) {
+        let outer = self.fn_name.replace(i.sig.ident.to_string());
+        syn::visit::visit_item_fn(self, i);
+        self.fn_name = outer;
+    }
+
+    fn visit_impl_item_fn(&mut self, i: &'ast ImplItemFn) {
+        let outer = self.fn_name.replace(i.sig.ident.to_string());
+        syn::visit::visit_impl_item_fn(self, i);
+        self.fn_name = outer;
+    }
+
+    fn visit_trait_item_fn(&mut self, i: &'ast TraitItemFn) {
+        let outer = self.fn_name.replace(i.sig.ident.to_string());
+        syn::visit::visit_trait_item_fn(self, i);
+        self.fn_name = outer;
+    }
+
+    /// `for i in 0..n`
+    fn visit_expr_for_loop(&mut self, i: &'ast ExprForLoop) {
+        if let Pat::Ident(pat) = &*i.pat
+            && let Expr::Range(range) = &*i.expr
+            && matches!(range.limits, RangeLimits::HalfOpen(_))
+            && range
+                .start
+                .as_deref()
+                .is_some_and(|start| is_int_literal(start, 0))
+        {
+            let line_number = i.for_token.span.start().line;
+            self.push(
+                &pat.ident.to_string(),
+                range.end.as_deref(),
+                &i.body,
+                line_number,
+                false,
+            );
+        }
+        syn::visit::visit_expr_for_loop(self, i);
+    }
+
+    /// `while i < n`, stepping with `i += 1`
+    fn visit_expr_while(&mut self, i: &'ast ExprWhile) {
+        if let Expr::Binary(cond) = &*i.cond
+            && matches!(cond.op, BinOp::Lt(_))
+            && let Some(var) = variable(&cond.left)
+        {
+            let line_number = i.while_token.span.start().line;
+            self.push(&var, Some(&cond.right), &i.body, line_number, true);
+        }
+        syn::visit::visit_expr_while(self, i);
+    }
+}
+
+fn analyze_file(syntax: &syn::File) -> CodeStats {
+    let mut stats = CodeStats::default();
+    let mut visitor = CodeAnalyzer {
+        stats: &mut stats,
+        fn_name: None,
+    };
+    visitor.visit_file(syntax);
+
+    stats
+}
+
+/// Find index loops which could walk an iterator instead, avoiding the bounds
+/// checks
+/// The heuristic is a `for i in 0..n` loop, or a `while i < n` loop stepping
+/// with `i += 1`, whose body indexes something with exactly `i` and doesn't
+/// grow or replace it, it's high confidence for `for i in 0..a.len()` only
+/// indexing `a` and using it for nothing else
+pub fn find_candidates(
+    files: &[(String, syn::File)],
+    min_confidence: Confidence,
+) -> Vec<FileStats> {
+    files
+        .iter()
+        .map(|(filename, syntax)| {
+            let mut stats = analyze_file(syntax);
+            stats
+                .candidates
+                .retain(|candidate| candidate.confidence >= min_confidence);
+            FileStats {
+                filename: filename.clone(),
+                stats,
+            }
+        })
+        .filter(|report| !report.stats.candidates.is_empty())
+        .collect()
+}
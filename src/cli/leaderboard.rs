@@ -0,0 +1,71 @@
+use std::{
+    collections::BTreeMap,
+    path::Path,
+};
+
+use super::{
+    CodeStats,
+    analyze_source_sites,
+    git,
+};
+
+/// How much unsafe code a contributor removed
+#[derive(Clone, Debug, Default)]
+pub struct Reductions {
+    pub unsafe_fns: isize,
+    pub unsafe_statements: isize,
+    pub unwraps: isize,
+}
+
+impl Reductions {
+    pub fn total(&self) -> isize {
+        self.unsafe_fns + self.unsafe_statements + self.unwraps
+    }
+}
+
+/// Analyze a file at a revision, files which don't exist (or don't parse) count as empty
+fn stats_at(crate_root: &Path, rev: &str, path: &str) -> CodeStats {
+    git::show_file(crate_root, rev, path)
+        .and_then(|content| analyze_source_sites(&content))
+        .map(|(stats, _)| stats)
+        .unwrap_or_default()
+}
+
+/// Rank contributors by how much unsafe code they removed over a git range
+///
+/// Every non-merge commit in the range is compared file by file against its
+/// first parent. Only reductions are credited: a commit adding unsafe code to
+/// one file and removing it from another still gets credit for the removal.
+pub fn rank_contributors(crate_root: impl AsRef<Path>, range: &str) -> Vec<(String, Reductions)> {
+    let crate_root = crate_root.as_ref();
+    let Some(commits) = git::commits_in_range(crate_root, range) else {
+        eprintln!("Error: failed to list commits in '{range}'");
+        std::process::exit(super::EXIT_ERROR);
+    };
+
+    let mut per_author: BTreeMap<String, Reductions> = BTreeMap::new();
+    for commit in &commits {
+        let Some(author) = git::author(crate_root, commit) else {
+            continue;
+        };
+        let reductions = per_author.entry(author).or_default();
+        let parent = format!("{commit}^");
+
+        for path in git::changed_rust_files(crate_root, commit).unwrap_or_default() {
+            let before = stats_at(crate_root, &parent, &path);
+            let after = stats_at(crate_root, commit, &path);
+
+            reductions.unsafe_fns += (before.unsafe_fns - after.unsafe_fns).max(0);
+            reductions.unsafe_statements +=
+                (before.unsafe_statements - after.unsafe_statements).max(0);
+            reductions.unwraps += (before.unwraps - after.unwraps).max(0);
+        }
+    }
+
+    let mut contributors: Vec<(String, Reductions)> = per_author
+        .into_iter()
+        .filter(|(_, reductions)| reductions.total() > 0)
+        .collect();
+    contributors.sort_by_key(|(_, reductions)| std::cmp::Reverse(reductions.total()));
+    contributors
+}
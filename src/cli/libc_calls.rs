@@ -0,0 +1,98 @@
+use std::{
+    collections::{
+        BTreeMap,
+        BTreeSet,
+    },
+    fs,
+    path::Path,
+};
+
+use syn::{
+    Expr,
+    ExprCall,
+    ItemUse,
+    UseTree,
+    visit::Visit,
+};
+
+use super::Report;
+
+/// Calls per libc fn name
+pub type Counts = BTreeMap<String, usize>;
+
+#[derive(Default)]
+struct LibcCallCollector {
+    /// fns imported with `use libc::{...}`, called without the `libc::` prefix
+    imported: BTreeSet<String>,
+    counts: Counts,
+}
+
+/// Collect the names a `use` tree under `libc::` brings into scope, globs
+/// can't be told apart from other calls so they're skipped
+fn imported_names(tree: &UseTree, names: &mut BTreeSet<String>) {
+    match tree {
+        UseTree::Name(name) => {
+            names.insert(name.ident.to_string());
+        }
+        UseTree::Group(group) => {
+            for tree in &group.items {
+                imported_names(tree, names);
+            }
+        }
+        UseTree::Path(_) | UseTree::Rename(_) | UseTree::Glob(_) => {}
+    }
+}
+
+impl<'ast> Visit<'ast> for LibcCallCollector {
+    fn visit_item_use(&mut self, i: &'ast ItemUse) {
+        if let UseTree::Path(path) = &i.tree
+            && path.ident == "libc"
+        {
+            imported_names(&path.tree, &mut self.imported);
+        }
+        syn::visit::visit_item_use(self, i);
+    }
+
+    fn visit_expr_call(&mut self, i: &'ast ExprCall) {
+        if let Expr::Path(expr) = &*i.func {
+            let segments: Vec<String> = expr
+                .path
+                .segments
+                .iter()
+                .map(|segment| segment.ident.to_string())
+                .collect();
+            let name = match segments.as_slice() {
+                [libc, name] if libc == "libc" => Some(name),
+                [name] if self.imported.contains(name) => Some(name),
+                _ => None,
+            };
+            if let Some(name) = name {
+                *self.counts.entry(name.clone()).or_default() += 1;
+            }
+        }
+        syn::visit::visit_expr_call(self, i);
+    }
+}
+
+/// Count the direct calls of `libc::*` fns in each file of the report, those
+/// made inside macro invocations aren't seen
+pub fn census(root: impl AsRef<Path>, report: &Report) -> BTreeMap<String, Counts> {
+    report
+        .files
+        .keys()
+        .filter_map(|filename| {
+            let content = fs::read_to_string(root.as_ref().join(filename)).ok()?;
+            let syntax = syn::parse_file(&content).ok()?;
+            let mut collector = LibcCallCollector::default();
+            // imports can follow the calls they're for, so take the top-level
+            // ones first
+            for item in &syntax.items {
+                if let syn::Item::Use(item) = item {
+                    collector.visit_item_use(item);
+                }
+            }
+            collector.visit_file(&syntax);
+            (!collector.counts.is_empty()).then(|| (filename.clone(), collector.counts))
+        })
+        .collect()
+}
@@ -0,0 +1,117 @@
+use std::sync::OnceLock;
+
+use super::git;
+
+/// Where filenames link to
+enum Host {
+    /// GitHub, GitLab and the like, the URL filenames are appended to e.g.
+    /// `https://github.com/owner/repo/blob/<ref>/<crate dir>/`
+    Blob(String),
+    /// Azure DevOps, which takes the path and version as query parameters
+    AzureDevOps {
+        repo_url: String,
+        /// the crate directory within the repository
+        prefix: String,
+        /// `GB<branch>` or `GC<commit>`
+        version: String,
+    },
+}
+
+static HOST: OnceLock<Host> = OnceLock::new();
+
+/// Link filenames in reports to `repo_url` at `rev`, each detected from the
+/// crate's git checkout when not given
+///
+/// The detected revision is the checked out commit, so links keep pointing at
+/// the analyzed code. It isn't detected for `--deterministic` reports, which
+/// would otherwise change with every commit.
+pub fn init(crate_root: &str, repo_url: Option<&str>, rev: Option<&str>, deterministic: bool) {
+    let Some(repo_url) = repo_url.map(str::to_string).or_else(|| {
+        let remote = git::git(crate_root, ["remote", "get-url", "origin"])?;
+        web_url(remote.trim())
+    }) else {
+        return;
+    };
+    let Some(rev) = rev.map(str::to_string).or_else(|| {
+        let head = git::git(crate_root, ["rev-parse", "HEAD"]).filter(|_| !deterministic)?;
+        Some(head.trim().to_string())
+    }) else {
+        return;
+    };
+    // filenames are relative to the crate root, which may be a subdirectory
+    let prefix = git::repo_prefix(crate_root);
+
+    let repo_url = repo_url.trim_end_matches('/');
+    tracing::info!("linking filenames to {repo_url} at {rev}");
+    let host = if repo_url.contains("/_git/") {
+        let is_commit = rev.len() == 40 && rev.chars().all(|c| c.is_ascii_hexdigit());
+        Host::AzureDevOps {
+            repo_url: repo_url.to_string(),
+            prefix,
+            version: format!("{}{rev}", if is_commit { "GC" } else { "GB" }),
+        }
+    } else {
+        let blob = if repo_url.contains("gitlab") {
+            "-/blob"
+        } else {
+            "blob"
+        };
+        Host::Blob(format!("{repo_url}/{blob}/{rev}/{prefix}"))
+    };
+    _ = HOST.set(host);
+}
+
+/// The browsable URL of a git remote, without any credentials in it
+fn web_url(remote: &str) -> Option<String> {
+    let remote = remote.trim_end_matches(".git");
+    let (host, path) = if let Some(rest) = remote
+        .strip_prefix("https://")
+        .or_else(|| remote.strip_prefix("http://"))
+        .or_else(|| remote.strip_prefix("ssh://"))
+    {
+        rest.split_once('/')?
+    } else {
+        // scp-like `git@github.com:owner/repo`
+        let (host, path) = remote.split_once(':')?;
+        if host.contains('/') {
+            return None;
+        }
+        (host, path)
+    };
+    let host = host.rsplit_once('@').map_or(host, |(_, host)| host);
+    if host == "ssh.dev.azure.com" {
+        // `git@ssh.dev.azure.com:v3/org/project/repo`
+        let mut parts = path.strip_prefix("v3/")?.splitn(3, '/');
+        let (org, project, repo) = (parts.next()?, parts.next()?, parts.next()?);
+        return Some(format!("https://dev.azure.com/{org}/{project}/_git/{repo}"));
+    }
+    Some(format!("https://{host}/{path}"))
+}
+
+/// The URL of a file in the repository, if linking is enabled
+pub fn file_url(filename: &str) -> Option<String> {
+    if filename == super::STDIN_FILENAME {
+        return None;
+    }
+    Some(match HOST.get()? {
+        Host::Blob(base) => format!("{base}{filename}"),
+        Host::AzureDevOps {
+            repo_url,
+            prefix,
+            version,
+        } => format!("{repo_url}?path=/{prefix}{filename}&version={version}"),
+    })
+}
+
+/// The URL of a line of a file in the repository, if linking is enabled
+pub fn line_url(filename: &str, line: usize) -> Option<String> {
+    let file_url = file_url(filename)?;
+    Some(match HOST.get()? {
+        Host::Blob(_) => format!("{file_url}#L{line}"),
+        // selects the whole line
+        Host::AzureDevOps { .. } => format!(
+            "{file_url}&line={line}&lineEnd={}&lineStartColumn=1&lineEndColumn=1",
+            line + 1
+        ),
+    })
+}
@@ -0,0 +1,68 @@
+use std::{
+    collections::{
+        BTreeMap,
+        BTreeSet,
+    },
+    path::Path,
+};
+
+use super::{
+    Report,
+    packages::Packages,
+};
+
+/// Split a `NAME=PATH` argument, naming a bare path by its file stem
+fn name_and_path(arg: &str) -> (String, &str) {
+    if let Some((name, path)) = arg.split_once('=')
+        && !name.is_empty()
+    {
+        return (name.to_string(), path);
+    }
+    let name = Path::new(arg)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| arg.to_string());
+    (name, arg)
+}
+
+/// Combine CSV or JSON reports into one, with each report's file keys
+/// prefixed by its name so files with the same path in two reports stay apart,
+/// and the reports as packages for rendering
+pub fn merge(reports: &[String]) -> Result<(Report, Packages), String> {
+    let mut names = BTreeMap::new();
+    let mut files = BTreeMap::new();
+    let mut unknown_metrics = BTreeSet::new();
+    for arg in reports {
+        let (name, path) = name_and_path(arg);
+        if let Some(other) = names.insert(name.clone(), path) {
+            return Err(format!(
+                "'{other}' and '{path}' are both named '{name}', name them with NAME=PATH"
+            ));
+        }
+        let report = std::fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| Report::load_baseline(path, &content))
+            .map_err(|err| format!("failed to load '{path}': {err}"))?;
+        tracing::info!("merging {path} as {name}/: {} files", report.files.len());
+
+        // a metric missing from one report makes the merged total an undercount
+        unknown_metrics.extend(report.unknown_metrics);
+        files.extend(
+            report
+                .files
+                .into_iter()
+                .map(|(filename, stats)| (format!("{name}/{filename}"), stats)),
+        );
+    }
+    if !unknown_metrics.is_empty() {
+        tracing::warn!(
+            "some reports are missing metrics, their merged totals are incomplete: {:?}",
+            unknown_metrics
+        );
+    }
+    let report = Report {
+        unknown_metrics,
+        ..Report::from_files(files)
+    };
+    Ok((report, Packages::from_names(names.into_keys())))
+}
@@ -0,0 +1,93 @@
+use std::{
+    fmt,
+    fs,
+    path::Path,
+    time::{
+        SystemTime,
+        UNIX_EPOCH,
+    },
+};
+
+use super::history;
+
+/// The `[package]` of a crate's Cargo.toml
+#[derive(Clone, Debug)]
+pub struct Package {
+    pub name: String,
+    /// `None` when inherited from the workspace
+    pub version: Option<String>,
+    pub edition: Option<String>,
+}
+
+/// Read the package from a crate's Cargo.toml, `None` for a virtual workspace
+/// manifest or a missing or invalid one
+pub fn package(crate_root: impl AsRef<Path>) -> Option<Package> {
+    let manifest: toml::Table = fs::read_to_string(crate_root.as_ref().join("Cargo.toml"))
+        .ok()?
+        .parse()
+        .ok()?;
+    let package = manifest.get("package")?.as_table()?;
+    let field = |key| {
+        package
+            .get(key)
+            .and_then(toml::Value::as_str)
+            .map(str::to_string)
+    };
+
+    Some(Package {
+        name: field("name")?,
+        version: field("version"),
+        edition: field("edition"),
+    })
+}
+
+/// What a report was generated from, shown in report headers so archived
+/// reports are self-describing
+#[derive(Clone, Debug)]
+pub struct Metadata {
+    pub package: Option<Package>,
+    /// seconds since the unix epoch, `None` for reproducible reports
+    pub timestamp: Option<u64>,
+}
+
+impl Metadata {
+    pub fn collect(crate_root: impl AsRef<Path>, deterministic: bool) -> Self {
+        Self {
+            package: package(crate_root),
+            timestamp: (!deterministic).then(|| {
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|duration| duration.as_secs())
+                    .unwrap_or_default()
+            }),
+        }
+    }
+}
+
+/// e.g. `foo 1.2.0 (edition 2021) analyzed 2025-06-01 14:03 UTC by crate-report 0.9.0`
+impl fmt::Display for Metadata {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(package) = &self.package {
+            write!(f, "{}", package.name)?;
+            if let Some(version) = &package.version {
+                write!(f, " {version}")?;
+            }
+            if let Some(edition) = &package.edition {
+                write!(f, " (edition {edition})")?;
+            }
+            write!(f, " analyzed")?;
+        } else {
+            write!(f, "Analyzed")?;
+        }
+        if let Some(timestamp) = self.timestamp {
+            write!(
+                f,
+                " {} {:02}:{:02} UTC",
+                history::format_date(timestamp),
+                timestamp % 86_400 / 3600,
+                timestamp % 3600 / 60
+            )?;
+        }
+        write!(f, " by crate-report {}", env!("CARGO_PKG_VERSION"))
+    }
+}
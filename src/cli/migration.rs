@@ -0,0 +1,82 @@
+use super::{
+    CodeStats,
+    Report,
+    history::HistoryEntry,
+};
+
+/// Migration progress towards zero unsafe fns and static mut items
+#[derive(Clone, Debug)]
+pub struct Progress {
+    pub name: String,
+    /// unsafe fns and static mut items left to convert
+    pub remaining: isize,
+    /// all fns and static mut items, i.e. the work when nothing was converted yet
+    pub total: isize,
+}
+
+impl Progress {
+    pub fn new(name: String, stats: &CodeStats) -> Self {
+        Self {
+            name,
+            remaining: remaining(stats),
+            total: stats.total_fns + stats.static_mut_items,
+        }
+    }
+
+    pub fn percent_complete(&self) -> f64 {
+        if self.total == 0 {
+            100.0
+        } else {
+            (self.total - self.remaining) as f64 / self.total as f64 * 100.0
+        }
+    }
+}
+
+pub fn remaining(stats: &CodeStats) -> isize {
+    stats.unsafe_fns + stats.static_mut_items
+}
+
+/// Progress aggregated per directory containing source files
+pub fn per_directory(report: &Report) -> Vec<Progress> {
+    report
+        .directories()
+        .into_iter()
+        .map(|(directory, stats)| Progress::new(directory, &stats))
+        .collect()
+}
+
+/// Fit a least squares line through the remaining work over time and return
+/// the timestamp at which it reaches zero
+///
+/// Returns `None` with fewer than two data points, or when the remaining work
+/// isn't trending down.
+pub fn estimate_completion(history: &[HistoryEntry]) -> Option<u64> {
+    if history.len() < 2 {
+        return None;
+    }
+
+    let n = history.len() as f64;
+    let points: Vec<(f64, f64)> = history
+        .iter()
+        .map(|entry| (entry.timestamp as f64, remaining(&entry.totals) as f64))
+        .collect();
+    let mean_t = points.iter().map(|(t, _)| t).sum::<f64>() / n;
+    let mean_r = points.iter().map(|(_, r)| r).sum::<f64>() / n;
+
+    let covariance: f64 = points
+        .iter()
+        .map(|(t, r)| (t - mean_t) * (r - mean_r))
+        .sum();
+    let variance: f64 = points.iter().map(|(t, _)| (t - mean_t).powi(2)).sum();
+    if variance == 0.0 {
+        return None;
+    }
+
+    let slope = covariance / variance;
+    if slope >= 0.0 {
+        return None;
+    }
+
+    let intercept = mean_r - slope * mean_t;
+    Some((-intercept / slope).max(0.0) as u64)
+}
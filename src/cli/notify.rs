@@ -0,0 +1,152 @@
+use std::cmp::Reverse;
+
+use super::{
+    Args,
+    CodeStats,
+    Diff,
+    Report,
+    config,
+    metadata,
+};
+
+/// How many of the files which regressed the most are listed
+const WORST_REGRESSIONS: usize = 3;
+
+/// The safety metrics summarized, with their labels
+const METRICS: [(&str, &str); 4] = [
+    ("unsafe_fns", "unsafe fns"),
+    ("unsafe_statements", "unsafe statements"),
+    ("static_mut_items", "static mut items"),
+    ("unwraps", "unwraps"),
+];
+
+/// Slack and Discord disagree on bold and links
+enum Flavor {
+    Slack,
+    Discord,
+}
+
+impl Flavor {
+    fn bold(&self, text: &str) -> String {
+        match self {
+            Flavor::Slack => format!("*{text}*"),
+            Flavor::Discord => format!("**{text}**"),
+        }
+    }
+
+    fn link(&self, text: &str, url: &str) -> String {
+        match self {
+            Flavor::Slack => format!("<{url}|{text}>"),
+            Flavor::Discord => format!("[{text}]({url})"),
+        }
+    }
+}
+
+/// A few lines: the totals with their change against the first baseline, the
+/// files which regressed the most and a link to the full report
+fn summary(
+    report: &Report,
+    baselines: &[(String, Report)],
+    args: &Args,
+    flavor: &Flavor,
+) -> String {
+    let metadata = metadata::Metadata::collect(&args.crate_root, args.deterministic);
+    let diff = baselines.first().map(|(_, baseline)| report.diff(baseline));
+
+    let mut out = format!("{}\n", flavor.bold(&format!("crate-report: {metadata}")));
+    let totals: Vec<String> = METRICS
+        .iter()
+        .map(|(metric, label)| {
+            let after = report.total.metric(metric).unwrap_or_default();
+            let before = diff
+                .as_ref()
+                .filter(|diff| !diff.unknown_metrics.contains(*metric))
+                .and_then(|diff| diff.before_total.metric(metric));
+            match before.map(|before| after - before) {
+                Some(delta) if delta != 0 => format!("{label} {after} ({delta:+})"),
+                _ => format!("{label} {after}"),
+            }
+        })
+        .collect();
+    out.push_str(&totals.join(" · "));
+    out.push('\n');
+
+    if let Some(diff) = &diff {
+        // files ordered by how much their metrics moved in the bad direction
+        let mut regressions: Vec<(isize, String)> = diff
+            .changes
+            .iter()
+            .filter_map(|(filename, change)| {
+                let (before, after) = match change {
+                    Diff::Added(after) => (CodeStats::default(), after),
+                    Diff::Changed(change) => (change.before.clone(), &change.after),
+                    Diff::Removed(_) => return None,
+                };
+                let regressed: Vec<(isize, String)> = METRICS
+                    .iter()
+                    .filter_map(|(metric, label)| {
+                        let before = before.metric(metric)?;
+                        let after = after.metric(metric)?;
+                        config::decrease_is(metric)
+                            .is_regression(before, after)
+                            .then(|| {
+                                (
+                                    (after - before).abs(),
+                                    format!("{label} {:+}", after - before),
+                                )
+                            })
+                    })
+                    .collect();
+                if regressed.is_empty() {
+                    return None;
+                }
+                let size = regressed.iter().map(|(size, _)| size).sum();
+                let changes: Vec<String> =
+                    regressed.into_iter().map(|(_, change)| change).collect();
+                Some((size, format!("{filename} ({})", changes.join(", "))))
+            })
+            .collect();
+        regressions.sort_by_key(|(size, _)| Reverse(*size));
+        if !regressions.is_empty() {
+            let worst: Vec<String> = regressions
+                .into_iter()
+                .take(WORST_REGRESSIONS)
+                .map(|(_, regression)| regression)
+                .collect();
+            out.push_str(&format!("Worst regressions: {}\n", worst.join(", ")));
+        }
+    }
+
+    if let Some(url) = &args.report_url {
+        out.push_str(&flavor.link("Full report", url));
+        out.push('\n');
+    }
+    out
+}
+
+/// Post a summary of the report to a Slack or Discord incoming webhook
+pub fn post(
+    url: &str,
+    report: &Report,
+    baselines: &[(String, Report)],
+    args: &Args,
+) -> Result<(), String> {
+    let flavor = if url.contains("discord") {
+        Flavor::Discord
+    } else {
+        Flavor::Slack
+    };
+    let text = summary(report, baselines, args, &flavor);
+    // Slack reads `text` and Discord `content`
+    let payload = match flavor {
+        Flavor::Slack => serde_json::json!({ "text": text }),
+        Flavor::Discord => serde_json::json!({ "content": text }),
+    };
+    // webhook URLs are secrets, so they aren't logged
+    tracing::info!("posting a summary to the webhook");
+    ureq::post(url)
+        .header("Content-Type", "application/json")
+        .send(payload.to_string())
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
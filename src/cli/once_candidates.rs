@@ -0,0 +1,364 @@
+use std::collections::{
+    BTreeMap,
+    BTreeSet,
+};
+
+use syn::{
+    BinOp,
+    Expr,
+    ExprAssign,
+    ExprBinary,
+    ExprMethodCall,
+    ExprPath,
+    ExprReference,
+    ImplItemFn,
+    ItemFn,
+    Signature,
+    spanned::Spanned,
+    visit::Visit,
+};
+
+use super::{
+    Confidence,
+    Suggestion,
+    context_candidates::{
+        Global,
+        static_muts,
+    },
+};
+
+#[derive(Clone, Default, Debug)]
+pub struct FileStats {
+    pub filename: String,
+    pub stats: CodeStats,
+}
+
+#[derive(Clone, Debug)]
+pub struct OnceCandidate {
+    pub name: String,
+    pub line_number: usize,
+    /// the function writing the static, and the line, unless it's never written
+    pub write: Option<(String, usize)>,
+    /// the functions reading the static
+    pub readers: BTreeSet<String>,
+    /// what the static could be instead, like `OnceLock<Config>`
+    pub suggestion: String,
+    pub confidence: Confidence,
+}
+
+impl Suggestion for OnceCandidate {
+    fn subject(&self) -> String {
+        self.name.clone()
+    }
+
+    fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    fn confidence(&self) -> Confidence {
+        self.confidence
+    }
+
+    fn detail(&self) -> Option<String> {
+        let written = match &self.write {
+            Some((function, line)) => format!("written once in `{function}` (line {line})"),
+            None => "never written".to_string(),
+        };
+        let readers: Vec<String> = self
+            .readers
+            .iter()
+            .map(|reader| format!("`{reader}`"))
+            .collect();
+        Some(format!(
+            "{written}, read in {} -> `{}`",
+            readers.join(", "),
+            self.suggestion
+        ))
+    }
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct CodeStats {
+    pub candidates: Vec<OnceCandidate>,
+}
+
+/// A function, and whether it takes any arguments
+#[derive(Clone)]
+struct Function {
+    name: String,
+    has_inputs: bool,
+}
+
+/// A write of a global, and whether it's the whole value, like `CONFIG = ..`
+/// rather than `CONFIG.name = ..` or `TABLE.push(..)`
+struct Write {
+    function: Function,
+    line: usize,
+    whole: bool,
+}
+
+/// Where each global is written and read
+#[derive(Default)]
+struct Uses {
+    writes: Vec<Write>,
+    readers: BTreeSet<String>,
+}
+
+struct Accesses<'a> {
+    globals: &'a BTreeMap<String, Global>,
+    /// the innermost fn
+    function: Option<Function>,
+    uses: BTreeMap<String, Uses>,
+}
+
+/// The static a place expression is in, like `CONFIG` for `CONFIG.name` or
+/// `TABLE[i]`
+fn place_base(expr: &Expr) -> Option<&ExprPath> {
+    match expr {
+        Expr::Path(path) => Some(path),
+        Expr::Field(field) => place_base(&field.base),
+        Expr::Index(index) => place_base(&index.expr),
+        Expr::Paren(paren) => place_base(&paren.expr),
+        _ => None,
+    }
+}
+
+/// Whether a fn is named like it sets things up, like `init_config` or
+/// `load_settings`
+fn is_init_fn_name(name: &str) -> bool {
+    name.contains("init") || name.starts_with("setup") || name.starts_with("load")
+}
+
+/// Whether a method likely takes `&mut self`, going by its name since the
+/// receiver's type isn't known
+pub fn is_mutating_method(name: &str) -> bool {
+    name.ends_with("_mut")
+        || name.starts_with("push")
+        || name.starts_with("sort")
+        || name.starts_with("get_or_insert")
+        || matches!(
+            name,
+            "append"
+                | "clear"
+                | "dedup"
+                | "drain"
+                | "extend"
+                | "fill"
+                | "insert"
+                | "pop"
+                | "remove"
+                | "replace"
+                | "resize"
+                | "retain"
+                | "reverse"
+                | "set"
+                | "swap"
+                | "take"
+                | "truncate"
+        )
+}
+
+impl Accesses<'_> {
+    fn global(&self, path: &ExprPath) -> Option<String> {
+        let name = path.path.segments.last()?.ident.to_string();
+        self.globals.contains_key(&name).then_some(name)
+    }
+
+    fn visit_fn_scope(&mut self, sig: &Signature, visit: impl FnOnce(&mut Self)) {
+        let function = Function {
+            name: sig.ident.to_string(),
+            has_inputs: !sig.inputs.is_empty(),
+        };
+        let outer = self.function.replace(function);
+        visit(self);
+        self.function = outer;
+    }
+
+    /// Visit an expression which is written to, counting a write of the static
+    /// it's in, if any, `assigned` when it's the left of an `=`
+    fn visit_place(&mut self, expr: &Expr, assigned: bool) {
+        if let Some(function) = &self.function
+            && let Some(name) = place_base(expr).and_then(|base| self.global(base))
+        {
+            let write = Write {
+                function: function.clone(),
+                line: expr.span().start().line,
+                whole: assigned && matches!(expr, Expr::Path(_)),
+            };
+            self.uses.entry(name).or_default().writes.push(write);
+            // only the indices are left to visit, like `i` in `TABLE[i]`
+            let mut expr = expr;
+            loop {
+                match expr {
+                    Expr::Field(field) => expr = &field.base,
+                    Expr::Index(index) => {
+                        self.visit_expr(&index.index);
+                        expr = &index.expr;
+                    }
+                    Expr::Paren(paren) => expr = &paren.expr,
+                    _ => break,
+                }
+            }
+        } else {
+            self.visit_expr(expr);
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for Accesses<'_> {
+    fn visit_item_fn(&mut self, i: &'ast ItemFn) {
+        self.visit_fn_scope(&i.sig, |this| syn::visit::visit_item_fn(this, i));
+    }
+
+    fn visit_impl_item_fn(&mut self, i: &'ast ImplItemFn) {
+        self.visit_fn_scope(&i.sig, |this| syn::visit::visit_impl_item_fn(this, i));
+    }
+
+    fn visit_expr_assign(&mut self, i: &'ast ExprAssign) {
+        self.visit_place(&i.left, true);
+        self.visit_expr(&i.right);
+    }
+
+    fn visit_expr_binary(&mut self, i: &'ast ExprBinary) {
+        let assigns = matches!(
+            i.op,
+            BinOp::AddAssign(_)
+                | BinOp::SubAssign(_)
+                | BinOp::MulAssign(_)
+                | BinOp::DivAssign(_)
+                | BinOp::RemAssign(_)
+                | BinOp::BitXorAssign(_)
+                | BinOp::BitAndAssign(_)
+                | BinOp::BitOrAssign(_)
+                | BinOp::ShlAssign(_)
+                | BinOp::ShrAssign(_)
+        );
+        if assigns {
+            self.visit_place(&i.left, false);
+            self.visit_expr(&i.right);
+        } else {
+            syn::visit::visit_expr_binary(self, i);
+        }
+    }
+
+    fn visit_expr_reference(&mut self, i: &'ast ExprReference) {
+        if i.mutability.is_some() {
+            self.visit_place(&i.expr, false);
+        } else {
+            syn::visit::visit_expr_reference(self, i);
+        }
+    }
+
+    fn visit_expr_method_call(&mut self, i: &'ast ExprMethodCall) {
+        if is_mutating_method(&i.method.to_string()) {
+            self.visit_place(&i.receiver, false);
+            for arg in &i.args {
+                self.visit_expr(arg);
+            }
+        } else {
+            syn::visit::visit_expr_method_call(self, i);
+        }
+    }
+
+    fn visit_expr_path(&mut self, i: &'ast ExprPath) {
+        if let Some(function) = &self.function
+            && let Some(name) = self.global(i)
+        {
+            self.uses
+                .entry(name)
+                .or_default()
+                .readers
+                .insert(function.name.clone());
+        }
+        syn::visit::visit_expr_path(self, i);
+    }
+}
+
+/// Find `static mut` items which could be a `OnceLock`, a `LazyLock` or a
+/// plain `static`
+/// The heuristic is a static which is read, and written by at most one
+/// assignment: never written it could be a `static`, written by a function
+/// without arguments a `LazyLock` computing the value, and otherwise a
+/// `OnceLock` set where it's written
+/// It's high confidence for a static never written, or assigned whole in a
+/// function named like `init`, and low when only part of it is written
+pub fn find_candidates(
+    files: &[(String, syn::File)],
+    min_confidence: Confidence,
+) -> Vec<FileStats> {
+    // statics are used from other files than their own
+    let globals = static_muts(files);
+    let mut accesses = Accesses {
+        globals: &globals,
+        function: None,
+        uses: BTreeMap::new(),
+    };
+    for (_, syntax) in files {
+        accesses.visit_file(syntax);
+    }
+
+    let mut file_reports: BTreeMap<String, CodeStats> = BTreeMap::new();
+    for (name, uses) in accesses.uses {
+        let mut readers = uses.readers;
+        let (write, suggestion, confidence) = match uses.writes.as_slice() {
+            [] => (None, "static".to_string(), Confidence::High),
+            [
+                Write {
+                    function,
+                    line,
+                    whole,
+                },
+            ] => {
+                // the initializer's own reads, like `CONFIG.is_none()`, don't
+                // count
+                readers.remove(&function.name);
+                // the lock is what makes the value optional until it's set
+                let ty = &globals[&name].ty;
+                let ty = ty
+                    .strip_prefix("Option<")
+                    .and_then(|ty| ty.strip_suffix('>'))
+                    .unwrap_or(ty);
+                let suggestion = if function.has_inputs {
+                    format!("OnceLock<{ty}>")
+                } else {
+                    format!("LazyLock<{ty}>")
+                };
+                let confidence = if !whole {
+                    Confidence::Low
+                } else if is_init_fn_name(&function.name) {
+                    Confidence::High
+                } else {
+                    Confidence::Medium
+                };
+                (Some((function.name.clone(), *line)), suggestion, confidence)
+            }
+            _ => continue,
+        };
+        if readers.is_empty() || confidence < min_confidence {
+            continue;
+        }
+        let global = &globals[&name];
+        file_reports
+            .entry(global.filename.clone())
+            .or_default()
+            .candidates
+            .push(OnceCandidate {
+                name,
+                line_number: global.line_number,
+                write,
+                readers,
+                suggestion,
+                confidence,
+            });
+    }
+
+    file_reports
+        .into_iter()
+        .map(|(filename, mut stats)| {
+            stats
+                .candidates
+                .sort_by_key(|candidate| candidate.line_number);
+            FileStats { filename, stats }
+        })
+        .collect()
+}
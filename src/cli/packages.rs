@@ -0,0 +1,108 @@
+use std::{
+    collections::BTreeMap,
+    path::Path,
+};
+
+use walkdir::WalkDir;
+
+use super::{
+    CodeStats,
+    metadata,
+    normalize_path,
+};
+
+/// The group for files which aren't in any package, e.g. loose scripts
+pub const UNPACKAGED: &str = "(no package)";
+
+/// The packages under a crate root, i.e. a workspace's members or just the
+/// crate itself
+#[derive(Clone, Debug, Default)]
+pub struct Packages {
+    /// (directory relative to the crate root with a trailing `/`, package
+    /// name), the root package's directory is empty
+    packages: Vec<(String, String)>,
+}
+
+impl Packages {
+    /// Find every Cargo.toml with a `[package]` under the crate root, skipping
+    /// `target` directories like the file walk does
+    pub fn find(crate_root: impl AsRef<Path>) -> Self {
+        let crate_root = crate_root.as_ref();
+        let mut packages: Vec<(String, String)> = WalkDir::new(crate_root)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|e| e.file_name().to_str() != Some("target"))
+            .filter_map(Result::ok)
+            .filter(|e| e.file_name() == "Cargo.toml")
+            .filter_map(|e| {
+                let dir = e.path().parent()?;
+                let package = metadata::package(dir)?;
+                let relative = normalize_path(
+                    &dir.strip_prefix(crate_root)
+                        .unwrap_or(dir)
+                        .display()
+                        .to_string(),
+                );
+                let prefix = if relative.is_empty() {
+                    relative
+                } else {
+                    format!("{relative}/")
+                };
+                Some((prefix, package.name))
+            })
+            .collect();
+        // the innermost package wins for nested ones
+        packages.sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.len()));
+        Self { packages }
+    }
+
+    /// Packages in the directories of the same name, e.g. for merged reports
+    /// whose file keys are prefixed with each report's name
+    pub fn from_names(names: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            packages: names
+                .into_iter()
+                .map(|name| (format!("{name}/"), name))
+                .collect(),
+        }
+    }
+
+    /// Package names in name order
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self
+            .packages
+            .iter()
+            .map(|(_, name)| name.as_str())
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Whether there's more than one package, i.e. the crate root is a workspace
+    pub fn is_workspace(&self) -> bool {
+        self.packages.len() > 1
+    }
+
+    /// The package a file relative to the crate root belongs to, if any
+    pub fn package_of(&self, filename: &str) -> Option<&str> {
+        self.packages
+            .iter()
+            .find(|(prefix, _)| filename.starts_with(prefix.as_str()))
+            .map(|(_, name)| name.as_str())
+    }
+
+    /// Aggregate per-file stats into per-package stats
+    pub fn group(&self, files: &BTreeMap<String, CodeStats>) -> BTreeMap<String, CodeStats> {
+        let mut per_package: BTreeMap<String, Vec<CodeStats>> = BTreeMap::new();
+        for (filename, stats) in files {
+            per_package
+                .entry(self.package_of(filename).unwrap_or(UNPACKAGED).to_string())
+                .or_default()
+                .push(stats.clone());
+        }
+        per_package
+            .into_iter()
+            .map(|(package, stats)| (package, stats.into_iter().sum()))
+            .collect()
+    }
+}
@@ -0,0 +1,180 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+    fs,
+    path::Path,
+};
+
+use syn::{
+    BinOp,
+    Expr,
+    ExprBinary,
+    ExprIndex,
+    ExprMethodCall,
+    ItemFn,
+    ItemMod,
+    Lit,
+    Macro,
+    spanned::Spanned,
+    visit::Visit,
+};
+
+use super::{
+    Report,
+    untested::{
+        is_cfg_test_attr,
+        is_test_attr,
+    },
+};
+
+/// The macros which panic unconditionally or when their condition fails,
+/// `debug_assert!`s are left out since they're compiled out of release builds
+const PANIC_MACROS: &[&str] = &[
+    "assert",
+    "assert_eq",
+    "assert_ne",
+    "panic",
+    "todo",
+    "unimplemented",
+    "unreachable",
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PanicKind {
+    Unwrap,
+    Expect,
+    Index,
+    Division,
+    Remainder,
+    /// one of [`PANIC_MACROS`], by name
+    Macro(String),
+}
+
+impl fmt::Display for PanicKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PanicKind::Unwrap => write!(f, "`unwrap`"),
+            PanicKind::Expect => write!(f, "`expect`"),
+            PanicKind::Index => write!(f, "indexing"),
+            PanicKind::Division => write!(f, "division"),
+            PanicKind::Remainder => write!(f, "remainder"),
+            PanicKind::Macro(name) => write!(f, "`{name}!`"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PanicSite {
+    pub line: usize,
+    pub kind: PanicKind,
+}
+
+/// Whether dividing by an expression can panic: unless it's a non-zero
+/// integer literal, or either side is a float literal
+fn may_divide_by_zero(i: &ExprBinary) -> bool {
+    let literal = |expr: &Expr| match expr {
+        Expr::Lit(lit) => Some(lit.lit.clone()),
+        _ => None,
+    };
+    match (literal(&i.left), literal(&i.right)) {
+        (Some(Lit::Float(_)), _) | (_, Some(Lit::Float(_))) => false,
+        (_, Some(Lit::Int(int))) => int.base10_parse::<u128>().is_ok_and(|int| int == 0),
+        _ => true,
+    }
+}
+
+#[derive(Default)]
+struct PanicCollector {
+    /// nesting depth of test fns and `#[cfg(test)]` modules
+    in_test: usize,
+    sites: Vec<PanicSite>,
+}
+
+impl PanicCollector {
+    fn push(&mut self, kind: PanicKind, span: proc_macro2::Span) {
+        if self.in_test == 0 {
+            self.sites.push(PanicSite {
+                line: span.start().line,
+                kind,
+            });
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for PanicCollector {
+    fn visit_item_fn(&mut self, i: &'ast ItemFn) {
+        let is_test = i.attrs.iter().any(is_test_attr);
+        self.in_test += is_test as usize;
+        syn::visit::visit_item_fn(self, i);
+        self.in_test -= is_test as usize;
+    }
+
+    fn visit_item_mod(&mut self, i: &'ast ItemMod) {
+        let is_test = i.attrs.iter().any(is_cfg_test_attr);
+        self.in_test += is_test as usize;
+        syn::visit::visit_item_mod(self, i);
+        self.in_test -= is_test as usize;
+    }
+
+    fn visit_expr_method_call(&mut self, i: &'ast ExprMethodCall) {
+        if i.method == "unwrap" {
+            self.push(PanicKind::Unwrap, i.method.span());
+        } else if i.method == "expect" {
+            self.push(PanicKind::Expect, i.method.span());
+        }
+        syn::visit::visit_expr_method_call(self, i);
+    }
+
+    fn visit_expr_index(&mut self, i: &'ast ExprIndex) {
+        self.push(PanicKind::Index, i.bracket_token.span.open());
+        syn::visit::visit_expr_index(self, i);
+    }
+
+    fn visit_expr_binary(&mut self, i: &'ast ExprBinary) {
+        let kind = match i.op {
+            BinOp::Div(_) | BinOp::DivAssign(_) => Some(PanicKind::Division),
+            BinOp::Rem(_) | BinOp::RemAssign(_) => Some(PanicKind::Remainder),
+            _ => None,
+        };
+        if let Some(kind) = kind
+            && may_divide_by_zero(i)
+        {
+            self.push(kind, i.op.span());
+        }
+        syn::visit::visit_expr_binary(self, i);
+    }
+
+    fn visit_macro(&mut self, i: &'ast Macro) {
+        if let Some(segment) = i.path.segments.last()
+            && PANIC_MACROS.contains(&segment.ident.to_string().as_str())
+        {
+            self.push(
+                PanicKind::Macro(segment.ident.to_string()),
+                segment.ident.span(),
+            );
+        }
+        syn::visit::visit_macro(self, i);
+    }
+}
+
+/// List where each file of the report can panic: unwraps, expects, indexing,
+/// division and remainder by anything but a non-zero literal, and panicking
+/// macros, outside of test fns and `#[cfg(test)]` modules
+///
+/// It's a syntactic audit, so overflow, calls which panic internally and
+/// anything inside macro arguments, like an `unwrap` in a `format!`, aren't
+/// seen.
+pub fn census(root: impl AsRef<Path>, report: &Report) -> BTreeMap<String, Vec<PanicSite>> {
+    report
+        .files
+        .keys()
+        .filter_map(|filename| {
+            let content = fs::read_to_string(root.as_ref().join(filename)).ok()?;
+            let syntax = syn::parse_file(&content).ok()?;
+            let mut collector = PanicCollector::default();
+            collector.visit_file(&syntax);
+            collector.sites.sort_by_key(|site| site.line);
+            (!collector.sites.is_empty()).then(|| (filename.clone(), collector.sites))
+        })
+        .collect()
+}
@@ -0,0 +1,106 @@
+use std::collections::{
+    BTreeMap,
+    BTreeSet,
+};
+
+use syn::{
+    Expr,
+    ExprCall,
+    ExprMethodCall,
+    Macro,
+    visit::Visit,
+};
+
+use crate::plugin::{
+    FileInfo,
+    MetricVisitor,
+};
+
+/// Counts the uses of a project's own helpers, configured under `[patterns]`
+///
+/// A pattern ending in `!` counts invocations of that macro, like `c!` or
+/// `cstr!`, any other counts calls of fns and methods with that name, like
+/// `transmute_ptr`. Only the last path segment is compared, so
+/// `ptr::transmute_ptr(p)` matches `transmute_ptr`. Like the built-in metrics,
+/// uses inside the arguments of other macros aren't seen.
+pub struct PatternCounter {
+    metric: String,
+    fns: BTreeSet<String>,
+    macros: BTreeSet<String>,
+}
+
+impl PatternCounter {
+    pub fn new(metric: &str, patterns: &[String]) -> Result<Self, String> {
+        let mut counter = Self {
+            metric: metric.to_string(),
+            fns: BTreeSet::new(),
+            macros: BTreeSet::new(),
+        };
+        for pattern in patterns {
+            let (name, set) = match pattern.strip_suffix('!') {
+                Some(name) => (name, &mut counter.macros),
+                None => (pattern.as_str(), &mut counter.fns),
+            };
+            if syn::parse_str::<syn::Ident>(name).is_err() {
+                return Err(format!(
+                    "invalid pattern '{pattern}' for '{metric}', expected a fn name or a macro name followed by '!'"
+                ));
+            }
+            set.insert(name.to_string());
+        }
+        Ok(counter)
+    }
+}
+
+struct PatternVisitor<'a> {
+    counter: &'a PatternCounter,
+    count: isize,
+}
+
+impl<'ast> Visit<'ast> for PatternVisitor<'_> {
+    fn visit_expr_call(&mut self, i: &'ast ExprCall) {
+        if let Expr::Path(expr) = &*i.func
+            && expr
+                .path
+                .segments
+                .last()
+                .is_some_and(|segment| self.counter.fns.contains(&segment.ident.to_string()))
+        {
+            self.count += 1;
+        }
+        syn::visit::visit_expr_call(self, i);
+    }
+
+    fn visit_expr_method_call(&mut self, i: &'ast ExprMethodCall) {
+        if self.counter.fns.contains(&i.method.to_string()) {
+            self.count += 1;
+        }
+        syn::visit::visit_expr_method_call(self, i);
+    }
+
+    fn visit_macro(&mut self, i: &'ast Macro) {
+        if i.path
+            .segments
+            .last()
+            .is_some_and(|segment| self.counter.macros.contains(&segment.ident.to_string()))
+        {
+            self.count += 1;
+        }
+        syn::visit::visit_macro(self, i);
+    }
+}
+
+impl MetricVisitor for PatternCounter {
+    fn metrics(&self) -> Vec<String> {
+        vec![self.metric.clone()]
+    }
+
+    fn count(&self, file: &syn::File, _info: FileInfo<'_>) -> BTreeMap<String, isize> {
+        let mut visitor = PatternVisitor {
+            counter: self,
+            count: 0,
+        };
+        visitor.visit_file(file);
+        BTreeMap::from([(self.metric.clone(), visitor.count)])
+    }
+}
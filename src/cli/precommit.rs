@@ -0,0 +1,146 @@
+use std::{
+    collections::BTreeMap,
+    path::Path,
+};
+
+use super::{
+    Repr,
+    Site,
+    SiteKind,
+    analyze_source_with,
+    deprecated,
+    git,
+    rust_files,
+};
+
+/// A comment allowing new unsafe code or an unwrap, on its line or the line above
+pub const SUPPRESSION: &str = "crate-report: allow";
+
+/// Unsafe code or an unwrap in a staged file which isn't in HEAD
+#[derive(Clone, Debug)]
+pub struct Violation {
+    pub filename: String,
+    pub line: usize,
+    pub description: String,
+}
+
+fn is_suppressed(lines: &[&str], line: usize) -> bool {
+    let end = line.min(lines.len());
+    let start = line.saturating_sub(2).min(end);
+    lines[start..end]
+        .iter()
+        .any(|line| line.contains("//") && line.contains(SUPPRESSION))
+}
+
+/// e.g. ``unsafe fn `read` `` or ``unwrap in `main` ``
+pub fn describe(site: &Site) -> String {
+    let kind = match site.kind {
+        SiteKind::Allocation => "manual allocation",
+        SiteKind::DeprecatedUse => "use of deprecated",
+        SiteKind::ExternClosure => "closure cast to an extern fn",
+        SiteKind::ExternFn => "extern fn",
+        SiteKind::FfiString => "FFI string conversion",
+        SiteKind::GlobalAllocator => "global allocator",
+        SiteKind::LeakProne => "leak-prone call",
+        SiteKind::ProcessExit => "process exit",
+        SiteKind::Recursive { exposed: false } => "recursive fn",
+        SiteKind::Recursive { exposed: true } => "recursive unsafe or exported fn",
+        SiteKind::Repr(Repr::C) => "repr(C) type",
+        SiteKind::Repr(Repr::Transparent) => "repr(transparent) type",
+        SiteKind::Repr(Repr::Packed) => "repr(packed) type",
+        SiteKind::StaticMut => "static mut",
+        SiteKind::ThreadUnsafe => "thread-unsafe call",
+        SiteKind::TrivialExpect => "expect with a trivial message",
+        SiteKind::UnsafeBlock => "unsafe block",
+        SiteKind::UnsafeFn => "unsafe fn",
+        SiteKind::Unwrap => "unwrap",
+    };
+    match site.kind {
+        _ if site.name.is_empty() => kind.to_string(),
+        SiteKind::DeprecatedUse
+        | SiteKind::ExternFn
+        | SiteKind::GlobalAllocator
+        | SiteKind::Recursive { .. }
+        | SiteKind::Repr(_)
+        | SiteKind::StaticMut
+        | SiteKind::UnsafeFn => format!("{kind} `{}`", site.name),
+        SiteKind::Allocation
+        | SiteKind::ExternClosure
+        | SiteKind::FfiString
+        | SiteKind::LeakProne
+        | SiteKind::ProcessExit
+        | SiteKind::ThreadUnsafe
+        | SiteKind::TrivialExpect
+        | SiteKind::UnsafeBlock
+        | SiteKind::Unwrap => {
+            format!("{kind} in `{}`", site.name)
+        }
+    }
+}
+
+/// The sites in `current` which aren't in `previous`
+///
+/// Sites are matched up by kind and name rather than line, like the todo
+/// checklist, so code moving around within a file doesn't count as new.
+pub fn added_sites(previous: Vec<Site>, current: impl IntoIterator<Item = Site>) -> Vec<Site> {
+    let mut existing: BTreeMap<(SiteKind, String), usize> = BTreeMap::new();
+    for site in previous {
+        *existing.entry((site.kind, site.name)).or_default() += 1;
+    }
+
+    current
+        .into_iter()
+        .filter(
+            |site| match existing.get_mut(&(site.kind, site.name.clone())) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    false
+                }
+                _ => true,
+            },
+        )
+        .collect()
+}
+
+/// Compare the staged version of every changed `.rs` file against HEAD
+///
+/// Sites are matched up like [`added_sites`]. Files which don't parse are left
+/// for the compiler to complain about.
+pub fn check(crate_root: impl AsRef<Path>) -> Vec<Violation> {
+    let crate_root = crate_root.as_ref();
+    let Some(files) = git::staged_rust_files(crate_root) else {
+        eprintln!("Error: failed to list staged files");
+        std::process::exit(super::EXIT_ERROR);
+    };
+
+    // deprecated items can be defined in any file, staged or not
+    let deprecated =
+        deprecated::definitions_in(rust_files(crate_root).map(walkdir::DirEntry::into_path));
+    let mut violations = Vec::new();
+    for filename in files {
+        // an empty revision is the index, i.e. `git show :./path`
+        let Some(staged) = git::show_file(crate_root, "", &filename) else {
+            continue;
+        };
+        let Some((_, staged_sites)) = analyze_source_with(&staged, &deprecated) else {
+            continue;
+        };
+        let lines: Vec<&str> = staged.lines().collect();
+
+        let head_sites = git::show_file(crate_root, "HEAD", &filename)
+            .as_deref()
+            .and_then(|head| analyze_source_with(head, &deprecated))
+            .map(|(_, sites)| sites)
+            .unwrap_or_default();
+        for site in added_sites(head_sites, staged_sites) {
+            if site.kind.is_hazard() && !is_suppressed(&lines, site.line) {
+                violations.push(Violation {
+                    filename: filename.clone(),
+                    line: site.line,
+                    description: describe(&site),
+                });
+            }
+        }
+    }
+    violations
+}
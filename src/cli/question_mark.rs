@@ -0,0 +1,216 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    io,
+    path::Path,
+};
+
+use syn::{
+    ExprAsync,
+    ExprClosure,
+    ExprMethodCall,
+    ImplItemFn,
+    ItemFn,
+    ReturnType,
+    Signature,
+    TraitItemFn,
+    Type,
+    spanned::Spanned,
+    visit::Visit,
+};
+
+use super::Report;
+
+/// What a fn returns, for `?` to propagate into
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Returns {
+    Option,
+    Result,
+}
+
+impl Returns {
+    /// Going by the last segment of the return type's path, so `io::Result<()>`
+    /// is a `Result` but an alias with another name isn't
+    pub fn of(sig: &Signature) -> Option<Self> {
+        // `?` isn't allowed in const fns
+        if sig.constness.is_some() {
+            return None;
+        }
+        let ReturnType::Type(_, ty) = &sig.output else {
+            return None;
+        };
+        let Type::Path(ty) = &**ty else {
+            return None;
+        };
+        match ty.path.segments.last()?.ident.to_string().as_str() {
+            "Option" => Some(Returns::Option),
+            "Result" => Some(Returns::Result),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Returns::Option => "Option",
+            Returns::Result => "Result",
+        }
+    }
+}
+
+/// The fn a `?` would return from
+#[derive(Clone, Debug)]
+pub struct FnScope {
+    pub name: String,
+    pub returns: Option<Returns>,
+}
+
+/// A visitor tracking the innermost fn, for the `?`s it could propagate into
+pub trait InFnScope: Sized {
+    /// `None` outside of fns and in closures and async blocks, which `?`
+    /// would return from instead
+    fn scope(&mut self) -> &mut Option<FnScope>;
+
+    fn visit_scope(&mut self, scope: Option<FnScope>, visit: impl FnOnce(&mut Self)) {
+        let outer = std::mem::replace(self.scope(), scope);
+        visit(self);
+        *self.scope() = outer;
+    }
+
+    fn visit_fn_scope(&mut self, sig: &Signature, visit: impl FnOnce(&mut Self)) {
+        let scope = FnScope {
+            name: sig.ident.to_string(),
+            returns: Returns::of(sig),
+        };
+        self.visit_scope(Some(scope), visit);
+    }
+}
+
+/// An `.unwrap()` or `.expect(...)` which could be a `?`
+#[derive(Clone, Debug)]
+pub struct Candidate {
+    pub line: usize,
+    /// `unwrap` or `expect`
+    pub method: String,
+    pub fn_name: String,
+    pub returns: Returns,
+    /// the line and the columns from the `.` to past the `)`, for an
+    /// `.unwrap()` on one line
+    rewrite: Option<(usize, usize, usize)>,
+}
+
+#[derive(Default)]
+struct CandidateCollector {
+    scope: Option<FnScope>,
+    candidates: Vec<Candidate>,
+}
+
+impl InFnScope for CandidateCollector {
+    fn scope(&mut self) -> &mut Option<FnScope> {
+        &mut self.scope
+    }
+}
+
+impl<'ast> Visit<'ast> for CandidateCollector {
+    fn visit_item_fn(&mut self, i: &'ast ItemFn) {
+        self.visit_fn_scope(&i.sig, |this| syn::visit::visit_item_fn(this, i));
+    }
+
+    fn visit_impl_item_fn(&mut self, i: &'ast ImplItemFn) {
+        self.visit_fn_scope(&i.sig, |this| syn::visit::visit_impl_item_fn(this, i));
+    }
+
+    fn visit_trait_item_fn(&mut self, i: &'ast TraitItemFn) {
+        self.visit_fn_scope(&i.sig, |this| syn::visit::visit_trait_item_fn(this, i));
+    }
+
+    fn visit_expr_closure(&mut self, i: &'ast ExprClosure) {
+        self.visit_scope(None, |this| syn::visit::visit_expr_closure(this, i));
+    }
+
+    fn visit_expr_async(&mut self, i: &'ast ExprAsync) {
+        self.visit_scope(None, |this| syn::visit::visit_expr_async(this, i));
+    }
+
+    fn visit_expr_method_call(&mut self, i: &'ast ExprMethodCall) {
+        if matches!(i.method.to_string().as_str(), "unwrap" | "expect")
+            && let Some(FnScope {
+                name,
+                returns: Some(returns),
+            }) = &self.scope
+        {
+            let start = i.dot_token.span().start();
+            let end = i.paren_token.span.close().end();
+            let rewrite = (i.method == "unwrap" && i.turbofish.is_none() && start.line == end.line)
+                .then_some((start.line, start.column, end.column));
+            self.candidates.push(Candidate {
+                line: i.method.span().start().line,
+                method: i.method.to_string(),
+                fn_name: name.clone(),
+                returns: *returns,
+                rewrite,
+            });
+        }
+        syn::visit::visit_expr_method_call(self, i);
+    }
+}
+
+/// Find the unwraps and expects in fns returning `Result` or `Option` in each
+/// file of the report, those inside closures and async blocks are skipped
+/// since `?` would return from them instead, and those inside macro
+/// invocations aren't seen
+pub fn census(root: impl AsRef<Path>, report: &Report) -> BTreeMap<String, Vec<Candidate>> {
+    report
+        .files
+        .keys()
+        .filter_map(|filename| {
+            let content = fs::read_to_string(root.as_ref().join(filename)).ok()?;
+            let syntax = syn::parse_file(&content).ok()?;
+            let mut collector = CandidateCollector::default();
+            collector.visit_file(&syntax);
+            (!collector.candidates.is_empty()).then(|| (filename.clone(), collector.candidates))
+        })
+        .collect()
+}
+
+/// Replace each `.unwrap()` which can be applied with `?`, returning how many
+/// were rewritten
+///
+/// The receiver's type isn't known, so the result can fail to compile where
+/// an `Option` is unwrapped in a fn returning `Result` or an error has no
+/// `From` conversion into the fn's.
+pub fn apply(
+    root: impl AsRef<Path>,
+    census: &BTreeMap<String, Vec<Candidate>>,
+) -> io::Result<usize> {
+    let mut applied = 0;
+    for (filename, candidates) in census {
+        let mut rewrites: Vec<(usize, usize, usize)> = candidates
+            .iter()
+            .filter_map(|candidate| candidate.rewrite)
+            .collect();
+        if rewrites.is_empty() {
+            continue;
+        }
+        let path = root.as_ref().join(filename);
+        let content = fs::read_to_string(&path)?;
+        let mut lines: Vec<String> = content.split('\n').map(str::to_string).collect();
+        // from the right, so the columns of those left of a rewrite still hold
+        rewrites.sort_unstable_by(|a, b| b.cmp(a));
+        for (line, start, end) in rewrites {
+            let Some(text) = lines.get_mut(line - 1) else {
+                continue;
+            };
+            // columns count chars rather than bytes
+            let byte = |column: usize| {
+                text.char_indices()
+                    .nth(column)
+                    .map_or(text.len(), |(byte, _)| byte)
+            };
+            let range = byte(start)..byte(end);
+            text.replace_range(range, "?");
+            applied += 1;
+        }
+        fs::write(&path, lines.join("\n"))?;
+    }
+    Ok(applied)
+}
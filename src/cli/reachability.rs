@@ -0,0 +1,208 @@
+use std::{
+    collections::{
+        BTreeMap,
+        BTreeSet,
+        VecDeque,
+    },
+    fs,
+    path::Path,
+};
+
+use syn::{
+    Block,
+    ExprCall,
+    ExprMethodCall,
+    ExprUnsafe,
+    ImplItemFn,
+    ItemFn,
+    Signature,
+    Visibility,
+    visit::Visit,
+};
+
+/// A function definition in the syntactic call graph
+#[derive(Clone, Debug)]
+struct FnNode {
+    name: String,
+    filename: String,
+    line_number: usize,
+    is_pub: bool,
+    is_unsafe: bool,
+    has_unsafe_block: bool,
+    callees: BTreeSet<String>,
+}
+
+/// A public safe function which transitively reaches unsafe code
+#[derive(Clone, Debug)]
+pub struct UnsafeEntryPoint {
+    pub fn_name: String,
+    pub filename: String,
+    pub line_number: usize,
+    /// number of calls between the entry point and the unsafe code, 0 if it
+    /// contains an unsafe block itself
+    pub depth: usize,
+    /// the call chain from the entry point to the function containing unsafe code
+    pub path: Vec<String>,
+}
+
+struct CallGraphBuilder<'a> {
+    filename: &'a str,
+    nodes: &'a mut Vec<FnNode>,
+    /// indices into `nodes` of the functions currently being visited
+    stack: Vec<usize>,
+}
+
+impl CallGraphBuilder<'_> {
+    fn visit_fn(&mut self, vis: &Visibility, sig: &Signature, block: &Block) {
+        self.nodes.push(FnNode {
+            name: sig.ident.to_string(),
+            filename: self.filename.to_string(),
+            line_number: sig.ident.span().start().line,
+            is_pub: matches!(vis, Visibility::Public(_)),
+            is_unsafe: sig.unsafety.is_some(),
+            has_unsafe_block: false,
+            callees: BTreeSet::new(),
+        });
+        self.stack.push(self.nodes.len() - 1);
+        self.visit_block(block);
+        self.stack.pop();
+    }
+
+    fn current(&mut self) -> Option<&mut FnNode> {
+        let index = *self.stack.last()?;
+        self.nodes.get_mut(index)
+    }
+}
+
+impl<'ast> Visit<'ast> for CallGraphBuilder<'_> {
+    fn visit_item_fn(&mut self, i: &'ast ItemFn) {
+        self.visit_fn(&i.vis, &i.sig, &i.block);
+    }
+
+    fn visit_impl_item_fn(&mut self, i: &'ast ImplItemFn) {
+        self.visit_fn(&i.vis, &i.sig, &i.block);
+    }
+
+    fn visit_expr_call(&mut self, i: &'ast ExprCall) {
+        if let syn::Expr::Path(expr_path) = &*i.func
+            && let Some(segment) = expr_path.path.segments.last()
+        {
+            let callee = segment.ident.to_string();
+            if let Some(node) = self.current() {
+                node.callees.insert(callee);
+            }
+        }
+        syn::visit::visit_expr_call(self, i);
+    }
+
+    fn visit_expr_method_call(&mut self, i: &'ast ExprMethodCall) {
+        let callee = i.method.to_string();
+        if let Some(node) = self.current() {
+            node.callees.insert(callee);
+        }
+        syn::visit::visit_expr_method_call(self, i);
+    }
+
+    fn visit_expr_unsafe(&mut self, i: &'ast ExprUnsafe) {
+        if let Some(node) = self.current() {
+            node.has_unsafe_block = true;
+        }
+        syn::visit::visit_expr_unsafe(self, i);
+    }
+}
+
+fn analyze_file(path: &Path, filename: &str, nodes: &mut Vec<FnNode>) -> Option<()> {
+    let content = fs::read_to_string(path).ok()?;
+    let syntax = syn::parse_file(&content).ok()?;
+
+    let mut visitor = CallGraphBuilder {
+        filename,
+        nodes,
+        stack: Vec::new(),
+    };
+    visitor.visit_file(&syntax);
+    Some(())
+}
+
+/// Find the public safe functions which transitively reach unsafe code
+///
+/// The call graph is purely syntactic: calls are resolved by the last path
+/// segment or method name only, so functions sharing a name are conflated and
+/// calls through traits, closures, or function pointers are not followed.
+pub fn find_unsafe_entry_points(root: impl AsRef<Path>) -> Vec<UnsafeEntryPoint> {
+    let root = root.as_ref();
+    let mut nodes = Vec::new();
+
+    for entry in super::rust_files(root) {
+        let path = entry.path();
+        let filename = super::normalize_path(
+            &path
+                .strip_prefix(root)
+                .unwrap_or(path)
+                .display()
+                .to_string(),
+        );
+        analyze_file(path, &filename, &mut nodes);
+    }
+
+    let mut by_name: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    for (index, node) in nodes.iter().enumerate() {
+        by_name.entry(&node.name).or_default().push(index);
+    }
+
+    let mut entry_points: Vec<UnsafeEntryPoint> = nodes
+        .iter()
+        .enumerate()
+        .filter(|(_, node)| node.is_pub && !node.is_unsafe)
+        .filter_map(|(start, node)| {
+            let path = shortest_path_to_unsafe(&nodes, &by_name, start)?;
+            Some(UnsafeEntryPoint {
+                fn_name: node.name.clone(),
+                filename: node.filename.clone(),
+                line_number: node.line_number,
+                depth: path.len() - 1,
+                path: path.into_iter().map(|i| nodes[i].name.clone()).collect(),
+            })
+        })
+        .collect();
+
+    entry_points.sort_by(|a, b| (&a.filename, a.line_number).cmp(&(&b.filename, b.line_number)));
+    entry_points
+}
+
+/// Breadth first search from `start` to the nearest function which is unsafe
+/// or contains an unsafe block, returning the node indices along the way
+fn shortest_path_to_unsafe(
+    nodes: &[FnNode],
+    by_name: &BTreeMap<&str, Vec<usize>>,
+    start: usize,
+) -> Option<Vec<usize>> {
+    let mut parents: BTreeMap<usize, usize> = BTreeMap::new();
+    let mut seen = BTreeSet::from([start]);
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(index) = queue.pop_front() {
+        let node = &nodes[index];
+        if node.is_unsafe || node.has_unsafe_block {
+            let mut path = vec![index];
+            let mut cursor = index;
+            while let Some(&parent) = parents.get(&cursor) {
+                path.push(parent);
+                cursor = parent;
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        for callee in &node.callees {
+            for &next in by_name.get(callee.as_str()).into_iter().flatten() {
+                if seen.insert(next) {
+                    parents.insert(next, index);
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+
+    None
+}
@@ -0,0 +1,218 @@
+use syn::{
+    Block,
+    Expr,
+    ExprMethodCall,
+    ExprPath,
+    ExprUnary,
+    FnArg,
+    ImplItemFn,
+    ItemFn,
+    Pat,
+    Signature,
+    Type,
+    UnOp,
+    spanned::Spanned,
+    visit::Visit,
+};
+
+use super::{
+    Confidence,
+    Suggestion,
+};
+
+#[derive(Clone, Default, Debug)]
+pub struct FileStats {
+    pub filename: String,
+    pub stats: CodeStats,
+}
+
+#[derive(Clone, Debug)]
+pub struct RefCandidate {
+    pub fn_name: String,
+    pub line_number: usize,
+    pub param: String,
+    /// the parameter's raw pointer type, like `*mut Foo`
+    pub pointer: String,
+    /// the reference it could be instead, like `&mut Foo` or `Option<&Foo>`
+    pub reference: String,
+    pub confidence: Confidence,
+}
+
+impl Suggestion for RefCandidate {
+    fn subject(&self) -> String {
+        self.fn_name.clone()
+    }
+
+    fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    fn confidence(&self) -> Confidence {
+        self.confidence
+    }
+
+    fn detail(&self) -> Option<String> {
+        Some(format!(
+            "`{}: {}` -> `{}: {}`",
+            self.param, self.pointer, self.param, self.reference
+        ))
+    }
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct CodeStats {
+    pub candidates: Vec<RefCandidate>,
+}
+
+pub struct CodeAnalyzer<'a> {
+    stats: &'a mut CodeStats,
+}
+
+/// How a fn body uses a pointer parameter
+struct PointerUses<'a> {
+    name: &'a str,
+    /// `*p`, `p.read()` and `p.write(..)`
+    derefs: usize,
+    /// `p.is_null()`, which makes it an `Option` of a reference
+    null_checks: usize,
+    /// anything else, like offsetting, storing or passing it on
+    other: usize,
+}
+
+impl PointerUses<'_> {
+    fn is_pointer(&self, expr: &Expr) -> bool {
+        matches!(expr, Expr::Path(path) if path.path.is_ident(self.name))
+    }
+}
+
+impl<'ast> Visit<'ast> for PointerUses<'_> {
+    fn visit_expr_unary(&mut self, i: &'ast ExprUnary) {
+        if matches!(i.op, UnOp::Deref(_)) && self.is_pointer(&i.expr) {
+            self.derefs += 1;
+        } else {
+            syn::visit::visit_expr_unary(self, i);
+        }
+    }
+
+    fn visit_expr_method_call(&mut self, i: &'ast ExprMethodCall) {
+        if !self.is_pointer(&i.receiver) {
+            syn::visit::visit_expr_method_call(self, i);
+            return;
+        }
+        match i.method.to_string().as_str() {
+            "is_null" => self.null_checks += 1,
+            "read" | "read_unaligned" | "read_volatile" | "write" | "write_unaligned"
+            | "write_volatile" => self.derefs += 1,
+            _ => self.other += 1,
+        }
+        for arg in &i.args {
+            self.visit_expr(arg);
+        }
+    }
+
+    fn visit_expr_path(&mut self, i: &'ast ExprPath) {
+        if i.path.is_ident(self.name) {
+            self.other += 1;
+        }
+    }
+}
+
+impl CodeAnalyzer<'_> {
+    fn check_fn(&mut self, sig: &Signature, block: &Block) {
+        if sig.unsafety.is_none() {
+            return;
+        }
+        for input in &sig.inputs {
+            let FnArg::Typed(arg) = input else {
+                continue;
+            };
+            let (Pat::Ident(pat), Type::Ptr(ptr)) = (&*arg.pat, &*arg.ty) else {
+                continue;
+            };
+            let name = pat.ident.to_string();
+            let mut uses = PointerUses {
+                name: &name,
+                derefs: 0,
+                null_checks: 0,
+                other: 0,
+            };
+            uses.visit_block(block);
+            if uses.derefs == 0 || uses.other > 0 {
+                continue;
+            }
+            let Some(elem) = ptr.elem.span().source_text() else {
+                continue;
+            };
+            let pointer = arg.ty.span().source_text().unwrap_or_default();
+            let mut reference = if ptr.mutability.is_some() {
+                format!("&mut {elem}")
+            } else {
+                format!("&{elem}")
+            };
+            // C callers can pass anything, and nothing checks the lifetime
+            let confidence = if sig.abi.is_some() {
+                Confidence::Low
+            } else if uses.null_checks > 0 {
+                Confidence::Medium
+            } else {
+                Confidence::High
+            };
+            if uses.null_checks > 0 {
+                reference = format!("Option<{reference}>");
+            }
+            self.stats.candidates.push(RefCandidate {
+                fn_name: sig.ident.to_string(),
+                line_number: pat.ident.span().start().line,
+                param: name,
+                pointer,
+                reference,
+                confidence,
+            });
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for CodeAnalyzer<'_> {
+    fn visit_item_fn(&mut self, i: &'ast ItemFn) {
+        self.check_fn(&i.sig, &i.block);
+        syn::visit::visit_item_fn(self, i);
+    }
+
+    fn visit_impl_item_fn(&mut self, i: &'ast ImplItemFn) {
+        self.check_fn(&i.sig, &i.block);
+        syn::visit::visit_impl_item_fn(self, i);
+    }
+}
+
+fn analyze_file(syntax: &syn::File) -> CodeStats {
+    let mut stats = CodeStats::default();
+    let mut visitor = CodeAnalyzer { stats: &mut stats };
+    visitor.visit_file(syntax);
+
+    stats
+}
+
+/// Find raw pointer parameters of unsafe functions which could be references
+/// The heuristic is a `*const T` or `*mut T` parameter which the body only
+/// dereferences, and maybe checks for null, never offsetting it, storing it
+/// or passing it on, it's medium confidence when it's checked for null and low
+/// in `extern` functions
+pub fn find_candidates(
+    files: &[(String, syn::File)],
+    min_confidence: Confidence,
+) -> Vec<FileStats> {
+    files
+        .iter()
+        .map(|(filename, syntax)| {
+            let mut stats = analyze_file(syntax);
+            stats
+                .candidates
+                .retain(|candidate| candidate.confidence >= min_confidence);
+            FileStats {
+                filename: filename.clone(),
+                stats,
+            }
+        })
+        .filter(|report| !report.stats.candidates.is_empty())
+        .collect()
+}
@@ -0,0 +1,103 @@
+use std::{
+    cmp::Ordering,
+    fs,
+    path::Path,
+};
+
+use super::{
+    Report,
+    SiteKind,
+    config,
+    todo,
+};
+
+/// A file's findings and the score they add up to, see [`config::Risk`]
+#[derive(Clone, Debug)]
+pub struct FileRisk {
+    pub filename: String,
+    pub score: f64,
+    pub unsafe_statements: isize,
+    pub static_mut_items: isize,
+    pub unchecked_calls: usize,
+    pub missing_safety_comments: usize,
+    /// percentage of lines covered, with `--coverage`
+    pub coverage: Option<f64>,
+}
+
+/// Count calls of fns named `*_unchecked`, like `get_unchecked(` or
+/// `from_utf8_unchecked::<T>(`, skipping comments and their definitions
+///
+/// This is a text scan, so calls in string literals or through macros are
+/// counted too.
+fn count_unchecked_calls(source: &str) -> usize {
+    source
+        .lines()
+        .map(|line| line.split("//").next().unwrap_or_default())
+        .map(|code| {
+            code.match_indices("_unchecked")
+                .filter(|(index, _)| {
+                    let rest = &code[index + "_unchecked".len()..];
+                    let name_start = code[..*index]
+                        .rfind(|c: char| !(c.is_alphanumeric() || c == '_'))
+                        .map_or(0, |i| i + 1);
+                    (rest.starts_with('(') || rest.starts_with("::<"))
+                        && !code[..name_start].trim_end().ends_with("fn")
+                })
+                .count()
+        })
+        .sum()
+}
+
+/// Every file with unsafe code ranked by its risk score, highest first
+pub fn rank(crate_root: impl AsRef<Path>, report: &Report) -> Vec<FileRisk> {
+    let weights = config::risk();
+    let mut ranked: Vec<FileRisk> = report
+        .files
+        .iter()
+        .filter_map(|(filename, stats)| {
+            let source = fs::read_to_string(crate_root.as_ref().join(filename)).unwrap_or_default();
+            let lines: Vec<&str> = source.lines().collect();
+            let unchecked_calls = count_unchecked_calls(&source);
+            if stats.unsafe_statements == 0 && stats.static_mut_items == 0 && unchecked_calls == 0 {
+                return None;
+            }
+
+            let missing_safety_comments = report
+                .sites
+                .get(filename)
+                .into_iter()
+                .flatten()
+                .filter(|site| {
+                    site.kind == SiteKind::UnsafeBlock
+                        && !todo::has_safety_comment(&lines, site.line)
+                })
+                .count();
+            let coverage = report
+                .coverage
+                .get(filename)
+                .and_then(|coverage| coverage.percent());
+
+            let score = weights.unsafe_statements * stats.unsafe_statements as f64
+                + weights.static_mut_items * stats.static_mut_items as f64
+                + weights.unchecked_calls * unchecked_calls as f64
+                + weights.missing_safety_comments * missing_safety_comments as f64
+                + weights.low_coverage * coverage.map_or(0.0, |percent| 1.0 - percent / 100.0);
+            Some(FileRisk {
+                filename: filename.clone(),
+                score,
+                unsafe_statements: stats.unsafe_statements,
+                static_mut_items: stats.static_mut_items,
+                unchecked_calls,
+                missing_safety_comments,
+                coverage,
+            })
+        })
+        .collect();
+    ranked.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| a.filename.cmp(&b.filename))
+    });
+    ranked
+}
@@ -0,0 +1,218 @@
+use syn::{
+    Expr,
+    ExprCall,
+    ExprMethodCall,
+    ExprUnary,
+    ExprUnsafe,
+    ItemFn,
+    Macro,
+    UnOp,
+    visit::Visit,
+};
+
+use super::{
+    Confidence,
+    Suggestion,
+};
+use crate::analysis::is_exported;
+
+#[derive(Clone, Default, Debug)]
+pub struct FileStats {
+    pub filename: String,
+    pub stats: CodeStats,
+}
+
+#[derive(Clone, Debug)]
+pub struct Candidate {
+    pub fn_name: String,
+    pub line_number: usize,
+    pub confidence: Confidence,
+}
+
+impl Suggestion for Candidate {
+    fn subject(&self) -> String {
+        self.fn_name.clone()
+    }
+
+    fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    fn confidence(&self) -> Confidence {
+        self.confidence
+    }
+
+    fn detail(&self) -> Option<String> {
+        None
+    }
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct CodeStats {
+    pub candidates: Vec<Candidate>,
+}
+
+pub struct CodeAnalyzer<'a> {
+    stats: &'a mut CodeStats,
+}
+
+/// Check if a type is a pointer type (raw pointer or NonNull<T>)
+fn has_pointer_type(ty: &syn::Type) -> bool {
+    match ty {
+        // Raw pointers: *const T, *mut T
+        syn::Type::Ptr(_) => true,
+        // Path types like NonNull<T>
+        syn::Type::Path(type_path) => {
+            // Check if this is NonNull<T>
+            if let Some(segment) = type_path.path.segments.last() {
+                segment.ident == "NonNull"
+            } else {
+                false
+            }
+        }
+        _ => false,
+    }
+}
+
+/// Check if function has a Safety comment in its attributes
+fn has_safety_comment(attrs: &[syn::Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        // Check for doc comments (outer attributes that start with #[doc = "..."])
+        if attr.path().is_ident("doc")
+            && let syn::Meta::NameValue(meta_name_value) = &attr.meta
+            && let syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(lit_str),
+                ..
+            }) = &meta_name_value.value
+        {
+            let comment = lit_str.value();
+            // Check if comment contains "#Safety" (case insensitive)
+            return comment.to_lowercase().contains("# safety");
+        }
+        false
+    })
+}
+
+/// Counts what in a fn body looks like it needs `unsafe`: dereferences, unsafe
+/// blocks, inline assembly and calls into `libc`, `ptr` or `*_unchecked` fns
+#[derive(Default)]
+struct UnsafeOps {
+    count: usize,
+}
+
+fn is_unsafe_looking(name: &str) -> bool {
+    name.ends_with("_unchecked") || name.starts_with("from_raw") || name == "transmute"
+}
+
+impl<'ast> Visit<'ast> for UnsafeOps {
+    fn visit_expr_unary(&mut self, i: &'ast ExprUnary) {
+        if matches!(i.op, UnOp::Deref(_)) {
+            self.count += 1;
+        }
+        syn::visit::visit_expr_unary(self, i);
+    }
+
+    fn visit_expr_unsafe(&mut self, i: &'ast ExprUnsafe) {
+        self.count += 1;
+        syn::visit::visit_expr_unsafe(self, i);
+    }
+
+    fn visit_expr_call(&mut self, i: &'ast ExprCall) {
+        if let Expr::Path(path) = &*i.func
+            && path.path.segments.iter().any(|segment| {
+                segment.ident == "libc"
+                    || segment.ident == "ptr"
+                    || is_unsafe_looking(&segment.ident.to_string())
+            })
+        {
+            self.count += 1;
+        }
+        syn::visit::visit_expr_call(self, i);
+    }
+
+    fn visit_expr_method_call(&mut self, i: &'ast ExprMethodCall) {
+        if is_unsafe_looking(&i.method.to_string()) {
+            self.count += 1;
+        }
+        syn::visit::visit_expr_method_call(self, i);
+    }
+
+    fn visit_macro(&mut self, i: &'ast Macro) {
+        if i.path.is_ident("asm") {
+            self.count += 1;
+        }
+        syn::visit::visit_macro(self, i);
+    }
+}
+
+impl<'a, 'ast> Visit<'ast> for CodeAnalyzer<'a> {
+    fn visit_item_fn(&mut self, i: &'ast ItemFn) {
+        use syn::spanned::Spanned;
+
+        if i.sig.unsafety.is_some() {
+            // if function is unsafe and it has no raw pointer arguments add it to the list
+            let has_raw_pointer = i.sig.inputs.iter().any(|arg| match arg {
+                syn::FnArg::Typed(pat_type) => has_pointer_type(&pat_type.ty),
+                _ => false,
+            });
+
+            // Exclude functions with Safety comments
+            let has_safety_doc = has_safety_comment(&i.attrs);
+
+            if !has_raw_pointer && !has_safety_doc {
+                let mut ops = UnsafeOps::default();
+                ops.visit_block(&i.block);
+                // C callers can't tell a safe fn from an unsafe one anyway
+                let confidence = if i.sig.abi.is_some() || is_exported(&i.attrs) {
+                    Confidence::Low
+                } else if ops.count > 0 {
+                    Confidence::Medium
+                } else {
+                    Confidence::High
+                };
+                let candidate = Candidate {
+                    fn_name: i.sig.ident.to_string(),
+                    line_number: i.span().start().line,
+                    confidence,
+                };
+                self.stats.candidates.push(candidate)
+            }
+        }
+        syn::visit::visit_item_fn(self, i);
+    }
+}
+
+fn analyze_file(syntax: &syn::File) -> CodeStats {
+    let mut stats = CodeStats::default();
+    let mut visitor = CodeAnalyzer { stats: &mut stats };
+    visitor.visit_file(syntax);
+
+    stats
+}
+
+/// find good candidates for functions to convert to being safe
+/// this is very simplistic, the heuristic is if the function
+/// has no raw pointers as parameters, it may be a good candidate
+///
+/// there may be other reasons why one of these functions can't be converted,
+/// it's high confidence when nothing in the body looks like it needs `unsafe`
+/// and low for exported and `extern` functions
+pub fn find_candidates(
+    files: &[(String, syn::File)],
+    min_confidence: Confidence,
+) -> Vec<FileStats> {
+    files
+        .iter()
+        .map(|(filename, syntax)| {
+            let mut stats = analyze_file(syntax);
+            stats
+                .candidates
+                .retain(|candidate| candidate.confidence >= min_confidence);
+            FileStats {
+                filename: filename.clone(),
+                stats,
+            }
+        })
+        .filter(|report| !report.stats.candidates.is_empty())
+        .collect()
+}
@@ -0,0 +1,98 @@
+use super::{
+    CodeStats,
+    Report,
+    config,
+};
+
+/// How bad a run is, from the worst of its [`Finding`]s
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Info,
+    Warn,
+    Fail,
+}
+
+impl Severity {
+    pub fn label(self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Warn => "warn",
+            Severity::Fail => "fail",
+        }
+    }
+
+    /// The PR comment verdict's prefix, without emoji for `ascii` markdown
+    pub fn badge(self) -> &'static str {
+        match (self, config::markdown().ascii) {
+            (Severity::Info, false) => "✅",
+            (Severity::Warn, false) => "⚠️ **Warning:**",
+            (Severity::Fail, false) => "❌ **Failing:**",
+            (Severity::Info, true) => "[OK]",
+            (Severity::Warn, true) => "[WARNING]",
+            (Severity::Fail, true) => "[FAILING]",
+        }
+    }
+}
+
+pub struct Finding {
+    pub severity: Severity,
+    pub reason: String,
+}
+
+/// Each total which regressed against the first baseline past its warn tier,
+/// see [`config::tiers`], and each file at a configured danger threshold,
+/// which always fails
+pub fn findings(report: &Report, baselines: &[(String, Report)]) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    if let Some((label, baseline)) = baselines.first() {
+        let diff = report.diff(baseline);
+        for metric in CodeStats::csv_headers().into_iter().skip(1) {
+            if diff.unknown_metrics.contains(&metric) {
+                continue;
+            }
+            let before = diff.before_total.metric(&metric).unwrap_or_default();
+            let after = diff.after_total.metric(&metric).unwrap_or_default();
+            if !config::decrease_is(&metric).is_regression(before, after) {
+                continue;
+            }
+            let regression = (after - before).abs();
+            let tiers = config::tiers(&metric);
+            let reached = |tier: Option<isize>| tier.is_some_and(|tier| regression >= tier);
+            let severity = if reached(tiers.fail) {
+                Severity::Fail
+            } else if reached(tiers.warn) {
+                Severity::Warn
+            } else {
+                continue;
+            };
+            findings.push(Finding {
+                severity,
+                reason: format!("{metric} {before} -> {after} vs {label}"),
+            });
+        }
+    }
+
+    for (filename, stats) in &report.files {
+        for (metric, threshold) in config::configured_thresholds() {
+            if stats
+                .threshold_value(metric)
+                .is_some_and(|value| threshold.level(value) == config::Level::Danger)
+            {
+                findings.push(Finding {
+                    severity: Severity::Fail,
+                    reason: format!("{filename} is at the {metric} danger threshold"),
+                });
+            }
+        }
+    }
+    findings
+}
+
+/// The worst severity among the findings, `Info` without any
+pub fn assess(findings: &[Finding]) -> Severity {
+    findings
+        .iter()
+        .map(|finding| finding.severity)
+        .max()
+        .unwrap_or(Severity::Info)
+}
@@ -0,0 +1,234 @@
+use serde::Serialize;
+
+use super::{
+    Args,
+    Report,
+    Repr,
+    SiteKind,
+    git,
+    precommit,
+};
+
+/// SonarQube's generic issue import format (10.3 and later), for
+/// `sonar.externalIssuesReportPaths`
+#[derive(Serialize)]
+struct GenericIssues {
+    rules: Vec<Rule>,
+    issues: Vec<Issue>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Rule {
+    id: &'static str,
+    name: &'static str,
+    description: &'static str,
+    engine_id: &'static str,
+    clean_code_attribute: &'static str,
+    /// `type` and `severity` are only read by versions before impacts
+    #[serde(rename = "type")]
+    kind: &'static str,
+    severity: &'static str,
+    impacts: [Impact; 1],
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Impact {
+    software_quality: &'static str,
+    severity: &'static str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Issue {
+    rule_id: &'static str,
+    primary_location: Location,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    message: String,
+    /// relative to the repository root rather than the crate root, which is
+    /// usually the project's base directory
+    file_path: String,
+    text_range: TextRange,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TextRange {
+    start_line: usize,
+    end_line: usize,
+}
+
+/// The rule a site is reported under
+fn rule_id(kind: SiteKind) -> &'static str {
+    match kind {
+        SiteKind::Allocation | SiteKind::GlobalAllocator => "manual-allocation",
+        SiteKind::DeprecatedUse => "deprecated-use",
+        SiteKind::ExternClosure | SiteKind::ExternFn => "extern-callback",
+        SiteKind::Repr(_) => "repr-type",
+        SiteKind::FfiString => "ffi-string",
+        SiteKind::LeakProne => "leak-prone",
+        SiteKind::ProcessExit => "process-exit",
+        SiteKind::Recursive { .. } => "recursive-fn",
+        SiteKind::ThreadUnsafe => "thread-unsafe",
+        SiteKind::TrivialExpect => "trivial-expect",
+        SiteKind::StaticMut => "static-mut",
+        SiteKind::UnsafeBlock => "unsafe-stmt",
+        SiteKind::UnsafeFn => "unsafe-fn",
+        SiteKind::Unwrap => "unwrap",
+    }
+}
+
+fn rules() -> Vec<Rule> {
+    [
+        (
+            SiteKind::UnsafeFn,
+            "Unsafe function",
+            "Callers of an unsafe fn must uphold invariants the compiler can't check.",
+            ("VULNERABILITY", "MAJOR"),
+            ("SECURITY", "MEDIUM"),
+        ),
+        (
+            SiteKind::UnsafeBlock,
+            "Unsafe block",
+            "The statements in an unsafe block aren't checked for memory safety by the compiler.",
+            ("VULNERABILITY", "MAJOR"),
+            ("SECURITY", "MEDIUM"),
+        ),
+        (
+            SiteKind::StaticMut,
+            "Static mut item",
+            "Every access to a static mut is unsafe and a potential data race.",
+            ("VULNERABILITY", "CRITICAL"),
+            ("SECURITY", "HIGH"),
+        ),
+        (
+            SiteKind::Unwrap,
+            "Unwrap call",
+            "unwrap panics on None or Err instead of handling it.",
+            ("CODE_SMELL", "MINOR"),
+            ("RELIABILITY", "LOW"),
+        ),
+        (
+            SiteKind::FfiString,
+            "FFI string conversion",
+            "The C string's pointer must not outlive it, and the pointer it's read from must be valid and NUL-terminated.",
+            ("CODE_SMELL", "INFO"),
+            ("RELIABILITY", "LOW"),
+        ),
+        (
+            SiteKind::ExternFn,
+            "Extern callback",
+            "A panic unwinding out of an extern fn called from C aborts, or is undefined behavior with older compilers.",
+            ("CODE_SMELL", "INFO"),
+            ("RELIABILITY", "LOW"),
+        ),
+        (
+            SiteKind::Repr(Repr::C),
+            "Repr type",
+            "The layout of a repr(C), repr(transparent) or repr(packed) type is part of the ABI, C code relies on it not changing.",
+            ("CODE_SMELL", "INFO"),
+            ("MAINTAINABILITY", "LOW"),
+        ),
+        (
+            SiteKind::Allocation,
+            "Manual allocation",
+            "Memory allocated and freed by hand through std::alloc must be freed exactly once, with the layout it was allocated with.",
+            ("CODE_SMELL", "MAJOR"),
+            ("RELIABILITY", "MEDIUM"),
+        ),
+        (
+            SiteKind::LeakProne,
+            "Leak-prone call",
+            "mem::forget, Box::leak, ManuallyDrop::new and into_raw skip the destructor, whatever is leaked must be freed by hand.",
+            ("CODE_SMELL", "MAJOR"),
+            ("RELIABILITY", "MEDIUM"),
+        ),
+        (
+            SiteKind::ProcessExit,
+            "Process exit",
+            "process::exit and abort end the process without running destructors, a library should return an error to its caller instead.",
+            ("CODE_SMELL", "MAJOR"),
+            ("RELIABILITY", "MEDIUM"),
+        ),
+        (
+            SiteKind::ThreadUnsafe,
+            "Thread-unsafe call",
+            "env::set_var, env::remove_var and some libc fns race with other threads, call them before any threads are spawned.",
+            ("BUG", "MAJOR"),
+            ("RELIABILITY", "HIGH"),
+        ),
+        (
+            SiteKind::Recursive { exposed: false },
+            "Recursive fn",
+            "A fn which calls itself can overflow the stack on deep enough input, consider an explicit stack or a depth limit.",
+            ("CODE_SMELL", "MINOR"),
+            ("RELIABILITY", "MEDIUM"),
+        ),
+        (
+            SiteKind::DeprecatedUse,
+            "Use of a deprecated item",
+            "The crate marked this item #[deprecated], move the use to its replacement.",
+            ("CODE_SMELL", "MINOR"),
+            ("MAINTAINABILITY", "LOW"),
+        ),
+        (
+            SiteKind::TrivialExpect,
+            "Expect with a trivial message",
+            "An empty or one-word expect message says no more than unwrap, describe what was expected instead.",
+            ("CODE_SMELL", "MINOR"),
+            ("RELIABILITY", "LOW"),
+        ),
+    ]
+    .into_iter()
+    .map(
+        |(kind, name, description, (rule_type, severity), (software_quality, impact))| Rule {
+            id: rule_id(kind),
+            name,
+            description,
+            engine_id: "crate-report",
+            clean_code_attribute: if kind == SiteKind::Unwrap {
+                "COMPLETE"
+            } else {
+                "TRUSTWORTHY"
+            },
+            kind: rule_type,
+            severity,
+            impacts: [Impact {
+                software_quality,
+                severity: impact,
+            }],
+        },
+    )
+    .collect()
+}
+
+pub fn format_sonar_report(report: &Report, args: &Args) -> String {
+    let prefix = git::repo_prefix(&args.crate_root);
+    let issues = report
+        .sites
+        .iter()
+        .flat_map(|(filename, sites)| sites.iter().map(move |site| (filename, site)))
+        .map(|(filename, site)| Issue {
+            rule_id: rule_id(site.kind),
+            primary_location: Location {
+                message: precommit::describe(site),
+                file_path: format!("{prefix}{filename}"),
+                text_range: TextRange {
+                    start_line: site.line,
+                    end_line: site.end_line,
+                },
+            },
+        })
+        .collect();
+
+    let issues = GenericIssues {
+        rules: rules(),
+        issues,
+    };
+    serde_json::to_string_pretty(&issues).unwrap() + "\n"
+}
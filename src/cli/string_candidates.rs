@@ -0,0 +1,180 @@
+use std::collections::BTreeMap;
+
+use syn::{
+    Expr,
+    ExprCall,
+    ImplItemFn,
+    ItemFn,
+    Signature,
+    spanned::Spanned,
+    visit::Visit,
+};
+
+use super::{
+    Confidence,
+    Suggestion,
+};
+
+/// The libc functions which copy into, append to or format a C string buffer
+const STRING_BUILDERS: &[&str] = &[
+    "snprintf",
+    "sprintf",
+    "stpcpy",
+    "strcat",
+    "strcpy",
+    "strncat",
+    "strncpy",
+    "vsnprintf",
+    "vsprintf",
+];
+
+#[derive(Clone, Default, Debug)]
+pub struct FileStats {
+    pub filename: String,
+    pub stats: CodeStats,
+}
+
+#[derive(Clone, Debug)]
+pub struct StringCandidate {
+    pub fn_name: String,
+    pub line_number: usize,
+    /// the lines of each string building call, by function
+    pub calls: BTreeMap<String, Vec<usize>>,
+    pub confidence: Confidence,
+}
+
+impl Suggestion for StringCandidate {
+    fn subject(&self) -> String {
+        self.fn_name.clone()
+    }
+
+    fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    fn confidence(&self) -> Confidence {
+        self.confidence
+    }
+
+    fn detail(&self) -> Option<String> {
+        let calls: Vec<String> = self
+            .calls
+            .iter()
+            .map(|(function, lines)| {
+                let lines: Vec<String> = lines.iter().map(usize::to_string).collect();
+                format!("`{function}` ({})", lines.join(", "))
+            })
+            .collect();
+        Some(calls.join(", "))
+    }
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct CodeStats {
+    pub candidates: Vec<StringCandidate>,
+}
+
+pub struct CodeAnalyzer<'a> {
+    stats: &'a mut CodeStats,
+    /// the calls of the innermost fn, nested fns are candidates of their own
+    calls: BTreeMap<String, Vec<usize>>,
+}
+
+impl CodeAnalyzer<'_> {
+    fn visit_fn_scope(&mut self, sig: &Signature, visit: impl FnOnce(&mut Self)) {
+        let outer = std::mem::take(&mut self.calls);
+        visit(self);
+        let calls = std::mem::replace(&mut self.calls, outer);
+        if !calls.is_empty() {
+            // format strings have to be translated to `format!`, and a
+            // `va_list` can't be at all
+            let confidence = if calls.keys().any(|name| name.starts_with('v')) {
+                Confidence::Low
+            } else if calls.keys().any(|name| name.ends_with("printf")) {
+                Confidence::Medium
+            } else {
+                Confidence::High
+            };
+            self.stats.candidates.push(StringCandidate {
+                fn_name: sig.ident.to_string(),
+                line_number: sig.ident.span().start().line,
+                calls,
+                confidence,
+            });
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for CodeAnalyzer<'_> {
+    fn visit_item_fn(&mut self, i: &'ast ItemFn) {
+        self.visit_fn_scope(&i.sig, |this| syn::visit::visit_item_fn(this, i));
+    }
+
+    fn visit_impl_item_fn(&mut self, i: &'ast ImplItemFn) {
+        self.visit_fn_scope(&i.sig, |this| syn::visit::visit_impl_item_fn(this, i));
+    }
+
+    fn visit_expr_call(&mut self, i: &'ast ExprCall) {
+        if let Expr::Path(path) = &*i.func {
+            let segments: Vec<String> = path
+                .path
+                .segments
+                .iter()
+                .map(|segment| segment.ident.to_string())
+                .collect();
+            let name = match segments.as_slice() {
+                [name] => Some(name),
+                [libc, name] if libc == "libc" => Some(name),
+                _ => None,
+            };
+            if let Some(name) = name
+                && STRING_BUILDERS.contains(&name.as_str())
+            {
+                self.calls
+                    .entry(name.clone())
+                    .or_default()
+                    .push(i.func.span().start().line);
+            }
+        }
+        syn::visit::visit_expr_call(self, i);
+    }
+}
+
+fn analyze_file(syntax: &syn::File) -> CodeStats {
+    let mut stats = CodeStats::default();
+    let mut visitor = CodeAnalyzer {
+        stats: &mut stats,
+        calls: BTreeMap::new(),
+    };
+    visitor.visit_file(syntax);
+    stats
+        .candidates
+        .sort_by_key(|candidate| candidate.line_number);
+
+    stats
+}
+
+/// Find functions building C strings by hand, which could build a `String`
+/// instead
+/// The heuristic is a function calling `strcpy`, `strcat`, `snprintf` or
+/// another libc function which copies into, appends to or formats a buffer,
+/// it's high confidence when the function only copies and appends
+pub fn find_candidates(
+    files: &[(String, syn::File)],
+    min_confidence: Confidence,
+) -> Vec<FileStats> {
+    files
+        .iter()
+        .map(|(filename, syntax)| {
+            let mut stats = analyze_file(syntax);
+            stats
+                .candidates
+                .retain(|candidate| candidate.confidence >= min_confidence);
+            FileStats {
+                filename: filename.clone(),
+                stats,
+            }
+        })
+        .filter(|report| !report.stats.candidates.is_empty())
+        .collect()
+}
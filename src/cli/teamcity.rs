@@ -0,0 +1,114 @@
+use super::{
+    Args,
+    CodeStats,
+    Diff,
+    Report,
+    config,
+    git,
+};
+
+/// Escape a service message attribute value
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '|' | '\'' | '[' | ']' => {
+                escaped.push('|');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("|n"),
+            '\r' => escaped.push_str("|r"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// A `##teamcity[...]` line
+fn message(name: &str, attributes: &[(&str, &str)]) -> String {
+    let attributes: String = attributes
+        .iter()
+        .map(|(key, value)| format!(" {key}='{}'", escape(value)))
+        .collect();
+    format!("##teamcity[{name}{attributes}]\n")
+}
+
+/// A `buildStatisticValue` per total, keyed `crate-report.<metric>` so TeamCity
+/// charts them across builds, and with a baseline an inspection per file and
+/// metric which regressed
+pub fn format_teamcity_messages(
+    report: &Report,
+    baselines: &[(String, Report)],
+    args: &Args,
+) -> String {
+    let metrics: Vec<String> = CodeStats::csv_headers().into_iter().skip(1).collect();
+    let mut out = String::new();
+    for metric in &metrics {
+        let value = report.total.metric(metric).unwrap_or_default();
+        out.push_str(&message(
+            "buildStatisticValue",
+            &[
+                ("key", &format!("crate-report.{metric}")),
+                ("value", &value.to_string()),
+            ],
+        ));
+    }
+
+    let Some((label, baseline)) = baselines.first() else {
+        return out;
+    };
+    let diff = report.diff(baseline);
+    // inspection files are relative to the checkout root
+    let prefix = git::repo_prefix(&args.crate_root);
+    let mut inspections = String::new();
+    let mut types = Vec::new();
+    for (filename, change) in &diff.changes {
+        let (before, after) = match change {
+            Diff::Added(after) => (CodeStats::default(), after),
+            Diff::Changed(change) => (change.before.clone(), &change.after),
+            Diff::Removed(_) => continue,
+        };
+        for metric in &metrics {
+            if diff.unknown_metrics.contains(metric) {
+                continue;
+            }
+            let before = before.metric(metric).unwrap_or_default();
+            let after = after.metric(metric).unwrap_or_default();
+            if !config::decrease_is(metric).is_regression(before, after) {
+                continue;
+            }
+            if !types.contains(metric) {
+                types.push(metric.clone());
+            }
+            inspections.push_str(&message(
+                "inspection",
+                &[
+                    ("typeId", &format!("crate-report.{metric}")),
+                    (
+                        "message",
+                        &format!("{metric} {before} -> {after} vs {label}"),
+                    ),
+                    ("file", &format!("{prefix}{filename}")),
+                    ("SEVERITY", "WARNING"),
+                ],
+            ));
+        }
+    }
+
+    // inspection types are declared before the inspections using them
+    for metric in types {
+        out.push_str(&message(
+            "inspectionType",
+            &[
+                ("id", &format!("crate-report.{metric}")),
+                ("name", &format!("{metric} regression")),
+                ("category", "crate-report"),
+                (
+                    "description",
+                    &format!("{metric} moved in the wrong direction compared to the baseline"),
+                ),
+            ],
+        ));
+    }
+    out + &inspections
+}
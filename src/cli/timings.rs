@@ -0,0 +1,85 @@
+use std::{
+    cmp,
+    path::Path,
+    sync::{
+        Mutex,
+        OnceLock,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+/// How many of the slowest files are listed
+const SLOWEST_FILES: usize = 20;
+
+#[derive(Default)]
+struct Timings {
+    phases: Vec<(&'static str, Duration)>,
+    files: Vec<FileTiming>,
+}
+
+struct FileTiming {
+    path: String,
+    parse: Duration,
+    visit: Duration,
+}
+
+/// Only set with `--timings`, so nothing is collected otherwise
+static TIMINGS: OnceLock<Mutex<Timings>> = OnceLock::new();
+
+pub fn enable() {
+    _ = TIMINGS.set(Mutex::default());
+}
+
+/// Run `f` and record its wall time as a phase of the run
+pub fn phase<T>(name: &'static str, f: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let result = f();
+    if let Some(timings) = TIMINGS.get() {
+        timings.lock().unwrap().phases.push((name, start.elapsed()));
+    }
+    result
+}
+
+pub fn record_file(path: &Path, parse: Duration, visit: Duration) {
+    if let Some(timings) = TIMINGS.get() {
+        timings.lock().unwrap().files.push(FileTiming {
+            path: path.display().to_string(),
+            parse,
+            visit,
+        });
+    }
+}
+
+/// Print the phases and the slowest files to stderr, so the report is unchanged
+pub fn print() {
+    let Some(timings) = TIMINGS.get() else {
+        return;
+    };
+    let mut timings = timings.lock().unwrap();
+
+    eprintln!("Timings:");
+    for (name, duration) in &timings.phases {
+        eprintln!("  {name:<10} {duration:>12.2?}");
+    }
+
+    let parse: Duration = timings.files.iter().map(|file| file.parse).sum();
+    let visit: Duration = timings.files.iter().map(|file| file.visit).sum();
+    eprintln!(
+        "  parse {parse:.2?} and visit {visit:.2?} summed over {} files (across threads)",
+        timings.files.len()
+    );
+
+    timings
+        .files
+        .sort_by_key(|file| cmp::Reverse(file.parse + file.visit));
+    eprintln!("Slowest files (parse / visit):");
+    for file in timings.files.iter().take(SLOWEST_FILES) {
+        eprintln!(
+            "  {:>12.2?} {:>12.2?}  {}",
+            file.parse, file.visit, file.path
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::Path,
+};
+
+use super::{
+    Report,
+    Site,
+    SiteKind,
+};
+
+/// How many lines above an unsafe block are searched for a `SAFETY:` comment
+const SAFETY_COMMENT_SEARCH_LINES: usize = 3;
+
+/// Check the lines just above (and on) an unsafe block for a `SAFETY:` comment
+pub fn has_safety_comment(lines: &[&str], line: usize) -> bool {
+    let end = line.min(lines.len());
+    let start = end.saturating_sub(SAFETY_COMMENT_SEARCH_LINES + 1);
+    lines[start..end]
+        .iter()
+        .any(|line| line.contains("//") && line.to_uppercase().contains("SAFETY:"))
+}
+
+/// Split a checklist line into whether it's checked and its key, the
+/// filename and description without the line number so the checked state
+/// survives code moving around within the file
+fn parse_item(line: &str) -> Option<(bool, (String, String))> {
+    let (checked, item) = if let Some(item) = line.strip_prefix("- [ ] ") {
+        (false, item)
+    } else if let Some(item) = line
+        .strip_prefix("- [x] ")
+        .or_else(|| line.strip_prefix("- [X] "))
+    {
+        (true, item)
+    } else {
+        return None;
+    };
+
+    let (location, description) = item.split_once(' ')?;
+    let (filename, _line) = location.rsplit_once(':')?;
+    Some((checked, (filename.to_string(), description.to_string())))
+}
+
+fn describe(site: &Site) -> Option<String> {
+    Some(match site.kind {
+        SiteKind::UnsafeFn => format!("unsafe fn `{}`", site.name),
+        SiteKind::StaticMut => format!("static mut `{}`", site.name),
+        SiteKind::UnsafeBlock if site.name.is_empty() => "undocumented unsafe block".to_string(),
+        SiteKind::UnsafeBlock => format!("undocumented unsafe block in `{}`", site.name),
+        SiteKind::Allocation
+        | SiteKind::DeprecatedUse
+        | SiteKind::ExternClosure
+        | SiteKind::ExternFn
+        | SiteKind::FfiString
+        | SiteKind::GlobalAllocator
+        | SiteKind::LeakProne
+        | SiteKind::ProcessExit
+        | SiteKind::Recursive { .. }
+        | SiteKind::ThreadUnsafe
+        | SiteKind::TrivialExpect
+        | SiteKind::Repr(_)
+        | SiteKind::Unwrap => return None,
+    })
+}
+
+/// Render the UNSAFE_TODO.md checklist
+///
+/// Items checked in `existing` stay checked. Since items are keyed by file and
+/// description, identical items (e.g. two undocumented blocks in the same fn)
+/// are matched up by count.
+pub fn render(crate_root: impl AsRef<Path>, report: &Report, existing: Option<&str>) -> String {
+    let mut checked: BTreeMap<(String, String), usize> = BTreeMap::new();
+    for (is_checked, key) in existing
+        .into_iter()
+        .flat_map(str::lines)
+        .filter_map(parse_item)
+    {
+        if is_checked {
+            *checked.entry(key).or_default() += 1;
+        }
+    }
+
+    let mut out = String::from(
+        "# Unsafe TODO
+
+Generated by [crate-report](https://github.com/richardscollin/crate-report). \
+Check items off as they are converted, regenerating this file keeps checked items checked.
+",
+    );
+
+    let mut done = 0;
+    let mut total = 0;
+    let mut sections = String::new();
+    for (filename, sites) in &report.sites {
+        let content = fs::read_to_string(crate_root.as_ref().join(filename)).unwrap_or_default();
+        let lines: Vec<&str> = content.lines().collect();
+
+        let items: Vec<(usize, String)> = sites
+            .iter()
+            .filter(|site| {
+                site.kind != SiteKind::UnsafeBlock || !has_safety_comment(&lines, site.line)
+            })
+            .filter_map(|site| Some((site.line, describe(site)?)))
+            .collect();
+        if items.is_empty() {
+            continue;
+        }
+
+        sections.push_str(&format!("\n## {filename}\n\n"));
+        for (line, description) in items {
+            let key = (filename.clone(), description);
+            let is_checked = match checked.get_mut(&key) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    true
+                }
+                _ => false,
+            };
+            done += is_checked as usize;
+            total += 1;
+            sections.push_str(&format!(
+                "- [{}] {filename}:{line} {}\n",
+                if is_checked { "x" } else { " " },
+                key.1
+            ));
+        }
+    }
+
+    out.push_str(&format!("\n{done}/{total} done\n"));
+    out.push_str(&sections);
+    out
+}
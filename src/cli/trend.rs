@@ -0,0 +1,41 @@
+use super::history::HistoryEntry;
+
+/// The most points a sparkline is drawn with, longer series are sampled down
+pub const SPARKLINE_WIDTH: usize = 60;
+
+const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Restrict history to the last `last` runs, and/or the `days` before the most
+/// recent entry
+pub fn window(history: &[HistoryEntry], last: Option<usize>, days: Option<u64>) -> &[HistoryEntry] {
+    let mut start = last.map_or(0, |last| history.len().saturating_sub(last));
+    if let (Some(days), Some(latest)) = (days, history.last()) {
+        let cutoff = latest.timestamp.saturating_sub(days * 86_400);
+        start = start.max(history.partition_point(|entry| entry.timestamp < cutoff));
+    }
+    &history[start..]
+}
+
+/// Draw values as a row of block characters scaled between their min and max
+pub fn sparkline(values: &[isize]) -> String {
+    let values: Vec<isize> = if values.len() > SPARKLINE_WIDTH {
+        (0..SPARKLINE_WIDTH)
+            .map(|i| values[i * (values.len() - 1) / (SPARKLINE_WIDTH - 1)])
+            .collect()
+    } else {
+        values.to_vec()
+    };
+
+    let min = values.iter().copied().min().unwrap_or_default();
+    let max = values.iter().copied().max().unwrap_or_default();
+    values
+        .iter()
+        .map(|&value| {
+            if max == min {
+                BARS[0]
+            } else {
+                BARS[((value - min) * (BARS.len() as isize - 1) / (max - min)) as usize]
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,122 @@
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::Path,
+};
+
+use syn::{
+    Attribute,
+    ItemFn,
+    ItemMod,
+    visit::Visit,
+};
+use walkdir::WalkDir;
+
+use super::{
+    Report,
+    Site,
+    SiteKind,
+};
+
+struct TestIdentCollector<'a> {
+    idents: &'a mut BTreeSet<String>,
+    /// nesting depth of test fns and `#[cfg(test)]` modules
+    in_test: usize,
+}
+
+/// Check for `#[test]` and test attributes of other harnesses like `#[tokio::test]`
+pub fn is_test_attr(attr: &Attribute) -> bool {
+    attr.path()
+        .segments
+        .last()
+        .map(|segment| segment.ident == "test")
+        .unwrap_or(false)
+}
+
+pub fn is_cfg_test_attr(attr: &Attribute) -> bool {
+    attr.path().is_ident("cfg")
+        && attr
+            .meta
+            .require_list()
+            .map(|list| list.tokens.to_string() == "test")
+            .unwrap_or(false)
+}
+
+impl<'ast> Visit<'ast> for TestIdentCollector<'_> {
+    fn visit_item_fn(&mut self, i: &'ast ItemFn) {
+        let is_test = i.attrs.iter().any(is_test_attr);
+        self.in_test += is_test as usize;
+        syn::visit::visit_item_fn(self, i);
+        self.in_test -= is_test as usize;
+    }
+
+    fn visit_item_mod(&mut self, i: &'ast ItemMod) {
+        let is_test = i.attrs.iter().any(is_cfg_test_attr);
+        self.in_test += is_test as usize;
+        syn::visit::visit_item_mod(self, i);
+        self.in_test -= is_test as usize;
+    }
+
+    fn visit_ident(&mut self, i: &'ast proc_macro2::Ident) {
+        if self.in_test > 0 {
+            self.idents.insert(i.to_string());
+        }
+    }
+}
+
+/// Collect every identifier used in test code: `#[test]` fns, `#[cfg(test)]`
+/// modules and every file under a `tests/` directory
+fn test_identifiers(root: &Path) -> BTreeSet<String> {
+    let mut idents = BTreeSet::new();
+
+    for entry in WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|e| {
+            e.file_name()
+                .to_str()
+                .map(|s| s != "target")
+                .unwrap_or(true)
+        })
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().extension().map(|ext| ext == "rs").unwrap_or(false))
+    {
+        let path = entry.path();
+        let Ok(content) = fs::read_to_string(path) else {
+            continue;
+        };
+        let Ok(syntax) = syn::parse_file(&content) else {
+            continue;
+        };
+
+        let in_tests_dir = path
+            .strip_prefix(root)
+            .unwrap_or(path)
+            .components()
+            .any(|c| c.as_os_str() == "tests");
+
+        let mut visitor = TestIdentCollector {
+            idents: &mut idents,
+            in_test: in_tests_dir as usize,
+        };
+        visitor.visit_file(&syntax);
+    }
+
+    idents
+}
+
+/// Find the unsafe functions whose name never appears in any test code
+///
+/// This is approximate: a function is considered tested if any test mentions
+/// its name, whether or not that mention actually resolves to it, and
+/// functions only exercised indirectly through other functions are reported.
+pub fn find_untested_unsafe_fns(root: impl AsRef<Path>, report: &Report) -> Vec<(String, Site)> {
+    let idents = test_identifiers(root.as_ref());
+
+    report
+        .sites
+        .iter()
+        .flat_map(|(filename, sites)| sites.iter().map(move |site| (filename, site)))
+        .filter(|(_, site)| site.kind == SiteKind::UnsafeFn && !idents.contains(&site.name))
+        .map(|(filename, site)| (filename.clone(), site.clone()))
+        .collect()
+}
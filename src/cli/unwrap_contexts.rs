@@ -0,0 +1,128 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::Path,
+};
+
+use syn::{
+    ExprMethodCall,
+    ItemFn,
+    ItemMod,
+    visit::Visit,
+};
+
+use super::{
+    Report,
+    untested::{
+        is_cfg_test_attr,
+        is_test_attr,
+    },
+};
+
+/// A file's unwraps by the kind of code they're in
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Contexts {
+    pub library: usize,
+    /// in a `main` fn, outside of examples and build scripts
+    pub main: usize,
+    /// in `#[test]` fns, `#[cfg(test)]` modules and `tests/`
+    pub tests: usize,
+    pub examples: usize,
+    pub build_scripts: usize,
+}
+
+impl Contexts {
+    pub fn total(&self) -> usize {
+        self.library + self.main + self.tests + self.examples + self.build_scripts
+    }
+}
+
+/// Where a file's unwraps go when its path alone decides it
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum FileContext {
+    Source,
+    Test,
+    Example,
+    BuildScript,
+}
+
+impl FileContext {
+    fn of(filename: &str) -> Self {
+        let mut components = filename.split('/');
+        if filename == "build.rs" || filename.ends_with("/build.rs") {
+            FileContext::BuildScript
+        } else if components.clone().any(|component| component == "examples") {
+            FileContext::Example
+        } else if components.any(|component| component == "tests") {
+            FileContext::Test
+        } else {
+            FileContext::Source
+        }
+    }
+}
+
+struct ContextCollector {
+    file: FileContext,
+    /// nesting depth of test fns and `#[cfg(test)]` modules
+    in_test: usize,
+    /// nesting depth of `main` fns
+    in_main: usize,
+    contexts: Contexts,
+}
+
+impl<'ast> Visit<'ast> for ContextCollector {
+    fn visit_item_fn(&mut self, i: &'ast ItemFn) {
+        let is_test = i.attrs.iter().any(is_test_attr);
+        let is_main = i.sig.ident == "main";
+        self.in_test += is_test as usize;
+        self.in_main += is_main as usize;
+        syn::visit::visit_item_fn(self, i);
+        self.in_test -= is_test as usize;
+        self.in_main -= is_main as usize;
+    }
+
+    fn visit_item_mod(&mut self, i: &'ast ItemMod) {
+        let is_test = i.attrs.iter().any(is_cfg_test_attr);
+        self.in_test += is_test as usize;
+        syn::visit::visit_item_mod(self, i);
+        self.in_test -= is_test as usize;
+    }
+
+    fn visit_expr_method_call(&mut self, i: &'ast ExprMethodCall) {
+        if i.method == "unwrap" {
+            let contexts = &mut self.contexts;
+            *match self.file {
+                FileContext::BuildScript => &mut contexts.build_scripts,
+                FileContext::Example => &mut contexts.examples,
+                FileContext::Test => &mut contexts.tests,
+                FileContext::Source if self.in_test > 0 => &mut contexts.tests,
+                FileContext::Source if self.in_main > 0 => &mut contexts.main,
+                FileContext::Source => &mut contexts.library,
+            } += 1;
+        }
+        syn::visit::visit_expr_method_call(self, i);
+    }
+}
+
+/// Split the unwraps of each file of the report by context: build scripts and
+/// examples by their path, tests by their path or attributes, then `main` fns
+/// and everything else as library code
+pub fn census(root: impl AsRef<Path>, report: &Report) -> BTreeMap<String, Contexts> {
+    report
+        .files
+        .iter()
+        .filter(|(_, stats)| stats.unwraps > 0)
+        .filter_map(|(filename, _)| {
+            let content = fs::read_to_string(root.as_ref().join(filename)).ok()?;
+            let syntax = syn::parse_file(&content).ok()?;
+            let mut collector = ContextCollector {
+                file: FileContext::of(filename),
+                in_test: 0,
+                in_main: 0,
+                contexts: Contexts::default(),
+            };
+            collector.visit_file(&syntax);
+            Some((filename.clone(), collector.contexts))
+        })
+        .collect()
+}
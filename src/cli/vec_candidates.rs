@@ -0,0 +1,316 @@
+use std::collections::BTreeMap;
+
+use syn::{
+    Expr,
+    ExprAssign,
+    ExprCall,
+    ExprMethodCall,
+    ImplItemFn,
+    ItemFn,
+    Local,
+    Pat,
+    Stmt,
+    spanned::Spanned,
+    visit::Visit,
+};
+
+use super::{
+    Confidence,
+    Suggestion,
+};
+
+#[derive(Clone, Default, Debug)]
+pub struct FileStats {
+    pub filename: String,
+    pub stats: CodeStats,
+}
+
+#[derive(Clone, Debug)]
+pub struct VecCandidate {
+    pub fn_name: String,
+    pub line_number: usize,
+    /// the pointer the buffer is held in, like `p` or `self.items`
+    pub buffer: String,
+    pub reason: String,
+    pub confidence: Confidence,
+}
+
+impl Suggestion for VecCandidate {
+    fn subject(&self) -> String {
+        self.fn_name.clone()
+    }
+
+    fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    fn confidence(&self) -> Confidence {
+        self.confidence
+    }
+
+    fn detail(&self) -> Option<String> {
+        Some(format!("`{}` {}", self.buffer, self.reason))
+    }
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct CodeStats {
+    pub candidates: Vec<VecCandidate>,
+}
+
+/// Where a pointer is allocated, reallocated and indexed
+#[derive(Default)]
+struct Buffer {
+    text: String,
+    fn_name: String,
+    allocated: Vec<usize>,
+    reallocated: Vec<usize>,
+    indexed: Vec<usize>,
+}
+
+pub struct CodeAnalyzer {
+    /// the innermost fn, local pointers are told apart by it
+    fn_name: Option<String>,
+    /// by the pointer's source text, prefixed by the fn for local variables
+    buffers: BTreeMap<String, Buffer>,
+}
+
+/// An expression through casts, `.cast()`s and `unsafe` blocks, like the
+/// `libc::malloc(n)` of `unsafe { libc::malloc(n) } as *mut u8`
+fn strip(expr: &Expr) -> &Expr {
+    match expr {
+        Expr::Cast(cast) => strip(&cast.expr),
+        Expr::Paren(paren) => strip(&paren.expr),
+        Expr::MethodCall(call) if call.method == "cast" || call.method == "cast_mut" => {
+            strip(&call.receiver)
+        }
+        Expr::Unsafe(block) => match block.block.stmts.as_slice() {
+            [Stmt::Expr(expr, None)] => strip(expr),
+            _ => expr,
+        },
+        _ => expr,
+    }
+}
+
+/// The C allocator an expression calls, `malloc`, `calloc` or `realloc`
+fn allocator(expr: &Expr) -> Option<&'static str> {
+    match strip(expr) {
+        Expr::Call(call) => allocator_call(call),
+        _ => None,
+    }
+}
+
+fn allocator_call(call: &ExprCall) -> Option<&'static str> {
+    let Expr::Path(path) = &*call.func else {
+        return None;
+    };
+    let segments: Vec<String> = path
+        .path
+        .segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect();
+    let name = match segments.as_slice() {
+        [name] => name,
+        [libc, name] if libc == "libc" => name,
+        _ => return None,
+    };
+    ["malloc", "calloc", "realloc"]
+        .into_iter()
+        .find(|allocator| name == allocator)
+}
+
+/// Lines as `line 3` or `lines 3, 7`
+fn format_lines(lines: &[usize]) -> String {
+    let list: Vec<String> = lines.iter().map(usize::to_string).collect();
+    match list.len() {
+        1 => format!("line {}", list[0]),
+        _ => format!("lines {}", list.join(", ")),
+    }
+}
+
+impl CodeAnalyzer {
+    fn buffer(&mut self, expr: &Expr) -> Option<&mut Buffer> {
+        let expr = strip(expr);
+        let is_local = matches!(expr, Expr::Path(path) if path.path.get_ident().is_some());
+        self.named_buffer(expr.span().source_text()?, is_local)
+    }
+
+    fn named_buffer(&mut self, text: String, is_local: bool) -> Option<&mut Buffer> {
+        let fn_name = self.fn_name.clone()?;
+        let key = if is_local {
+            format!("{fn_name}::{text}")
+        } else {
+            text.clone()
+        };
+        Some(self.buffers.entry(key).or_insert_with(|| Buffer {
+            text,
+            fn_name,
+            ..Buffer::default()
+        }))
+    }
+
+    fn visit_fn_scope(&mut self, name: String, visit: impl FnOnce(&mut Self)) {
+        let outer = self.fn_name.replace(name);
+        visit(self);
+        self.fn_name = outer;
+    }
+}
+
+impl<'ast> Visit<'ast> for CodeAnalyzer {
+    fn visit_item_fn(&mut self, i: &'ast ItemFn) {
+        self.visit_fn_scope(i.sig.ident.to_string(), |this| {
+            syn::visit::visit_item_fn(this, i)
+        });
+    }
+
+    fn visit_impl_item_fn(&mut self, i: &'ast ImplItemFn) {
+        self.visit_fn_scope(i.sig.ident.to_string(), |this| {
+            syn::visit::visit_impl_item_fn(this, i)
+        });
+    }
+
+    /// `let p = libc::malloc(n)`
+    fn visit_local(&mut self, i: &'ast Local) {
+        let pat = match &i.pat {
+            Pat::Type(pat) => &*pat.pat,
+            pat => pat,
+        };
+        if let Pat::Ident(pat) = pat
+            && let Some(init) = &i.init
+            && allocator(&init.expr).is_some()
+        {
+            let line = init.expr.span().start().line;
+            if let Some(buffer) = self.named_buffer(pat.ident.to_string(), true) {
+                buffer.allocated.push(line);
+            }
+        }
+        syn::visit::visit_local(self, i);
+    }
+
+    /// `self.items = libc::malloc(n) as *mut Item`
+    fn visit_expr_assign(&mut self, i: &'ast ExprAssign) {
+        if allocator(&i.right).is_some() {
+            let line = i.right.span().start().line;
+            if let Some(buffer) = self.buffer(&i.left) {
+                buffer.allocated.push(line);
+            }
+        }
+        syn::visit::visit_expr_assign(self, i);
+    }
+
+    /// `libc::realloc(p, n)` and `slice::from_raw_parts(p, n)`
+    fn visit_expr_call(&mut self, i: &'ast ExprCall) {
+        let line = i.func.span().start().line;
+        if let Some(pointer) = i.args.first() {
+            if allocator_call(i) == Some("realloc") {
+                if let Some(buffer) = self.buffer(pointer) {
+                    buffer.reallocated.push(line);
+                }
+            } else if let Expr::Path(path) = &*i.func
+                && path.path.segments.last().is_some_and(|segment| {
+                    segment.ident == "from_raw_parts" || segment.ident == "from_raw_parts_mut"
+                })
+                && let Some(buffer) = self.buffer(pointer)
+            {
+                buffer.indexed.push(line);
+            }
+        }
+        syn::visit::visit_expr_call(self, i);
+    }
+
+    /// `*p.add(i)` and the like
+    fn visit_expr_method_call(&mut self, i: &'ast ExprMethodCall) {
+        if matches!(
+            i.method.to_string().as_str(),
+            "add" | "offset" | "wrapping_add" | "wrapping_offset"
+        ) {
+            let line = i.method.span().start().line;
+            if let Some(buffer) = self.buffer(&i.receiver) {
+                buffer.indexed.push(line);
+            }
+        }
+        syn::visit::visit_expr_method_call(self, i);
+    }
+}
+
+fn analyze_file(syntax: &syn::File) -> CodeStats {
+    let mut visitor = CodeAnalyzer {
+        fn_name: None,
+        buffers: BTreeMap::new(),
+    };
+    visitor.visit_file(syntax);
+
+    let mut stats = CodeStats::default();
+    for buffer in visitor.buffers.into_values() {
+        let growable = !buffer.reallocated.is_empty()
+            || (!buffer.allocated.is_empty() && !buffer.indexed.is_empty());
+        if !growable {
+            continue;
+        }
+        let mut uses = Vec::new();
+        if !buffer.allocated.is_empty() {
+            uses.push(format!("allocated at {}", format_lines(&buffer.allocated)));
+        }
+        if !buffer.reallocated.is_empty() {
+            uses.push(format!(
+                "reallocated at {}",
+                format_lines(&buffer.reallocated)
+            ));
+        }
+        if !buffer.indexed.is_empty() {
+            uses.push(format!("indexed at {}", format_lines(&buffer.indexed)));
+        }
+        // only a realloc or only indexing could be something else, like a
+        // buffer handed back to C or a fixed size array
+        let confidence = if !buffer.reallocated.is_empty() && !buffer.indexed.is_empty() {
+            Confidence::High
+        } else {
+            Confidence::Medium
+        };
+        let line_number = buffer
+            .allocated
+            .first()
+            .or(buffer.reallocated.first())
+            .copied()
+            .unwrap_or_default();
+        stats.candidates.push(VecCandidate {
+            fn_name: buffer.fn_name,
+            line_number,
+            reason: uses.join(", "),
+            buffer: buffer.text,
+            confidence,
+        });
+    }
+    stats
+        .candidates
+        .sort_by_key(|candidate| candidate.line_number);
+
+    stats
+}
+
+/// Find buffers from the C allocator used as growable arrays, which could be
+/// a `Vec` instead
+/// The heuristic is a pointer which is passed to `realloc`, or which is
+/// allocated with `malloc`, `calloc` or `realloc` and then indexed with `add`,
+/// `offset` or `from_raw_parts`, fields are followed across the fns of a file,
+/// it's high confidence for a buffer both reallocated and indexed
+pub fn find_candidates(
+    files: &[(String, syn::File)],
+    min_confidence: Confidence,
+) -> Vec<FileStats> {
+    files
+        .iter()
+        .map(|(filename, syntax)| {
+            let mut stats = analyze_file(syntax);
+            stats
+                .candidates
+                .retain(|candidate| candidate.confidence >= min_confidence);
+            FileStats {
+                filename: filename.clone(),
+                stats,
+            }
+        })
+        .filter(|report| !report.stats.candidates.is_empty())
+        .collect()
+}
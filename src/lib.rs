@@ -0,0 +1,22 @@
+mod analysis;
+#[cfg(feature = "cli")]
+mod cli;
+pub mod plugin;
+
+pub use analysis::{
+    CodeStats,
+    analyze_source,
+};
+#[cfg(feature = "cli")]
+pub use cli::{
+    Change,
+    Diff,
+    DiffReport,
+    EXIT_ERROR,
+    EXIT_VIOLATION,
+    Report,
+    analyze_crate,
+    analyze_crate_with,
+    load_report,
+    run,
+};
@@ -0,0 +1,94 @@
+use std::{
+    collections::BTreeMap,
+    path::Path,
+    sync::RwLock,
+};
+
+/// The file a [`MetricVisitor`] is counting in
+#[derive(Clone, Copy, Debug)]
+pub struct FileInfo<'a> {
+    /// relative to the crate root, `None` for source without a file (e.g. at
+    /// a git revision)
+    pub path: Option<&'a Path>,
+    pub source: &'a str,
+}
+
+/// Counts organization-specific metrics in each parsed file
+///
+/// The counts are reported like the built-in metrics: as CSV and JSON
+/// columns, totals, table columns and in diffs against baselines, which
+/// decrease is good is configured under `[directions]` like any other metric.
+///
+/// ```no_run
+/// use std::collections::BTreeMap;
+///
+/// use crate_report::plugin::{FileInfo, MetricVisitor};
+///
+/// struct Transmutes;
+///
+/// impl MetricVisitor for Transmutes {
+///     fn metrics(&self) -> Vec<String> {
+///         vec!["transmutes".to_string()]
+///     }
+///
+///     fn count(&self, _file: &syn::File, info: FileInfo<'_>) -> BTreeMap<String, isize> {
+///         let count = info.source.matches("transmute(").count() as isize;
+///         BTreeMap::from([("transmutes".to_string(), count)])
+///     }
+/// }
+///
+/// fn main() {
+///     crate_report::plugin::register(Transmutes);
+///     crate_report::run();
+/// }
+/// ```
+pub trait MetricVisitor: Send + Sync {
+    /// The names of the counts, used as column names so they should be
+    /// snake_case and not clash with the built-in metrics
+    fn metrics(&self) -> Vec<String>;
+
+    /// Count the metrics in a file, missing ones are zero
+    fn count(&self, file: &syn::File, info: FileInfo<'_>) -> BTreeMap<String, isize>;
+}
+
+static VISITORS: RwLock<Vec<Box<dyn MetricVisitor>>> = RwLock::new(Vec::new());
+
+/// Add a visitor whose metrics are counted in every analyzed file, call
+/// before [`crate::run`]
+pub fn register(visitor: impl MetricVisitor + 'static) {
+    VISITORS.write().unwrap().push(Box::new(visitor));
+}
+
+/// The names of every registered metric, in registration order
+#[cfg(feature = "cli")]
+pub(crate) fn metrics() -> Vec<String> {
+    VISITORS
+        .read()
+        .unwrap()
+        .iter()
+        .flat_map(|visitor| visitor.metrics())
+        .collect()
+}
+
+#[cfg(feature = "cli")]
+pub(crate) fn is_registered(metric: &str) -> bool {
+    metrics().iter().any(|name| name == metric)
+}
+
+/// Run every registered visitor over a file
+pub(crate) fn count(file: &syn::File, info: FileInfo<'_>) -> BTreeMap<String, isize> {
+    let visitors = VISITORS.read().unwrap();
+    let mut counts: BTreeMap<String, isize> = visitors
+        .iter()
+        .flat_map(|visitor| visitor.metrics())
+        .map(|metric| (metric, 0))
+        .collect();
+    for visitor in visitors.iter() {
+        for (metric, count) in visitor.count(file, info) {
+            if let Some(total) = counts.get_mut(&metric) {
+                *total += count;
+            }
+        }
+    }
+    counts
+}
@@ -1,148 +0,0 @@
-use std::{
-    fs,
-    path::Path,
-};
-
-use syn::{
-    ItemFn,
-    visit::Visit,
-};
-use walkdir::WalkDir;
-
-#[derive(Clone, Default, Debug)]
-pub struct FileStats {
-    pub filename: String,
-    pub stats: CodeStats,
-}
-
-#[derive(Clone, Default, Debug)]
-pub struct Candidate {
-    pub fn_name: String,
-    pub line_number: usize,
-}
-
-#[derive(Clone, Default, Debug)]
-pub struct CodeStats {
-    pub candidates: Vec<Candidate>,
-}
-
-pub struct CodeAnalyzer<'a> {
-    stats: &'a mut CodeStats,
-}
-
-/// Check if a type is a pointer type (raw pointer or NonNull<T>)
-fn has_pointer_type(ty: &syn::Type) -> bool {
-    match ty {
-        // Raw pointers: *const T, *mut T
-        syn::Type::Ptr(_) => true,
-        // Path types like NonNull<T>
-        syn::Type::Path(type_path) => {
-            // Check if this is NonNull<T>
-            if let Some(segment) = type_path.path.segments.last() {
-                segment.ident == "NonNull"
-            } else {
-                false
-            }
-        }
-        _ => false,
-    }
-}
-
-/// Check if function has a Safety comment in its attributes
-fn has_safety_comment(attrs: &[syn::Attribute]) -> bool {
-    attrs.iter().any(|attr| {
-        // Check for doc comments (outer attributes that start with #[doc = "..."])
-        if attr.path().is_ident("doc")
-            && let syn::Meta::NameValue(meta_name_value) = &attr.meta
-            && let syn::Expr::Lit(syn::ExprLit {
-                lit: syn::Lit::Str(lit_str),
-                ..
-            }) = &meta_name_value.value
-        {
-            let comment = lit_str.value();
-            // Check if comment contains "#Safety" (case insensitive)
-            return comment.to_lowercase().contains("# safety");
-        }
-        false
-    })
-}
-
-impl<'a, 'ast> Visit<'ast> for CodeAnalyzer<'a> {
-    fn visit_item_fn(&mut self, i: &'ast ItemFn) {
-        use syn::spanned::Spanned;
-
-        if i.sig.unsafety.is_some() {
-            // if function is unsafe and it has no raw pointer arguments add it to the list
-            let has_raw_pointer = i.sig.inputs.iter().any(|arg| match arg {
-                syn::FnArg::Typed(pat_type) => has_pointer_type(&pat_type.ty),
-                _ => false,
-            });
-
-            // Exclude functions with Safety comments
-            let has_safety_doc = has_safety_comment(&i.attrs);
-
-            if !has_raw_pointer && !has_safety_doc {
-                let candidate = Candidate {
-                    fn_name: i.sig.ident.to_string(),
-                    line_number: i.span().start().line,
-                };
-                self.stats.candidates.push(candidate)
-            }
-        }
-        syn::visit::visit_item_fn(self, i);
-    }
-}
-
-fn analyze_file(path: &Path) -> Option<FileStats> {
-    let content = fs::read_to_string(path).ok()?;
-    let syntax = syn::parse_file(&content).ok()?;
-
-    let mut stats = CodeStats::default();
-    let mut visitor = CodeAnalyzer { stats: &mut stats };
-    visitor.visit_file(&syntax);
-
-    Some(FileStats {
-        filename: path.display().to_string(),
-        stats,
-    })
-}
-
-/// find good candidates for functions to convert to being safe
-/// this is very simplistic, the heuristic is if the function
-/// has no raw pointers as parameters, it may be a good candidate
-///
-/// there may be other reasons why one of these functions can't be converted
-pub fn find_candidates(root: impl AsRef<Path>) -> Vec<FileStats> {
-    let root = root.as_ref();
-    let mut file_reports = Vec::new();
-
-    for entry in WalkDir::new(root)
-        .into_iter()
-        .filter_entry(|e| {
-            e.file_name()
-                .to_str()
-                .map(|s| s != "target")
-                .unwrap_or(true)
-        })
-        .filter_map(|e| e.ok())
-        .filter(|e| e.path().extension().map(|ext| ext == "rs").unwrap_or(false))
-    {
-        let path = entry.path();
-        if let Some(file_stats) = analyze_file(path) {
-            file_reports.push(file_stats);
-        }
-    }
-
-    // Strip common root prefix and find max filename length for alignment
-    let mut max_filename_len = 0;
-    for file_report in &mut file_reports {
-        if let Ok(relative_path) = Path::new(&file_report.filename).strip_prefix(root) {
-            file_report.filename = relative_path.display().to_string();
-        }
-        max_filename_len = max_filename_len.max(file_report.filename.len());
-    }
-
-    file_reports.sort_by(|a, b| a.filename.cmp(&b.filename));
-    file_reports.retain(|r| !r.stats.candidates.is_empty());
-    file_reports
-}